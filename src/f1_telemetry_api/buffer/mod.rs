@@ -0,0 +1,262 @@
+use crate::f1_telemetry_api::events::Event;
+use crate::f1_telemetry_client::packets::header::PacketHeader;
+use arc_swap::ArcSwap;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+// Events per chunk. Evicting whole chunks keeps the buffer cheap to
+// trim, at the cost of eviction granularity.
+const CHUNK_SIZE: usize = 256;
+
+/// How much telemetry the buffer keeps. Whole chunks are evicted, so the
+/// buffer can hold up to one chunk more than asked for.
+#[derive(Clone, Copy, Debug)]
+pub struct Retention {
+    pub max_events: usize,
+    // Wall-clock age after which events are dropped, if limited
+    pub max_age: Option<Duration>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BufferedEvent {
+    pub frame_identifier: u32,
+    pub session_time: f32,
+    pub event: Event,
+}
+
+// Slots for the events of a single session in session time order. Slots
+// are only ever set in order, once each, so the chunk being filled can be
+// shared with readers, who only look at the slots set when they took their
+// snapshot.
+struct Chunk {
+    session_uid: u64,
+    slots: Box<[OnceLock<BufferedEvent>]>,
+}
+
+impl Chunk {
+    fn new(session_uid: u64, events: impl IntoIterator<Item = BufferedEvent>) -> Self {
+        let slots: Box<[_]> = (0..CHUNK_SIZE).map(|_| OnceLock::new()).collect();
+        for (slot, event) in slots.iter().zip(events) {
+            let _ = slot.set(event);
+        }
+        Self { session_uid, slots }
+    }
+}
+
+// The first `len` events of a chunk
+#[derive(Clone)]
+struct ChunkView {
+    chunk: Arc<Chunk>,
+    len: usize,
+    last_received: Instant,
+}
+
+impl ChunkView {
+    fn new(session_uid: u64) -> Self {
+        Self {
+            chunk: Arc::new(Chunk::new(session_uid, [])),
+            len: 0,
+            last_received: Instant::now(),
+        }
+    }
+
+    fn session_uid(&self) -> u64 {
+        self.chunk.session_uid
+    }
+
+    fn events(&self) -> impl Iterator<Item = &BufferedEvent> {
+        self.chunk.slots[..self.len].iter().map(slot_event)
+    }
+
+    fn push(&mut self, event: BufferedEvent) {
+        let _ = self.chunk.slots[self.len].set(event);
+        self.len += 1;
+    }
+
+    // Drop the events `keep` rejects, returning how many were dropped. The
+    // events kept are copied to a new chunk, leaving snapshots as they were.
+    fn retain(&mut self, keep: impl Fn(&BufferedEvent) -> bool) -> usize {
+        let kept: Vec<_> = self.events().filter(|e| keep(e)).cloned().collect();
+        let removed = self.len - kept.len();
+        if removed > 0 {
+            self.len = kept.len();
+            self.chunk = Arc::new(Chunk::new(self.session_uid(), kept));
+        }
+        removed
+    }
+}
+
+fn slot_event(slot: &OnceLock<BufferedEvent>) -> &BufferedEvent {
+    slot.get()
+        .expect("events are set before they are published")
+}
+
+/// Immutable view of the buffer, oldest events first
+#[derive(Default)]
+pub struct Snapshot {
+    sealed: Arc<VecDeque<ChunkView>>,
+    tail: Option<ChunkView>,
+}
+
+impl Snapshot {
+    fn chunks(&self) -> impl DoubleEndedIterator<Item = &ChunkView> {
+        self.sealed.iter().chain(&self.tail)
+    }
+
+    /// Session the most recent event belongs to
+    pub fn latest_session(&self) -> Option<u64> {
+        self.chunks().next_back().map(ChunkView::session_uid)
+    }
+
+    /// Events of a session whose session time is within `[from, to]`, in order
+    pub fn range(
+        &self,
+        session_uid: u64,
+        from: Option<f32>,
+        to: Option<f32>,
+    ) -> impl Iterator<Item = &BufferedEvent> {
+        let from = from.unwrap_or(f32::MIN);
+        let to = to.unwrap_or(f32::MAX);
+
+        self.chunks()
+            .filter(move |c| c.session_uid() == session_uid)
+            .flat_map(move |c| {
+                let slots = &c.chunk.slots[..c.len];
+                let start = slots.partition_point(|e| slot_event(e).session_time < from);
+                let end = slots.partition_point(|e| slot_event(e).session_time <= to);
+                slots[start..end.max(start)].iter().map(slot_event)
+            })
+    }
+}
+
+struct Writer {
+    // Shared with the published snapshot, and only copied when a chunk is
+    // sealed, evicted or rewritten
+    sealed: Arc<VecDeque<ChunkView>>,
+    tail: Option<ChunkView>,
+    len: usize,
+}
+
+/// Time-indexed ring buffer of telemetry events.
+///
+/// A single writer appends under a mutex and publishes an immutable
+/// snapshot, so readers never wait on the writer or on each other. Events
+/// are appended in place, so publishing doesn't copy any of them.
+pub struct EventBuffer {
+    retention: Retention,
+    writer: Mutex<Writer>,
+    published: ArcSwap<Snapshot>,
+}
+
+impl EventBuffer {
+    pub fn new(retention: Retention) -> Self {
+        Self {
+            retention,
+            writer: Mutex::new(Writer {
+                sealed: Arc::default(),
+                tail: None,
+                len: 0,
+            }),
+            published: ArcSwap::from_pointee(Snapshot::default()),
+        }
+    }
+
+    pub fn snapshot(&self) -> Arc<Snapshot> {
+        self.published.load_full()
+    }
+
+    /// Append the events decoded from one packet
+    pub fn extend(&self, header: &PacketHeader, events: impl IntoIterator<Item = Event>) {
+        let mut writer = self.writer.lock().unwrap();
+        let now = Instant::now();
+
+        for event in events {
+            let tail = match writer.tail.take() {
+                Some(tail) if tail.session_uid() == header.session_uid && tail.len < CHUNK_SIZE => {
+                    tail
+                }
+                previous => {
+                    if let Some(previous) = previous {
+                        Arc::make_mut(&mut writer.sealed).push_back(previous);
+                    }
+                    ChunkView::new(header.session_uid)
+                }
+            };
+
+            let tail = writer.tail.insert(tail);
+            tail.push(BufferedEvent {
+                frame_identifier: header.frame_identifier,
+                session_time: header.session_time,
+                event,
+            });
+            tail.last_received = now;
+            writer.len += 1;
+        }
+
+        self.evict(&mut writer, now);
+        self.publish(&writer);
+    }
+
+    /// Drop a session's events after the frame a flashback rewound to
+    pub fn discard_after(&self, session_uid: u64, frame_identifier: u32) {
+        let mut writer = self.writer.lock().unwrap();
+        let keep = |e: &BufferedEvent| e.frame_identifier <= frame_identifier;
+
+        let mut removed = 0;
+        if let Some(tail) = writer
+            .tail
+            .as_mut()
+            .filter(|t| t.session_uid() == session_uid)
+        {
+            removed += tail.retain(keep);
+        }
+
+        // Rewound frames are at the end of the session, so stop at the
+        // first chunk that is left untouched
+        let touched = writer
+            .sealed
+            .iter()
+            .rev()
+            .filter(|c| c.session_uid() == session_uid)
+            .take_while(|c| !c.events().all(keep))
+            .count();
+        if touched > 0 {
+            let sealed = Arc::make_mut(&mut writer.sealed);
+            for chunk in sealed
+                .iter_mut()
+                .rev()
+                .filter(|c| c.session_uid() == session_uid)
+                .take(touched)
+            {
+                removed += chunk.retain(keep);
+            }
+            sealed.retain(|c| c.len > 0);
+        }
+
+        writer.len -= removed;
+        self.publish(&writer);
+    }
+
+    fn evict(&self, writer: &mut Writer, now: Instant) {
+        while let Some(oldest) = writer.sealed.front() {
+            let too_many = writer.len - oldest.len >= self.retention.max_events;
+            let too_old = self
+                .retention
+                .max_age
+                .is_some_and(|age| now.duration_since(oldest.last_received) > age);
+            if !too_many && !too_old {
+                break;
+            }
+            writer.len -= oldest.len;
+            Arc::make_mut(&mut writer.sealed).pop_front();
+        }
+    }
+
+    fn publish(&self, writer: &Writer) {
+        self.published.store(Arc::new(Snapshot {
+            sealed: writer.sealed.clone(),
+            tail: writer.tail.clone(),
+        }));
+    }
+}
@@ -0,0 +1,97 @@
+// OpenAPI schema for the packet enums: each one is a string enum of its API
+// names, with "unknown" standing in for any code missing from the spec.
+
+use crate::f1_telemetry_client::packets::enums::*;
+use poem_openapi::registry::{MetaSchema, MetaSchemaRef, Registry};
+use poem_openapi::types::{
+    ParseError, ParseFromJSON, ParseFromParameter, ParseResult, ToJSON, Type,
+};
+use serde_json::Value;
+use std::borrow::Cow;
+
+macro_rules! api_enum {
+    ($($name:ident),* $(,)?) => {$(
+        impl Type for $name {
+            const IS_REQUIRED: bool = true;
+
+            type RawValueType = Self;
+
+            type RawElementValueType = Self;
+
+            fn name() -> Cow<'static, str> {
+                stringify!($name).into()
+            }
+
+            fn schema_ref() -> MetaSchemaRef {
+                MetaSchemaRef::Reference(Self::name().into_owned())
+            }
+
+            fn register(registry: &mut Registry) {
+                registry.create_schema::<Self, _>(Self::name().into_owned(), |_| MetaSchema {
+                    enum_items: $name::NAMES.iter().map(|&n| Value::from(n)).collect(),
+                    ..MetaSchema::new("string")
+                });
+            }
+
+            fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+                Some(self)
+            }
+
+            fn raw_element_iter<'a>(
+                &'a self,
+            ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+                Box::new(self.as_raw_value().into_iter())
+            }
+        }
+
+        impl ToJSON for $name {
+            fn to_json(&self) -> Option<Value> {
+                Some(Value::from(self.as_str()))
+            }
+        }
+
+        impl ParseFromJSON for $name {
+            fn parse_from_json(value: Option<Value>) -> ParseResult<Self> {
+                match value.unwrap_or_default() {
+                    Value::String(name) => Self::parse_from_parameter(&name),
+                    value => Err(ParseError::expected_type(value)),
+                }
+            }
+        }
+
+        impl ParseFromParameter for $name {
+            fn parse_from_parameter(value: &str) -> ParseResult<Self> {
+                $name::from_name(value).ok_or_else(|| ParseError::custom("Expect a valid enumeration value."))
+            }
+        }
+    )*};
+}
+
+api_enum!(
+    Track,
+    SessionType,
+    GameMode,
+    Ruleset,
+    SurfaceType,
+    PenaltyType,
+    RetirementReason,
+    DrsDisabledReason,
+    InfringementType,
+    Weather,
+    TemperatureChange,
+    Formula,
+    SafetyCarStatus,
+    SafetyCarEventType,
+    FiaFlag,
+    DriverStatus,
+    PitStatus,
+    ResultStatus,
+    ActualTyreCompound,
+    VisualTyreCompound,
+    ErsDeployMode,
+    FuelMix,
+    TractionControl,
+    Platform,
+    ReadyStatus,
+    SessionLength,
+);
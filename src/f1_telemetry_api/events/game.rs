@@ -0,0 +1,259 @@
+use super::{Event, EventMetadata, EventType};
+use crate::f1_telemetry_client::packets::enums::{
+    DrsDisabledReason, InfringementType, PenaltyType, RetirementReason, SafetyCarEventType,
+    SafetyCarStatus,
+};
+use crate::f1_telemetry_client::packets::event::{EventDataDetails, PacketEventData};
+use crate::f1_telemetry_client::packets::header::PacketHeader;
+use poem_openapi::{Enum, Object};
+
+// Events decoded from the Event packet. Unlike telemetry they concern the
+// whole session, so they are produced whichever cars are selected.
+
+#[derive(Clone, Enum, Debug, PartialEq)]
+#[oai(rename_all = "snake_case")]
+pub enum SessionEventKind {
+    SessionStarted,
+    SessionEnded,
+    LightsOut,
+    ChequeredFlag,
+    RedFlag,
+    DrsEnabled,
+    DrsDisabled,
+}
+
+/// Change in the state of the session that isn't tied to a car
+#[derive(Object, Clone, Debug)]
+pub struct SessionEvent {
+    #[oai(rename = "type")]
+    pub event_type: EventType,
+    pub kind: SessionEventKind,
+    pub drs_disabled_reason: Option<DrsDisabledReason>,
+    pub timestamp: f32,
+}
+
+#[derive(Object, Clone, Debug)]
+pub struct StartLightsEvent {
+    #[oai(rename = "type")]
+    pub event_type: EventType,
+    pub num_lights: u8,
+    pub timestamp: f32,
+}
+
+#[derive(Object, Clone, Debug)]
+pub struct FastestLapEvent {
+    #[oai(rename = "type")]
+    pub event_type: EventType,
+    pub lap_time: f32, // Seconds
+    #[oai(flatten)]
+    pub metadata: EventMetadata,
+}
+
+#[derive(Object, Clone, Debug)]
+pub struct RetirementEvent {
+    #[oai(rename = "type")]
+    pub event_type: EventType,
+    // Only sent by later games
+    pub reason: Option<RetirementReason>,
+    #[oai(flatten)]
+    pub metadata: EventMetadata,
+}
+
+#[derive(Object, Clone, Debug)]
+pub struct TeamMateInPitsEvent {
+    #[oai(rename = "type")]
+    pub event_type: EventType,
+    #[oai(flatten)]
+    pub metadata: EventMetadata,
+}
+
+#[derive(Object, Clone, Debug)]
+pub struct RaceWinnerEvent {
+    #[oai(rename = "type")]
+    pub event_type: EventType,
+    #[oai(flatten)]
+    pub metadata: EventMetadata,
+}
+
+#[derive(Object, Clone, Debug)]
+pub struct PenaltyEvent {
+    #[oai(rename = "type")]
+    pub event_type: EventType,
+    pub penalty_type: PenaltyType,
+    pub infringement_type: InfringementType,
+    pub other_car_index: Option<u8>,
+    pub time: u8, // Time gained, or time spent doing the action, in seconds
+    pub lap_num: u8,
+    pub places_gained: u8,
+    #[oai(flatten)]
+    pub metadata: EventMetadata,
+}
+
+#[derive(Object, Clone, Debug)]
+pub struct SpeedTrapEvent {
+    #[oai(rename = "type")]
+    pub event_type: EventType,
+    pub speed: f32, // km/h
+    pub is_overall_fastest_in_session: bool,
+    pub is_driver_fastest_in_session: bool,
+    pub fastest_car_index_in_session: u8,
+    pub fastest_speed_in_session: f32,
+    #[oai(flatten)]
+    pub metadata: EventMetadata,
+}
+
+/// A drive through or stop go penalty has been served
+#[derive(Object, Clone, Debug)]
+pub struct PenaltyServedEvent {
+    #[oai(rename = "type")]
+    pub event_type: EventType,
+    pub penalty_type: PenaltyType,
+    #[oai(flatten)]
+    pub metadata: EventMetadata,
+}
+
+#[derive(Object, Clone, Debug)]
+pub struct FlashbackEvent {
+    #[oai(rename = "type")]
+    pub event_type: EventType,
+    pub flashback_frame_identifier: u32,
+    pub flashback_session_time: f32,
+    pub timestamp: f32,
+}
+
+/// `car_index` is the overtaking car
+#[derive(Object, Clone, Debug)]
+pub struct OvertakeEvent {
+    #[oai(rename = "type")]
+    pub event_type: EventType,
+    pub overtaken_car_index: u8,
+    #[oai(flatten)]
+    pub metadata: EventMetadata,
+}
+
+#[derive(Object, Clone, Debug)]
+pub struct SafetyCarEvent {
+    #[oai(rename = "type")]
+    pub event_type: EventType,
+    pub safety_car_type: SafetyCarStatus,
+    pub safety_car_event: SafetyCarEventType,
+    pub timestamp: f32,
+}
+
+/// `car_index` is the first of the two cars involved
+#[derive(Object, Clone, Debug)]
+pub struct CollisionEvent {
+    #[oai(rename = "type")]
+    pub event_type: EventType,
+    pub other_car_index: u8,
+    #[oai(flatten)]
+    pub metadata: EventMetadata,
+}
+
+fn session_event(header: &PacketHeader, kind: SessionEventKind) -> Event {
+    Event::Session(SessionEvent {
+        event_type: EventType::SessionEvent,
+        kind,
+        drs_disabled_reason: None,
+        timestamp: header.session_time,
+    })
+}
+
+/// Convert an Event packet into its API event. Button presses aren't surfaced.
+pub fn from_event_packet(header: &PacketHeader, data: &PacketEventData) -> Option<Event> {
+    let car = |idx: u8| EventMetadata::new(header, idx as usize);
+
+    let event = match data.event_details {
+        EventDataDetails::SessionStarted => session_event(header, SessionEventKind::SessionStarted),
+        EventDataDetails::SessionEnded => session_event(header, SessionEventKind::SessionEnded),
+        EventDataDetails::LightsOut => session_event(header, SessionEventKind::LightsOut),
+        EventDataDetails::ChequeredFlag => session_event(header, SessionEventKind::ChequeredFlag),
+        EventDataDetails::RedFlag => session_event(header, SessionEventKind::RedFlag),
+        EventDataDetails::DrsEnabled => session_event(header, SessionEventKind::DrsEnabled),
+        EventDataDetails::DrsDisabled(d) => Event::Session(SessionEvent {
+            event_type: EventType::SessionEvent,
+            kind: SessionEventKind::DrsDisabled,
+            drs_disabled_reason: d.reason,
+            timestamp: header.session_time,
+        }),
+        EventDataDetails::StartLights(d) => Event::StartLights(StartLightsEvent {
+            event_type: EventType::StartLightsEvent,
+            num_lights: d.num_lights,
+            timestamp: header.session_time,
+        }),
+        EventDataDetails::FastestLap(d) => Event::FastestLap(FastestLapEvent {
+            event_type: EventType::FastestLapEvent,
+            lap_time: d.lap_time,
+            metadata: car(d.vehicle_idx),
+        }),
+        EventDataDetails::Retirement(d) => Event::Retirement(RetirementEvent {
+            event_type: EventType::RetirementEvent,
+            reason: d.reason,
+            metadata: car(d.vehicle_idx),
+        }),
+        EventDataDetails::TeamMateInPits(d) => Event::TeamMateInPits(TeamMateInPitsEvent {
+            event_type: EventType::TeamMateInPitsEvent,
+            metadata: car(d.vehicle_idx),
+        }),
+        EventDataDetails::RaceWinner(d) => Event::RaceWinner(RaceWinnerEvent {
+            event_type: EventType::RaceWinnerEvent,
+            metadata: car(d.vehicle_idx),
+        }),
+        EventDataDetails::Penalty(d) => Event::Penalty(PenaltyEvent {
+            event_type: EventType::PenaltyEvent,
+            penalty_type: d.penalty_type,
+            infringement_type: d.infringement_type,
+            other_car_index: (d.other_vehicle_idx < 22).then_some(d.other_vehicle_idx),
+            time: d.time,
+            lap_num: d.lap_num,
+            places_gained: d.places_gained,
+            metadata: car(d.vehicle_idx),
+        }),
+        EventDataDetails::SpeedTrap(d) => Event::SpeedTrap(SpeedTrapEvent {
+            event_type: EventType::SpeedTrapEvent,
+            speed: d.speed,
+            is_overall_fastest_in_session: d.is_overall_fastest_in_session != 0,
+            is_driver_fastest_in_session: d.is_driver_fastest_in_session != 0,
+            fastest_car_index_in_session: d.fastest_vehicle_idx_in_session,
+            fastest_speed_in_session: d.fastest_speed_in_session,
+            metadata: car(d.vehicle_idx),
+        }),
+        EventDataDetails::DriveThroughPenaltyServed(d) => {
+            Event::PenaltyServed(PenaltyServedEvent {
+                event_type: EventType::PenaltyServedEvent,
+                penalty_type: PenaltyType::DriveThrough,
+                metadata: car(d.vehicle_idx),
+            })
+        }
+        EventDataDetails::StopGoPenaltyServed(d) => Event::PenaltyServed(PenaltyServedEvent {
+            event_type: EventType::PenaltyServedEvent,
+            penalty_type: PenaltyType::StopGo,
+            metadata: car(d.vehicle_idx),
+        }),
+        EventDataDetails::Flashback(d) => Event::Flashback(FlashbackEvent {
+            event_type: EventType::FlashbackEvent,
+            flashback_frame_identifier: d.flashback_frame_identifier,
+            flashback_session_time: d.flashback_session_time,
+            timestamp: header.session_time,
+        }),
+        EventDataDetails::Overtake(d) => Event::Overtake(OvertakeEvent {
+            event_type: EventType::OvertakeEvent,
+            overtaken_car_index: d.being_overtaken_vehicle_idx,
+            metadata: car(d.overtaking_vehicle_idx),
+        }),
+        EventDataDetails::SafetyCar(d) => Event::SafetyCar(SafetyCarEvent {
+            event_type: EventType::SafetyCarEvent,
+            safety_car_type: d.safety_car_type,
+            safety_car_event: d.event_type,
+            timestamp: header.session_time,
+        }),
+        EventDataDetails::Collision(d) => Event::Collision(CollisionEvent {
+            event_type: EventType::CollisionEvent,
+            other_car_index: d.vehicle2_idx,
+            metadata: car(d.vehicle1_idx),
+        }),
+        EventDataDetails::Buttons(_) => return None,
+    };
+
+    Some(event)
+}
@@ -0,0 +1,53 @@
+use super::{Event, EventType, HeartbeatEvent};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+
+// The game sends session packets twice a second even when paused, so a
+// longer silence means it has stopped or is sending elsewhere
+const CONNECTED_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// When the last packet arrived, shared by the listener and subscribers
+pub struct Liveness {
+    started: Instant,
+    // Milliseconds after `started`, plus one so that zero means never
+    last_packet: AtomicU64,
+}
+
+impl Liveness {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            last_packet: AtomicU64::new(0),
+        }
+    }
+
+    pub fn touch(&self) {
+        let elapsed = self.started.elapsed().as_millis() as u64;
+        self.last_packet.store(elapsed + 1, Ordering::Relaxed);
+    }
+
+    pub fn heartbeat(&self, dropped_events: u64) -> Event {
+        Event::Heartbeat(self.status(dropped_events))
+    }
+
+    /// The heartbeat itself, for streams that don't carry every event type
+    pub fn status(&self, dropped_events: u64) -> HeartbeatEvent {
+        let last_packet_age = match self.last_packet.load(Ordering::Relaxed) {
+            0 => None,
+            at => Some(
+                self.started
+                    .elapsed()
+                    .saturating_sub(Duration::from_millis(at - 1)),
+            ),
+        };
+
+        HeartbeatEvent {
+            event_type: EventType::Heartbeat,
+            game_connected: last_packet_age.is_some_and(|age| age < CONNECTED_TIMEOUT),
+            last_packet_age_ms: last_packet_age.map(|age| age.as_millis() as u64),
+            dropped_events,
+        }
+    }
+}
@@ -1,20 +1,47 @@
+mod game;
+mod heartbeat;
+mod subscription;
+
+use crate::f1_telemetry_client::packets::enums::{DriverStatus, ResultStatus};
+use crate::f1_telemetry_client::packets::header::PacketHeader;
 use crate::f1_telemetry_client::TelemetryPacket;
 use poem_openapi::{Enum, Object, Union};
 use serde::{Deserialize, Serialize};
 
+pub use game::*;
+pub use heartbeat::{Liveness, HEARTBEAT_INTERVAL};
+pub use subscription::Subscription;
+
 #[derive(Object, Clone, Debug)]
 pub struct EventMetadata {
     pub timestamp: f32,
+    pub car_index: u8,
+}
+
+impl EventMetadata {
+    fn new(header: &PacketHeader, car_index: usize) -> Self {
+        Self {
+            timestamp: header.session_time,
+            car_index: car_index as u8,
+        }
+    }
 }
 
-// Heartbeat event
+/// Sent to each subscriber every `HEARTBEAT_INTERVAL`, and straight away
+/// when it fell behind and missed events
 #[derive(Object, Clone, Debug)]
 pub struct HeartbeatEvent {
     #[oai(rename = "type")]
     pub event_type: EventType,
+    // Whether a packet arrived recently enough for the game to be running
+    pub game_connected: bool,
+    // Missing until the first packet arrives
+    pub last_packet_age_ms: Option<u64>,
+    // Events this subscriber missed since its previous heartbeat
+    pub dropped_events: u64,
 }
 
-#[derive(Clone, Enum, Serialize, Deserialize, Debug)]
+#[derive(Clone, Copy, Enum, Serialize, Deserialize, Debug, PartialEq, Eq, Hash)]
 #[oai(rename = "EventType")]
 pub enum EventType {
     #[oai(rename = "car_telemetry")]
@@ -25,6 +52,32 @@ pub enum EventType {
     LapDataEvent,
     #[oai(rename = "heartbeat")]
     Heartbeat,
+    #[oai(rename = "session")]
+    SessionEvent,
+    #[oai(rename = "start_lights")]
+    StartLightsEvent,
+    #[oai(rename = "fastest_lap")]
+    FastestLapEvent,
+    #[oai(rename = "retirement")]
+    RetirementEvent,
+    #[oai(rename = "team_mate_in_pits")]
+    TeamMateInPitsEvent,
+    #[oai(rename = "race_winner")]
+    RaceWinnerEvent,
+    #[oai(rename = "penalty")]
+    PenaltyEvent,
+    #[oai(rename = "speed_trap")]
+    SpeedTrapEvent,
+    #[oai(rename = "penalty_served")]
+    PenaltyServedEvent,
+    #[oai(rename = "flashback")]
+    FlashbackEvent,
+    #[oai(rename = "overtake")]
+    OvertakeEvent,
+    #[oai(rename = "safety_car")]
+    SafetyCarEvent,
+    #[oai(rename = "collision")]
+    CollisionEvent,
 }
 
 // Union type for all possible events
@@ -39,6 +92,32 @@ pub enum Event {
     LapData(LapDataEvent),
     #[oai(mapping = "heartbeat")]
     Heartbeat(HeartbeatEvent),
+    #[oai(mapping = "session")]
+    Session(SessionEvent),
+    #[oai(mapping = "start_lights")]
+    StartLights(StartLightsEvent),
+    #[oai(mapping = "fastest_lap")]
+    FastestLap(FastestLapEvent),
+    #[oai(mapping = "retirement")]
+    Retirement(RetirementEvent),
+    #[oai(mapping = "team_mate_in_pits")]
+    TeamMateInPits(TeamMateInPitsEvent),
+    #[oai(mapping = "race_winner")]
+    RaceWinner(RaceWinnerEvent),
+    #[oai(mapping = "penalty")]
+    Penalty(PenaltyEvent),
+    #[oai(mapping = "speed_trap")]
+    SpeedTrap(SpeedTrapEvent),
+    #[oai(mapping = "penalty_served")]
+    PenaltyServed(PenaltyServedEvent),
+    #[oai(mapping = "flashback")]
+    Flashback(FlashbackEvent),
+    #[oai(mapping = "overtake")]
+    Overtake(OvertakeEvent),
+    #[oai(mapping = "safety_car")]
+    SafetyCar(SafetyCarEvent),
+    #[oai(mapping = "collision")]
+    Collision(CollisionEvent),
 }
 
 // Data event
@@ -66,6 +145,9 @@ pub struct CarMotionEvent {
     pub world_position_x: f32,
     pub world_position_y: f32,
     pub world_position_z: f32,
+    pub g_force_lateral: f32,
+    pub g_force_longitudinal: f32,
+    pub g_force_vertical: f32,
     #[oai(flatten)]
     pub metadata: EventMetadata,
 }
@@ -91,78 +173,171 @@ pub struct LapDataEvent {
     pub sector: u8,
     pub current_lap_invalid: u8,
     pub grid_position: u8,
-    pub driver_status: u8,
-    pub result_status: u8,
+    pub driver_status: DriverStatus,
+    pub result_status: ResultStatus,
 
     #[oai(flatten)]
     pub metadata: EventMetadata,
 }
 
-impl TryFrom<TelemetryPacket> for Event {
-    type Error = String;
+/// Which cars on the grid produce events
+#[derive(Clone, Copy, Debug, Default)]
+pub enum CarSelection {
+    /// The player's car, plus the secondary player's car in split-screen
+    #[default]
+    Player,
+    /// Every car on the grid
+    All,
+}
+
+impl CarSelection {
+    pub fn car_indices(&self, header: &PacketHeader) -> Vec<usize> {
+        match self {
+            CarSelection::Player => header.player_car_indices(),
+            CarSelection::All => (0..22).collect(),
+        }
+    }
+}
+
+impl Event {
+    /// Whether the event was decoded from the Event packet rather than telemetry
+    pub fn is_game_event(&self) -> bool {
+        !matches!(
+            self,
+            Event::CarTelemetry(_) | Event::CarMotion(_) | Event::LapData(_) | Event::Heartbeat(_)
+        )
+    }
+
+    pub fn event_type(&self) -> EventType {
+        match self {
+            Event::CarTelemetry(e) => e.event_type,
+            Event::CarMotion(e) => e.event_type,
+            Event::LapData(e) => e.event_type,
+            Event::Heartbeat(e) => e.event_type,
+            Event::Session(e) => e.event_type,
+            Event::StartLights(e) => e.event_type,
+            Event::FastestLap(e) => e.event_type,
+            Event::Retirement(e) => e.event_type,
+            Event::TeamMateInPits(e) => e.event_type,
+            Event::RaceWinner(e) => e.event_type,
+            Event::Penalty(e) => e.event_type,
+            Event::SpeedTrap(e) => e.event_type,
+            Event::PenaltyServed(e) => e.event_type,
+            Event::Flashback(e) => e.event_type,
+            Event::Overtake(e) => e.event_type,
+            Event::SafetyCar(e) => e.event_type,
+            Event::Collision(e) => e.event_type,
+        }
+    }
+
+    fn metadata(&self) -> Option<&EventMetadata> {
+        match self {
+            Event::CarTelemetry(e) => Some(&e.metadata),
+            Event::CarMotion(e) => Some(&e.metadata),
+            Event::LapData(e) => Some(&e.metadata),
+            Event::FastestLap(e) => Some(&e.metadata),
+            Event::Retirement(e) => Some(&e.metadata),
+            Event::TeamMateInPits(e) => Some(&e.metadata),
+            Event::RaceWinner(e) => Some(&e.metadata),
+            Event::Penalty(e) => Some(&e.metadata),
+            Event::SpeedTrap(e) => Some(&e.metadata),
+            Event::PenaltyServed(e) => Some(&e.metadata),
+            Event::Overtake(e) => Some(&e.metadata),
+            Event::Collision(e) => Some(&e.metadata),
+            _ => None,
+        }
+    }
+
+    /// Car the event is about, if it concerns a single car
+    pub fn car_index(&self) -> Option<u8> {
+        self.metadata().map(|m| m.car_index)
+    }
+
+    /// Session time the event happened at, missing for heartbeats
+    pub fn timestamp(&self) -> Option<f32> {
+        match self {
+            Event::Heartbeat(_) => None,
+            Event::Session(e) => Some(e.timestamp),
+            Event::StartLights(e) => Some(e.timestamp),
+            Event::Flashback(e) => Some(e.timestamp),
+            Event::SafetyCar(e) => Some(e.timestamp),
+            e => e.metadata().map(|m| m.timestamp),
+        }
+    }
 
-    fn try_from(value: TelemetryPacket) -> Result<Event, Self::Error> {
+    /// Convert a packet into events for every selected car
+    pub fn from_packet(value: TelemetryPacket, selection: CarSelection) -> Vec<Event> {
         match value {
-            TelemetryPacket::CarTelemetry((header, data)) => {
-                Ok(Event::CarTelemetry(CarTelemetryEvent {
-                    event_type: EventType::CarTelemetryEvent,
-                    brake: data.brake,
-                    throttle: data.throttle,
-                    speed: data.speed,
-                    tyre_inner_temp: data.tyre_inner_temp,
-                    tyre_surface_temp: data.tyre_surface_temp,
-                    brake_temp: data.brake_temp,
-                    engine_temperature: data.engine_temperature,
-                    tyre_pressure: data.tyre_pressure,
-                    metadata: EventMetadata {
-                        timestamp: header.session_time,
-                    },
-                }))
-            }
-            TelemetryPacket::Motion((header, data)) => match data.car_motion_data.get(0) {
-                Some(m) => Ok(Event::CarMotion(CarMotionEvent {
-                    event_type: EventType::CarTelemetryEvent,
-                    g_force_lateral: m.g_force_lateral,
-                    g_force_longitudinal: m.g_force_longitudinal,
-                    g_force_vertical: m.g_force_vertical,
-                    world_position_x: m.world_position_x,
-                    world_position_y: m.world_position_y,
-                    world_position_z: m.world_position_z,
-                    metadata: EventMetadata {
-                        timestamp: header.session_time,
-                    },
-                })),
-                _ => Err("Could not get car data for first car".into()),
-            },
-            TelemetryPacket::LapData((header, data)) => match data.lap_data.get(0) {
-                Some(d) => Ok(Event::LapData(LapDataEvent {
-                    event_type: EventType::LapDataEvent,
-                    car_position: d.car_position,
-                    current_lap_invalid: d.current_lap_invalid,
-                    current_lap_num: d.current_lap_num,
-                    current_lap_time_in_ms: d.current_lap_time_in_ms,
-                    delta_to_car_in_front_minutes_part: d.delta_to_car_in_front_minutes_part,
-                    delta_to_car_in_front_ms_part: d.delta_to_car_in_front_ms_part,
-                    delta_to_race_leader_minutes_part: d.delta_to_race_leader_minutes_part,
-                    delta_to_race_leader_ms_part: d.delta_to_race_leader_ms_part,
-                    driver_status: d.driver_status,
-                    grid_position: d.grid_position,
-                    lap_distance: d.lap_distance,
-                    last_lap_time_in_ms: d.last_lap_time_in_ms,
-                    result_status: d.result_status,
-                    sector: d.sector,
-                    sector1_time_minutes_part: d.sector1_time_minutes_part,
-                    sector1_time_ms_part: d.sector1_time_ms_part,
-                    sector2_time_minutes_part: d.sector2_time_minutes_part,
-                    sector2_time_ms_part: d.sector2_time_ms_part,
-                    total_distance: d.total_distance,
-                    metadata: EventMetadata {
-                        timestamp: header.session_time,
-                    },
-                })),
-                _ => Err("Error getting first item of lap data array".into()),
-            },
-            _ => Err("Unimplemented".into()),
+            TelemetryPacket::CarTelemetry((header, data)) => selection
+                .car_indices(&header)
+                .into_iter()
+                .filter_map(|i| {
+                    let t = data.car_telemetry_data.get(i)?;
+                    Some(Event::CarTelemetry(CarTelemetryEvent {
+                        event_type: EventType::CarTelemetryEvent,
+                        brake: t.brake,
+                        throttle: t.throttle,
+                        speed: t.speed,
+                        tyre_inner_temp: t.tyre_inner_temp,
+                        tyre_surface_temp: t.tyre_surface_temp,
+                        brake_temp: t.brake_temp,
+                        engine_temperature: t.engine_temperature,
+                        tyre_pressure: t.tyre_pressure,
+                        metadata: EventMetadata::new(&header, i),
+                    }))
+                })
+                .collect(),
+            TelemetryPacket::Motion((header, data)) => selection
+                .car_indices(&header)
+                .into_iter()
+                .filter_map(|i| {
+                    let m = data.car_motion_data.get(i)?;
+                    Some(Event::CarMotion(CarMotionEvent {
+                        event_type: EventType::CarMotionEvent,
+                        g_force_lateral: m.g_force_lateral,
+                        g_force_longitudinal: m.g_force_longitudinal,
+                        g_force_vertical: m.g_force_vertical,
+                        world_position_x: m.world_position_x,
+                        world_position_y: m.world_position_y,
+                        world_position_z: m.world_position_z,
+                        metadata: EventMetadata::new(&header, i),
+                    }))
+                })
+                .collect(),
+            TelemetryPacket::LapData((header, data)) => selection
+                .car_indices(&header)
+                .into_iter()
+                .filter_map(|i| {
+                    let d = data.lap_data.get(i)?;
+                    Some(Event::LapData(LapDataEvent {
+                        event_type: EventType::LapDataEvent,
+                        car_position: d.car_position,
+                        current_lap_invalid: d.current_lap_invalid,
+                        current_lap_num: d.current_lap_num,
+                        current_lap_time_in_ms: d.current_lap_time_in_ms,
+                        delta_to_car_in_front_minutes_part: d.delta_to_car_in_front_minutes_part,
+                        delta_to_car_in_front_ms_part: d.delta_to_car_in_front_ms_part,
+                        delta_to_race_leader_minutes_part: d.delta_to_race_leader_minutes_part,
+                        delta_to_race_leader_ms_part: d.delta_to_race_leader_ms_part,
+                        driver_status: d.driver_status,
+                        grid_position: d.grid_position,
+                        lap_distance: d.lap_distance,
+                        last_lap_time_in_ms: d.last_lap_time_in_ms,
+                        result_status: d.result_status,
+                        sector: d.sector,
+                        sector1_time_minutes_part: d.sector1_time_minutes_part,
+                        sector1_time_ms_part: d.sector1_time_ms_part,
+                        sector2_time_minutes_part: d.sector2_time_minutes_part,
+                        sector2_time_ms_part: d.sector2_time_ms_part,
+                        total_distance: d.total_distance,
+                        metadata: EventMetadata::new(&header, i),
+                    }))
+                })
+                .collect(),
+            TelemetryPacket::Event((header, data)) => game::from_event_packet(&header, &data)
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
         }
     }
 }
@@ -0,0 +1,54 @@
+use super::{Event, EventType};
+use std::collections::HashMap;
+
+/// What a real-time subscriber asked to receive
+#[derive(Clone, Debug, Default)]
+pub struct Subscription {
+    // Every type if empty
+    pub types: Vec<EventType>,
+    pub car_index: Option<u8>,
+    // Most events per second of each type and car, in session time
+    pub hz: Option<f32>,
+    last_sent: HashMap<(EventType, Option<u8>), f32>,
+}
+
+impl Subscription {
+    pub fn new(types: Vec<EventType>, car_index: Option<u8>, hz: Option<f32>) -> Self {
+        Self {
+            types,
+            car_index,
+            hz,
+            last_sent: HashMap::new(),
+        }
+    }
+
+    /// Whether to send `event` to the subscriber. Events that aren't about a
+    /// single car pass the car filter, and only telemetry is downsampled.
+    pub fn accepts(&mut self, event: &Event) -> bool {
+        let event_type = event.event_type();
+        if !self.types.is_empty() && !self.types.contains(&event_type) {
+            return false;
+        }
+
+        let car_index = event.car_index();
+        if self.car_index.is_some() && car_index.is_some() && car_index != self.car_index {
+            return false;
+        }
+
+        let (Some(hz), Some(time)) = (self.hz, event.timestamp()) else {
+            return true;
+        };
+        if event.is_game_event() {
+            return true;
+        }
+
+        match self.last_sent.get(&(event_type, car_index)) {
+            // Session time going backwards means a flashback or a new session
+            Some(&last) if time >= last && time - last < 1.0 / hz => false,
+            _ => {
+                self.last_sent.insert((event_type, car_index), time);
+                true
+            }
+        }
+    }
+}
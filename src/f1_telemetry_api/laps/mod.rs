@@ -0,0 +1,135 @@
+use crate::f1_telemetry_client::packets::car_status::PacketCarStatusData;
+use crate::f1_telemetry_client::packets::enums::{ActualTyreCompound, VisualTyreCompound};
+use crate::f1_telemetry_client::packets::header::PacketHeader;
+use crate::f1_telemetry_client::packets::lap_data::{LapData, PacketLapData};
+use poem_openapi::Object;
+
+#[derive(Object, Clone, Debug)]
+pub struct CompletedLap {
+    pub session_uid: String,
+    pub car_index: u8,
+    pub lap_num: u8,
+    pub lap_time_in_ms: u32,
+    pub sector1_time_in_ms: u32,
+    pub sector2_time_in_ms: u32,
+    pub sector3_time_in_ms: u32,
+    pub valid: bool,
+    pub tyre_actual_compound: Option<ActualTyreCompound>,
+    pub tyre_visual_compound: Option<VisualTyreCompound>,
+    // Only known when the start of the lap was seen
+    pub fuel_used: Option<f32>,
+    // Session time at which the lap was completed
+    pub session_time: f32,
+    // Frame at which the lap was completed, used to discard it after a flashback
+    pub frame_identifier: u32,
+}
+
+#[derive(Clone, Copy, Default)]
+struct CarState {
+    // Last lap data frame seen for the car, which still holds the sector
+    // times of a lap on the frame before the lap counter ticks over
+    last_frame: Option<LapData>,
+    fuel_in_tank: Option<f32>,
+    fuel_at_lap_start: Option<f32>,
+    lap_start_frame: Option<u32>,
+    tyre_actual_compound: Option<ActualTyreCompound>,
+    tyre_visual_compound: Option<VisualTyreCompound>,
+}
+
+/// Turns the per-frame lap data stream into one record per completed lap
+#[derive(Default)]
+pub struct LapAssembler {
+    session_uid: Option<u64>,
+    cars: [CarState; 22],
+}
+
+impl LapAssembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn start_session(&mut self, header: &PacketHeader) {
+        if self.session_uid != Some(header.session_uid) {
+            self.session_uid = Some(header.session_uid);
+            self.cars = Default::default();
+        }
+    }
+
+    pub fn on_car_status(&mut self, header: &PacketHeader, data: &PacketCarStatusData) {
+        self.start_session(header);
+
+        for (car, status) in self.cars.iter_mut().zip(&data.car_status_data) {
+            car.fuel_in_tank = Some(status.fuel_in_tank);
+            car.tyre_actual_compound = Some(status.actual_tyre_compound);
+            car.tyre_visual_compound = Some(status.visual_tyre_compound);
+        }
+    }
+
+    /// Forget state from frames after `frame_identifier`, which the game rewound to
+    pub fn on_flashback(&mut self, header: &PacketHeader, frame_identifier: u32) {
+        if self.session_uid != Some(header.session_uid) {
+            return;
+        }
+
+        for car in self.cars.iter_mut() {
+            // The next frame after the rewind becomes the reference instead
+            car.last_frame = None;
+            if car
+                .lap_start_frame
+                .is_some_and(|frame| frame > frame_identifier)
+            {
+                car.fuel_at_lap_start = None;
+                car.lap_start_frame = None;
+            }
+        }
+    }
+
+    /// Feed a lap data packet, returning the laps it completes
+    pub fn on_lap_data(
+        &mut self,
+        header: &PacketHeader,
+        data: &PacketLapData,
+    ) -> Vec<CompletedLap> {
+        self.start_session(header);
+
+        let mut completed = Vec::new();
+
+        for (car_index, (car, frame)) in self.cars.iter_mut().zip(&data.lap_data).enumerate() {
+            let Some(previous) = car.last_frame.replace(*frame) else {
+                continue;
+            };
+
+            if previous.current_lap_num == 0 || frame.current_lap_num <= previous.current_lap_num {
+                continue;
+            }
+
+            let lap_time = frame.last_lap_time_in_ms;
+            let sector1 = previous.sector1_time_in_ms();
+            let sector2 = previous.sector2_time_in_ms();
+
+            completed.push(CompletedLap {
+                session_uid: header.session_uid.to_string(),
+                car_index: car_index as u8,
+                lap_num: previous.current_lap_num,
+                lap_time_in_ms: lap_time,
+                sector1_time_in_ms: sector1,
+                sector2_time_in_ms: sector2,
+                sector3_time_in_ms: lap_time.saturating_sub(sector1 + sector2),
+                valid: previous.current_lap_invalid == 0,
+                tyre_actual_compound: car.tyre_actual_compound,
+                tyre_visual_compound: car.tyre_visual_compound,
+                fuel_used: car
+                    .fuel_at_lap_start
+                    .zip(car.fuel_in_tank)
+                    .map(|(start, end)| start - end),
+                session_time: header.session_time,
+                frame_identifier: header.frame_identifier,
+            });
+
+            car.fuel_at_lap_start = car.fuel_in_tank;
+            car.lap_start_frame = Some(header.frame_identifier);
+        }
+
+        completed
+    }
+}
@@ -1,27 +1,123 @@
-mod events;
+pub mod buffer;
+mod enums;
+pub mod events;
+pub mod laps;
+pub mod race_control;
+pub mod reference;
 mod routes;
+pub mod sessions;
+pub mod standings;
 
-use poem::{listener::TcpListener, middleware::Cors, EndpointExt, Result, Route, Server};
+use crate::f1_telemetry_client::TelemetrySource;
+use crate::f1_telemetry_storage::Storage;
+use buffer::{EventBuffer, Retention};
+use events::CarSelection;
+use poem::{get, listener::TcpListener, middleware::Cors, EndpointExt, Route, Server};
 use poem_openapi::OpenApiService;
+use routes::admin::AdminApi;
 use routes::events::EventsApi;
+use routes::laps::LapsApi;
+use routes::race_control::RaceControlApi;
+use routes::reference::ReferenceApi;
+use routes::sessions::SessionsApi;
+use routes::standings::StandingsApi;
+use routes::websocket::{websocket, WebSocketApi};
+use sessions::SessionManager;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
 use tracing::info;
 
-#[derive(Default)]
-pub struct F1TelemetryApi;
+// How long open connections, like event streams, are given to close on shutdown
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Settings of the API that aren't tied to where the telemetry comes from
+#[derive(Clone, Copy, Debug)]
+pub struct ApiConfig {
+    pub car_selection: CarSelection,
+    // Capacity of the broadcast channel real-time subscribers read from
+    pub event_capacity: usize,
+    // How much telemetry is kept in memory for the history endpoints
+    pub retention: Retention,
+}
+
+pub struct F1TelemetryApi {
+    source: TelemetrySource,
+    config: ApiConfig,
+    storage: Storage,
+}
 
 impl F1TelemetryApi {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new(source: TelemetrySource, config: ApiConfig, storage: Storage) -> Self {
+        Self {
+            source,
+            config,
+            storage,
+        }
     }
 
-    pub async fn start(&self, addr: &str) -> Result<()> {
-        let events = EventsApi::new(5000);
+    /// Serve the API and process telemetry until `shutdown` is cancelled,
+    /// then wait for the data received to be stored
+    pub async fn start(
+        &self,
+        addr: &str,
+        shutdown: CancellationToken,
+    ) -> Result<(), Box<dyn Error>> {
+        let tasks = TaskTracker::new();
+        let session_manager = Arc::new(Mutex::new(SessionManager::new()));
+        let events = EventsApi::new(
+            self.config.event_capacity,
+            self.config.car_selection,
+            session_manager.clone(),
+            Arc::new(EventBuffer::new(self.config.retention)),
+        );
+        let laps = LapsApi::new(session_manager.clone(), self.storage.clone());
+        let race_control = RaceControlApi::new(
+            self.config.event_capacity,
+            session_manager.clone(),
+            events.liveness(),
+        );
+        let standings = StandingsApi::new(self.config.event_capacity, session_manager.clone());
+        let ws = WebSocketApi::new(
+            events.sender(),
+            standings.sender(),
+            race_control.sender(),
+            session_manager.clone(),
+            events.liveness(),
+        );
+        let sessions = SessionsApi::new(session_manager, self.storage.clone());
+
+        let errors = events.errors();
+        let writer = self.storage.start_writer(self.config.car_selection, &tasks);
 
-        // Begin listening for UDP data from F1 game
-        events.start_listener("0.0.0.0:20777").await;
+        // Begin listening for data from the F1 game, or a replayed capture
+        let listener = events
+            .start_listener(
+                self.source.clone(),
+                writer,
+                standings.sender(),
+                race_control.sender(),
+                shutdown.clone(),
+                &tasks,
+            )
+            .await?;
 
-        let api_service =
-            OpenApiService::new(events, "Hello World", "1.0").server(format!("http://{}", addr));
+        let api_service = OpenApiService::new(
+            (
+                AdminApi::new(listener, errors),
+                events,
+                laps,
+                race_control,
+                ReferenceApi,
+                sessions,
+                standings,
+            ),
+            "Hello World",
+            "1.0",
+        )
+        .server(format!("http://{}", addr));
 
         let spec = api_service.spec_endpoint();
 
@@ -30,11 +126,24 @@ impl F1TelemetryApi {
         let app = Route::new()
             .nest("/", api_service)
             .nest("/openapi.json", spec)
+            .at("/ws", get(websocket).data(Arc::new(ws)))
             .with(cors);
 
         info!("Program started. Visit: {}", addr);
-        Server::new(TcpListener::bind(addr)).run(app).await.unwrap();
+        let served = Server::new(TcpListener::bind(addr))
+            .run_with_graceful_shutdown(
+                app,
+                shutdown.clone().cancelled_owned(),
+                Some(SHUTDOWN_TIMEOUT),
+            )
+            .await;
+
+        // Stop listening also when the server failed, and let the storage
+        // writer finish
+        shutdown.cancel();
+        tasks.close();
+        tasks.wait().await;
 
-        return Ok(());
+        served.map_err(|e| format!("Error serving the API on {}: {}", addr, e).into())
     }
 }
@@ -0,0 +1,164 @@
+use crate::f1_telemetry_api::events::{Event, EventType};
+use crate::f1_telemetry_client::packets::enums::{
+    PenaltyType, RetirementReason, SafetyCarEventType, SafetyCarStatus,
+};
+use poem_openapi::Object;
+
+/// Human-readable message about a game event, as race control would announce it
+#[derive(Object, Clone, Debug)]
+pub struct RaceControlMessage {
+    #[oai(rename = "type")]
+    pub event_type: EventType,
+    pub timestamp: f32, // Session time the event happened at
+    pub message: String,
+    pub car_index: Option<u8>,
+    pub other_car_index: Option<u8>, // Second car involved, e.g. the one overtaken
+}
+
+/// Build the message for an event, naming cars after `driver_names`.
+/// Events race control doesn't announce give `None`.
+pub fn message(event: &Event, driver_names: &[String]) -> Option<RaceControlMessage> {
+    let name = |idx: u8| {
+        driver_names
+            .get(idx as usize)
+            .cloned()
+            .unwrap_or_else(|| format!("Car {}", idx))
+    };
+
+    let (event_type, timestamp, message, car_index, other_car_index) = match event {
+        Event::Penalty(e) => {
+            let car = e.metadata.car_index;
+            let penalty = match e.penalty_type {
+                PenaltyType::TimePenalty => format!("{} second time penalty", e.time),
+                other => capitalise(&humanise(other.as_str())),
+            };
+            let mut message = format!(
+                "{} for {} ({})",
+                penalty,
+                name(car),
+                humanise(e.infringement_type.as_str())
+            );
+            if let Some(other) = e.other_car_index {
+                message.push_str(&format!(" involving {}", name(other)));
+            }
+            if e.lap_num > 0 {
+                message.push_str(&format!(" on lap {}", e.lap_num));
+            }
+            (
+                EventType::PenaltyEvent,
+                e.metadata.timestamp,
+                message,
+                Some(car),
+                e.other_car_index,
+            )
+        }
+        Event::PenaltyServed(e) => {
+            let car = e.metadata.car_index;
+            let message = format!(
+                "{} has served a {} penalty",
+                name(car),
+                humanise(e.penalty_type.as_str())
+            );
+            let ts = e.metadata.timestamp;
+            (EventType::PenaltyServedEvent, ts, message, Some(car), None)
+        }
+        Event::SafetyCar(e) => {
+            let car = match e.safety_car_type {
+                SafetyCarStatus::Virtual => "Virtual safety car",
+                SafetyCarStatus::FormationLap => "Formation lap safety car",
+                _ => "Safety car",
+            };
+            let message = match e.safety_car_event {
+                SafetyCarEventType::Deployed => format!("{} deployed", car),
+                SafetyCarEventType::Returning => format!("{} ending", car),
+                SafetyCarEventType::Returned => format!("{} in the pit lane", car),
+                SafetyCarEventType::ResumeRace => "Race resumed".into(),
+                SafetyCarEventType::Unknown(n) => format!("{} update ({})", car, n),
+            };
+            (EventType::SafetyCarEvent, e.timestamp, message, None, None)
+        }
+        Event::Collision(e) => {
+            let car = e.metadata.car_index;
+            let message = format!(
+                "Collision between {} and {}",
+                name(car),
+                name(e.other_car_index)
+            );
+            (
+                EventType::CollisionEvent,
+                e.metadata.timestamp,
+                message,
+                Some(car),
+                Some(e.other_car_index),
+            )
+        }
+        Event::Overtake(e) => {
+            let car = e.metadata.car_index;
+            let message = format!("{} overtakes {}", name(car), name(e.overtaken_car_index));
+            (
+                EventType::OvertakeEvent,
+                e.metadata.timestamp,
+                message,
+                Some(car),
+                Some(e.overtaken_car_index),
+            )
+        }
+        Event::Retirement(e) => {
+            let car = e.metadata.car_index;
+            let message = match e.reason {
+                None | Some(RetirementReason::Invalid | RetirementReason::Retired) => {
+                    format!("{} has retired", name(car))
+                }
+                Some(reason) => {
+                    format!("{} has retired ({})", name(car), humanise(reason.as_str()))
+                }
+            };
+            let ts = e.metadata.timestamp;
+            (EventType::RetirementEvent, ts, message, Some(car), None)
+        }
+        Event::FastestLap(e) => {
+            let car = e.metadata.car_index;
+            let message = format!("Fastest lap: {} {}", name(car), lap_time(e.lap_time));
+            let ts = e.metadata.timestamp;
+            (EventType::FastestLapEvent, ts, message, Some(car), None)
+        }
+        Event::SpeedTrap(e) => {
+            let car = e.metadata.car_index;
+            let mut message = format!("Speed trap: {} {:.1} km/h", name(car), e.speed);
+            if e.is_overall_fastest_in_session {
+                message.push_str(" (fastest of the session)");
+            } else if e.is_driver_fastest_in_session {
+                message.push_str(" (personal best)");
+            }
+            let ts = e.metadata.timestamp;
+            (EventType::SpeedTrapEvent, ts, message, Some(car), None)
+        }
+        _ => return None,
+    };
+
+    Some(RaceControlMessage {
+        event_type,
+        timestamp,
+        message,
+        car_index,
+        other_car_index,
+    })
+}
+
+fn humanise(name: &str) -> String {
+    name.replace('_', " ")
+}
+
+fn capitalise(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(c) => c.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// Lap time in seconds as m:ss.mmm
+fn lap_time(seconds: f32) -> String {
+    let ms = (seconds * 1000.0).round() as u32;
+    format!("{}:{:02}.{:03}", ms / 60_000, ms / 1000 % 60, ms % 1000)
+}
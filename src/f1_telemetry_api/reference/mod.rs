@@ -0,0 +1,438 @@
+use crate::f1_telemetry_client::packets::enums::{SessionType, Track};
+use poem_openapi::Object;
+
+// Lookup tables from the appendices of the F1 24 UDP specification, so
+// clients can show names instead of raw ids
+
+#[derive(Object, Clone, Debug)]
+pub struct TrackInfo {
+    pub id: i8,
+    // Value of the track in other API responses
+    pub key: String,
+    pub name: String,
+    // Lap length in metres, missing for layouts only found in the game
+    pub length: Option<u32>,
+}
+
+#[derive(Object, Clone, Debug)]
+pub struct TeamInfo {
+    pub id: u8,
+    pub name: String,
+    // Livery colour as #rrggbb, only known for the F1 teams
+    pub colour: Option<String>,
+}
+
+#[derive(Object, Clone, Debug)]
+pub struct DriverInfo {
+    pub id: u8,
+    pub name: String,
+}
+
+#[derive(Object, Clone, Debug)]
+pub struct NationalityInfo {
+    pub id: u8,
+    pub name: String,
+}
+
+#[derive(Object, Clone, Debug)]
+pub struct SessionTypeInfo {
+    pub id: u8,
+    // Value of the session type in other API responses
+    pub key: String,
+    pub name: String,
+}
+
+pub fn tracks() -> Vec<TrackInfo> {
+    TRACKS
+        .iter()
+        .map(|&(id, name, length)| TrackInfo {
+            id,
+            key: Track::from(id).as_str().into(),
+            name: name.into(),
+            length,
+        })
+        .collect()
+}
+
+pub fn teams() -> Vec<TeamInfo> {
+    TEAMS
+        .iter()
+        .map(|&(id, name, colour)| TeamInfo {
+            id,
+            name: name.into(),
+            colour: colour.map(Into::into),
+        })
+        .collect()
+}
+
+/// Name of a team from the Team IDs appendix
+pub fn team_name(id: u8) -> Option<&'static str> {
+    TEAMS
+        .iter()
+        .find(|&&(team_id, _, _)| team_id == id)
+        .map(|&(_, name, _)| name)
+}
+
+pub fn drivers() -> Vec<DriverInfo> {
+    DRIVERS
+        .iter()
+        .map(|&(id, name)| DriverInfo {
+            id,
+            name: name.into(),
+        })
+        .collect()
+}
+
+/// Name of a driver from the Driver IDs appendix
+pub fn driver_name(id: u8) -> Option<&'static str> {
+    DRIVERS
+        .iter()
+        .find(|&&(driver_id, _)| driver_id == id)
+        .map(|&(_, name)| name)
+}
+
+pub fn nationalities() -> Vec<NationalityInfo> {
+    NATIONALITIES
+        .iter()
+        .map(|&(id, name)| NationalityInfo {
+            id,
+            name: name.into(),
+        })
+        .collect()
+}
+
+pub fn session_types() -> Vec<SessionTypeInfo> {
+    SESSION_TYPES
+        .iter()
+        .map(|&(id, name)| SessionTypeInfo {
+            id,
+            key: SessionType::from(id).as_str().into(),
+            name: name.into(),
+        })
+        .collect()
+}
+
+const TRACKS: &[(i8, &str, Option<u32>)] = &[
+    (0, "Melbourne", Some(5278)),
+    (1, "Paul Ricard", Some(5842)),
+    (2, "Shanghai", Some(5451)),
+    (3, "Sakhir (Bahrain)", Some(5412)),
+    (4, "Catalunya", Some(4657)),
+    (5, "Monaco", Some(3337)),
+    (6, "Montreal", Some(4361)),
+    (7, "Silverstone", Some(5891)),
+    (8, "Hockenheim", Some(4574)),
+    (9, "Hungaroring", Some(4381)),
+    (10, "Spa", Some(7004)),
+    (11, "Monza", Some(5793)),
+    (12, "Singapore", Some(4940)),
+    (13, "Suzuka", Some(5807)),
+    (14, "Abu Dhabi", Some(5281)),
+    (15, "Texas", Some(5513)),
+    (16, "Brazil", Some(4309)),
+    (17, "Austria", Some(4318)),
+    (18, "Sochi", Some(5848)),
+    (19, "Mexico", Some(4304)),
+    (20, "Baku (Azerbaijan)", Some(6003)),
+    (21, "Sakhir Short", Some(3543)),
+    (22, "Silverstone Short", None),
+    (23, "Texas Short", None),
+    (24, "Suzuka Short", None),
+    (25, "Hanoi", Some(5607)),
+    (26, "Zandvoort", Some(4259)),
+    (27, "Imola", Some(4909)),
+    (28, "Portimão", Some(4653)),
+    (29, "Jeddah", Some(6174)),
+    (30, "Miami", Some(5412)),
+    (31, "Las Vegas", Some(6201)),
+    (32, "Losail", Some(5419)),
+];
+
+const TEAMS: &[(u8, &str, Option<&str>)] = &[
+    (0, "Mercedes", Some("#27f4d2")),
+    (1, "Ferrari", Some("#e8002d")),
+    (2, "Red Bull Racing", Some("#3671c6")),
+    (3, "Williams", Some("#64c4ff")),
+    (4, "Aston Martin", Some("#229971")),
+    (5, "Alpine", Some("#ff87bc")),
+    (6, "RB", Some("#6692ff")),
+    (7, "Haas", Some("#b6babd")),
+    (8, "McLaren", Some("#ff8000")),
+    (9, "Sauber", Some("#52e252")),
+    (41, "F1 Generic", None),
+    (104, "F1 Custom Team", None),
+    (143, "Art GP '23", None),
+    (144, "Campos '23", None),
+    (145, "Carlin '23", None),
+    (146, "PHM '23", None),
+    (147, "Dams '23", None),
+    (148, "Hitech '23", None),
+    (149, "MP Motorsport '23", None),
+    (150, "Prema '23", None),
+    (151, "Trident '23", None),
+    (152, "Van Amersfoort Racing '23", None),
+    (153, "Virtuosi '23", None),
+];
+
+const DRIVERS: &[(u8, &str)] = &[
+    (0, "Carlos Sainz"),
+    (1, "Daniil Kvyat"),
+    (2, "Daniel Ricciardo"),
+    (3, "Fernando Alonso"),
+    (4, "Felipe Massa"),
+    (6, "Kimi Räikkönen"),
+    (7, "Lewis Hamilton"),
+    (9, "Max Verstappen"),
+    (10, "Nico Hulkenburg"),
+    (11, "Kevin Magnussen"),
+    (12, "Romain Grosjean"),
+    (13, "Sebastian Vettel"),
+    (14, "Sergio Perez"),
+    (15, "Valtteri Bottas"),
+    (17, "Esteban Ocon"),
+    (19, "Lance Stroll"),
+    (20, "Arron Barnes"),
+    (21, "Martin Giles"),
+    (22, "Alex Murray"),
+    (23, "Lucas Roth"),
+    (24, "Igor Correia"),
+    (25, "Sophie Levasseur"),
+    (26, "Jonas Schiffer"),
+    (27, "Alain Forest"),
+    (28, "Jay Letourneau"),
+    (29, "Esto Saari"),
+    (30, "Yasar Atiyeh"),
+    (31, "Callisto Calabresi"),
+    (32, "Naota Izum"),
+    (33, "Howard Clarke"),
+    (34, "Wilheim Kaufmann"),
+    (35, "Marie Laursen"),
+    (36, "Flavio Nieves"),
+    (37, "Peter Belousov"),
+    (38, "Klimek Michalski"),
+    (39, "Santiago Moreno"),
+    (40, "Benjamin Coppens"),
+    (41, "Noah Visser"),
+    (42, "Gert Waldmuller"),
+    (43, "Julian Quesada"),
+    (44, "Daniel Jones"),
+    (45, "Artem Markelov"),
+    (46, "Tadasuke Makino"),
+    (47, "Sean Gelael"),
+    (48, "Nyck De Vries"),
+    (49, "Jack Aitken"),
+    (50, "George Russell"),
+    (51, "Maximilian Günther"),
+    (52, "Nirei Fukuzumi"),
+    (53, "Luca Ghiotto"),
+    (54, "Lando Norris"),
+    (55, "Sérgio Sette Câmara"),
+    (56, "Louis Delétraz"),
+    (57, "Antonio Fuoco"),
+    (58, "Charles Leclerc"),
+    (59, "Pierre Gasly"),
+    (62, "Alexander Albon"),
+    (63, "Nicholas Latifi"),
+    (64, "Dorian Boccolacci"),
+    (65, "Niko Kari"),
+    (66, "Roberto Merhi"),
+    (67, "Arjun Maini"),
+    (68, "Alessio Lorandi"),
+    (69, "Ruben Meijer"),
+    (70, "Rashid Nair"),
+    (71, "Jack Tremblay"),
+    (72, "Devon Butler"),
+    (73, "Lukas Weber"),
+    (74, "Antonio Giovinazzi"),
+    (75, "Robert Kubica"),
+    (76, "Alain Prost"),
+    (77, "Ayrton Senna"),
+    (78, "Nobuharu Matsushita"),
+    (79, "Nikita Mazepin"),
+    (80, "Guanya Zhou"),
+    (81, "Mick Schumacher"),
+    (82, "Callum Ilott"),
+    (83, "Juan Manuel Correa"),
+    (84, "Jordan King"),
+    (85, "Mahaveer Raghunathan"),
+    (86, "Tatiana Calderon"),
+    (87, "Anthoine Hubert"),
+    (88, "Guiliano Alesi"),
+    (89, "Ralph Boschung"),
+    (90, "Michael Schumacher"),
+    (91, "Dan Ticktum"),
+    (92, "Marcus Armstrong"),
+    (93, "Christian Lundgaard"),
+    (94, "Yuki Tsunoda"),
+    (95, "Jehan Daruvala"),
+    (96, "Gulherme Samaia"),
+    (97, "Pedro Piquet"),
+    (98, "Felipe Drugovich"),
+    (99, "Robert Schwartzman"),
+    (100, "Roy Nissany"),
+    (101, "Marino Sato"),
+    (102, "Aidan Jackson"),
+    (103, "Casper Akkerman"),
+    (109, "Jenson Button"),
+    (110, "David Coulthard"),
+    (111, "Nico Rosberg"),
+    (112, "Oscar Piastri"),
+    (113, "Liam Lawson"),
+    (114, "Juri Vips"),
+    (115, "Theo Pourchaire"),
+    (116, "Richard Verschoor"),
+    (117, "Lirim Zendeli"),
+    (118, "David Beckmann"),
+    (121, "Alessio Deledda"),
+    (122, "Bent Viscaal"),
+    (123, "Enzo Fittipaldi"),
+    (125, "Mark Webber"),
+    (126, "Jacques Villeneuve"),
+    (127, "Callie Mayer"),
+    (128, "Noah Bell"),
+    (129, "Jake Hughes"),
+    (130, "Frederik Vesti"),
+    (131, "Olli Caldwell"),
+    (132, "Logan Sargeant"),
+    (133, "Cem Bolukbasi"),
+    (134, "Ayumu Iwasa"),
+    (135, "Clement Novalak"),
+    (136, "Jack Doohan"),
+    (137, "Amaury Cordeel"),
+    (138, "Dennis Hauger"),
+    (139, "Calan Williams"),
+    (140, "Jamie Chadwick"),
+    (141, "Kamui Kobayashi"),
+    (142, "Pastor Maldonado"),
+    (143, "Mika Hakkinen"),
+    (144, "Nigel Mansell"),
+    (145, "Zane Maloney"),
+    (146, "Victor Martins"),
+    (147, "Oliver Bearman"),
+    (148, "Jak Crawford"),
+    (149, "Isack Hadjar"),
+    (150, "Arthur Leclerc"),
+    (151, "Brad Benavides"),
+    (152, "Roman Stanek"),
+    (153, "Kush Maini"),
+    (154, "James Hunt"),
+    (155, "Juan Pablo Montoya"),
+    (156, "Brendon Leigh"),
+    (157, "David Tonizza"),
+    (158, "Jarno Opmeer"),
+    (159, "Lucas Blakeley"),
+];
+
+const NATIONALITIES: &[(u8, &str)] = &[
+    (1, "American"),
+    (2, "Argentinean"),
+    (3, "Australian"),
+    (4, "Austrian"),
+    (5, "Azerbaijani"),
+    (6, "Bahraini"),
+    (7, "Belgian"),
+    (8, "Bolivian"),
+    (9, "Brazilian"),
+    (10, "British"),
+    (11, "Bulgarian"),
+    (12, "Cameroonian"),
+    (13, "Canadian"),
+    (14, "Chilean"),
+    (15, "Chinese"),
+    (16, "Colombian"),
+    (17, "Costa Rican"),
+    (18, "Croatian"),
+    (19, "Cypriot"),
+    (20, "Czech"),
+    (21, "Danish"),
+    (22, "Dutch"),
+    (23, "Ecuadorian"),
+    (24, "English"),
+    (25, "Emirian"),
+    (26, "Estonian"),
+    (27, "Finnish"),
+    (28, "French"),
+    (29, "German"),
+    (30, "Ghanaian"),
+    (31, "Greek"),
+    (32, "Guatemalan"),
+    (33, "Honduran"),
+    (34, "Hong Konger"),
+    (35, "Hungarian"),
+    (36, "Icelander"),
+    (37, "Indian"),
+    (38, "Indonesian"),
+    (39, "Irish"),
+    (40, "Israeli"),
+    (41, "Italian"),
+    (42, "Jamaican"),
+    (43, "Japanese"),
+    (44, "Jordanian"),
+    (45, "Kuwaiti"),
+    (46, "Latvian"),
+    (47, "Lebanese"),
+    (48, "Lithuanian"),
+    (49, "Luxembourger"),
+    (50, "Malaysian"),
+    (51, "Maltese"),
+    (52, "Mexican"),
+    (53, "Monegasque"),
+    (54, "New Zealander"),
+    (55, "Nicaraguan"),
+    (56, "Northern Irish"),
+    (57, "Norwegian"),
+    (58, "Omani"),
+    (59, "Pakistani"),
+    (60, "Panamanian"),
+    (61, "Paraguayan"),
+    (62, "Peruvian"),
+    (63, "Polish"),
+    (64, "Portuguese"),
+    (65, "Qatari"),
+    (66, "Romanian"),
+    (68, "Salvadoran"),
+    (69, "Saudi"),
+    (70, "Scottish"),
+    (71, "Serbian"),
+    (72, "Singaporean"),
+    (73, "Slovakian"),
+    (74, "Slovenian"),
+    (75, "South Korean"),
+    (76, "South African"),
+    (77, "Spanish"),
+    (78, "Swedish"),
+    (79, "Swiss"),
+    (80, "Thai"),
+    (81, "Turkish"),
+    (82, "Uruguayan"),
+    (83, "Ukrainian"),
+    (84, "Venezuelan"),
+    (85, "Barbadian"),
+    (86, "Welsh"),
+    (87, "Vietnamese"),
+    (88, "Algerian"),
+    (89, "Bosnian"),
+    (90, "Filipino"),
+];
+
+const SESSION_TYPES: &[(u8, &str)] = &[
+    (0, "Unknown"),
+    (1, "Practice 1"),
+    (2, "Practice 2"),
+    (3, "Practice 3"),
+    (4, "Short Practice"),
+    (5, "Qualifying 1"),
+    (6, "Qualifying 2"),
+    (7, "Qualifying 3"),
+    (8, "Short Qualifying"),
+    (9, "One-Shot Qualifying"),
+    (10, "Sprint Shootout 1"),
+    (11, "Sprint Shootout 2"),
+    (12, "Sprint Shootout 3"),
+    (13, "Short Sprint Shootout"),
+    (14, "One-Shot Sprint Shootout"),
+    (15, "Race"),
+    (16, "Race 2"),
+    (17, "Race 3"),
+    (18, "Time Trial"),
+];
@@ -0,0 +1,130 @@
+use crate::f1_telemetry_client::{ErrorCounts, F1TelemetryClient};
+use poem_openapi::payload::{Json, PlainText};
+use poem_openapi::{ApiResponse, Object, OpenApi};
+use std::sync::Arc;
+
+/// Control of the UDP listener while the process runs, and diagnostics of
+/// the data it receives
+pub struct AdminApi {
+    // None when replaying a capture
+    listener: Option<Arc<F1TelemetryClient>>,
+    errors: Arc<ErrorCounts>,
+}
+
+#[derive(Object)]
+struct ListenerStatus {
+    address: String,
+    // Datagrams are discarded while paused
+    paused: bool,
+}
+
+#[derive(Object)]
+struct RebindRequest {
+    /// Address to listen on, e.g. `0.0.0.0:20777`
+    address: String,
+}
+
+/// Datagrams discarded for one kind of decoding error
+#[derive(Object)]
+struct PacketErrorCount {
+    /// too_short, unknown_packet_id, unsupported_format,
+    /// unsupported_version, invalid_enum_value or invalid_utf8
+    kind: String,
+    count: u64,
+    last_error: Option<String>,
+    last_seen_at: Option<String>,
+}
+
+#[derive(ApiResponse)]
+enum ListenerResponse {
+    #[oai(status = 200)]
+    Success(Json<ListenerStatus>),
+    #[oai(status = 400)]
+    BadRequest(PlainText<String>),
+    /// Not listening for the game, e.g. when replaying a capture
+    #[oai(status = 409)]
+    Conflict,
+    /// The socket's address couldn't be read
+    #[oai(status = 500)]
+    InternalError(PlainText<String>),
+}
+
+impl AdminApi {
+    pub fn new(listener: Option<Arc<F1TelemetryClient>>, errors: Arc<ErrorCounts>) -> Self {
+        Self { listener, errors }
+    }
+
+    fn status(listener: &F1TelemetryClient) -> ListenerResponse {
+        match listener.local_addr() {
+            Ok(addr) => ListenerResponse::Success(Json(ListenerStatus {
+                address: addr.to_string(),
+                paused: listener.is_paused(),
+            })),
+            Err(e) => ListenerResponse::InternalError(PlainText(e.to_string())),
+        }
+    }
+}
+
+#[OpenApi]
+impl AdminApi {
+    #[oai(path = "/admin/listener", method = "get")]
+    async fn listener(&self) -> ListenerResponse {
+        match &self.listener {
+            Some(listener) => Self::status(listener),
+            None => ListenerResponse::Conflict,
+        }
+    }
+
+    /// Stop processing packets from the game until resumed
+    #[oai(path = "/admin/listener/pause", method = "post")]
+    async fn pause(&self) -> ListenerResponse {
+        let Some(listener) = &self.listener else {
+            return ListenerResponse::Conflict;
+        };
+        listener.pause();
+        Self::status(listener)
+    }
+
+    #[oai(path = "/admin/listener/resume", method = "post")]
+    async fn resume(&self) -> ListenerResponse {
+        let Some(listener) = &self.listener else {
+            return ListenerResponse::Conflict;
+        };
+        listener.resume();
+        Self::status(listener)
+    }
+
+    /// Listen on another address, keeping the current one if it can't be bound
+    #[oai(path = "/admin/listener/rebind", method = "post")]
+    async fn rebind(&self, body: Json<RebindRequest>) -> ListenerResponse {
+        let Some(listener) = &self.listener else {
+            return ListenerResponse::Conflict;
+        };
+        if let Err(e) = listener.rebind(&body.address).await {
+            return ListenerResponse::BadRequest(PlainText(format!(
+                "Error binding {}: {}",
+                body.address, e
+            )));
+        }
+        Self::status(listener)
+    }
+
+    /// Datagrams that couldn't be decoded since the process started, per
+    /// kind of error. Counts that keep climbing usually mean a game update
+    /// changed a packet layout.
+    #[oai(path = "/admin/packet_errors", method = "get")]
+    async fn packet_errors(&self) -> Json<Vec<PacketErrorCount>> {
+        Json(
+            self.errors
+                .counts()
+                .into_iter()
+                .map(|c| PacketErrorCount {
+                    kind: c.kind.into(),
+                    count: c.count,
+                    last_error: c.last_error,
+                    last_seen_at: c.last_seen_at.map(|t| t.to_rfc3339()),
+                })
+                .collect(),
+        )
+    }
+}
@@ -1,129 +1,369 @@
-use crate::f1_telemetry_api::events::Event;
+use crate::f1_telemetry_api::buffer::EventBuffer;
+use crate::f1_telemetry_api::events::CarSelection;
 use crate::f1_telemetry_api::events::LapDataEvent;
-use crate::f1_telemetry_client::F1TelemetryClient;
+use crate::f1_telemetry_api::events::{
+    Event, EventType, Liveness, Subscription, HEARTBEAT_INTERVAL,
+};
+use crate::f1_telemetry_api::laps::LapAssembler;
+use crate::f1_telemetry_api::race_control::RaceControlMessage;
+use crate::f1_telemetry_api::routes::history::{self, Cursor, HistoryFilter, Projected};
+use crate::f1_telemetry_api::sessions::SessionManager;
+use crate::f1_telemetry_api::standings::StandingsUpdate;
+use crate::f1_telemetry_client::packets::event::EventDataDetails;
+use crate::f1_telemetry_client::{
+    ErrorCounts, F1TelemetryClient, F1TelemetryReplay, TelemetryPacket, TelemetrySource,
+};
+use crate::f1_telemetry_storage::StorageWriter;
 use futures_util::{stream::BoxStream, StreamExt};
-use poem::web::Query;
 use poem::Result;
 use poem_openapi::param::Query;
 use poem_openapi::payload::Json;
+use poem_openapi::types::ParseFromParameter;
 use poem_openapi::ApiResponse;
 use poem_openapi::{payload::EventStream, OpenApi};
-use std::{
-    process::exit,
-    sync::{Arc, Mutex},
-};
-use tokio::sync::broadcast;
+use std::error::Error;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
 use tracing::{debug, error};
 
-struct PositionEvent {
-    x: f32,
-    y: f32,
-    speed: u16,
-}
-
 pub struct EventsApi {
     sender: Arc<broadcast::Sender<Event>>,
-    data: Arc<Mutex<Vec<Event>>>,
+    buffer: Arc<EventBuffer>,
+    liveness: Arc<Liveness>,
+    errors: Arc<ErrorCounts>,
+    sessions: Arc<Mutex<SessionManager>>,
+    car_selection: CarSelection,
+}
+
+#[derive(ApiResponse)]
+enum GetEventsResponse {
+    #[oai(status = 200)]
+    Success(EventStream<BoxStream<'static, Event>>),
+    #[oai(status = 400)]
+    BadRequest,
 }
 
 #[derive(ApiResponse)]
 enum GetLapDataResponse {
     #[oai(status = 200)]
-    Success(Json<Vec<LapDataEvent>>),
+    Success(
+        Json<Projected<LapDataEvent>>,
+        #[oai(header = "X-Next-Cursor")] Option<String>,
+    ),
+    #[oai(status = 400)]
+    BadRequest,
 }
 
 #[OpenApi]
 impl EventsApi {
-    pub fn new(capacity: usize) -> Self {
+    pub fn new(
+        capacity: usize,
+        car_selection: CarSelection,
+        sessions: Arc<Mutex<SessionManager>>,
+        buffer: Arc<EventBuffer>,
+    ) -> Self {
         let (sender, _) = broadcast::channel(capacity);
 
         EventsApi {
             sender: Arc::new(sender),
-            data: Arc::new(Mutex::new(Vec::new())),
+            buffer,
+            liveness: Arc::new(Liveness::new()),
+            errors: Arc::new(ErrorCounts::new()),
+            sessions,
+            car_selection,
         }
     }
 
-    pub async fn start_listener(&self, addr: &str) {
-        let client = F1TelemetryClient::new(addr).await.unwrap();
-
-        let client_handle = Arc::new(client);
-
-        let ctrlc_client_clone = client_handle.clone();
-        tokio::spawn(async move {
-            tokio::signal::ctrl_c().await.unwrap();
-            debug!("\nStopping telemetry capture...");
+    /// Channel the real-time events are broadcast on
+    pub fn sender(&self) -> Arc<broadcast::Sender<Event>> {
+        self.sender.clone()
+    }
 
-            ctrlc_client_clone.stop().await;
+    /// When the last packet arrived, for subscribers outside this API
+    pub fn liveness(&self) -> Arc<Liveness> {
+        self.liveness.clone()
+    }
 
-            exit(0);
-        });
+    /// Datagrams from the source that couldn't be decoded
+    pub fn errors(&self) -> Arc<ErrorCounts> {
+        self.errors.clone()
+    }
 
-        let client_clone = client_handle.clone();
+    /// Start receiving packets from `source` on a task of `tasks` that
+    /// ends on `shutdown`. Returns the UDP client when listening to the
+    /// game, for it to be controlled while running.
+    pub async fn start_listener(
+        &self,
+        source: TelemetrySource,
+        writer: StorageWriter,
+        standings: Arc<broadcast::Sender<StandingsUpdate>>,
+        race_control: Arc<broadcast::Sender<RaceControlMessage>>,
+        shutdown: CancellationToken,
+        tasks: &TaskTracker,
+    ) -> Result<Option<Arc<F1TelemetryClient>>, Box<dyn Error>> {
         let sender = self.sender.clone();
-        let data_clone = self.data.clone();
+        let sessions_clone = self.sessions.clone();
+        let car_selection = self.car_selection;
+        let buffer = self.buffer.clone();
+        let liveness = self.liveness.clone();
+        let lap_assembler = Mutex::new(LapAssembler::new());
 
-        // Listen for events on the telemetry client and
+        // Listen for events on the telemetry source and
         // 1. send them in realtime to all listeners
-        // 2. save them in memory for further processing
-        tokio::spawn(async move {
-            let lap_number: Arc<Mutex<usize>> = Arc::new(Mutex::new(0));
-            client_clone
-                .start(|x| {
-                    if let Ok(ev) = Event::try_from(x) {
-                        let ev_clone = ev.clone();
-                        if let Event::CarMotion(_) = ev {
-                            if let Err(e) = sender.send(ev) {
-                                error!("Error sending event {:?}", e.0);
-                            }
-                        }
+        // 2. save them in memory, grouped by session, for further processing:
+        //    telemetry in the ring buffer, race control messages of the game
+        //    events with the session
+        // 3. persist them to the database
+        // 4. keep the timing tower up to date and send what changed
+        // 5. send the race control messages
+        let handle_packet = move |x: TelemetryPacket| {
+            liveness.touch();
+            writer.record(&x);
 
-                        let mut g = lap_number.try_lock().unwrap();
-                        if let Event::LapData(lap_data) = ev_clone.clone() {
-                            (*g) = lap_data.current_lap_num.into();
+            let header = *x.header();
+            {
+                let mut sessions = sessions_clone.lock().unwrap();
+                sessions.observe(&header);
+                match &x {
+                    TelemetryPacket::Session((header, data)) => {
+                        sessions.update_metadata(header, data);
+                    }
+                    TelemetryPacket::Participants((header, data)) => {
+                        sessions.update_participants(header, data);
+                    }
+                    TelemetryPacket::CarStatus((header, data)) => {
+                        lap_assembler.lock().unwrap().on_car_status(header, data);
+                        sessions.update_car_status(header, data);
+                    }
+                    TelemetryPacket::LapData((header, data)) => {
+                        for lap in lap_assembler.lock().unwrap().on_lap_data(header, data) {
+                            sessions.push_lap(header.session_uid, lap);
+                        }
+                        sessions.update_lap_data(header, data);
+                    }
+                    TelemetryPacket::Event((header, data)) => {
+                        if let EventDataDetails::Flashback(f) = data.event_details {
+                            sessions
+                                .discard_after(header.session_uid, f.flashback_frame_identifier);
+                            buffer.discard_after(header.session_uid, f.flashback_frame_identifier);
+                            lap_assembler
+                                .lock()
+                                .unwrap()
+                                .on_flashback(header, f.flashback_frame_identifier);
                         }
+                    }
+                    _ => (),
+                }
+
+                if matches!(
+                    x,
+                    TelemetryPacket::Participants(_)
+                        | TelemetryPacket::CarStatus(_)
+                        | TelemetryPacket::LapData(_)
+                ) {
+                    if let Some(update) = sessions.standings_changes(&header) {
+                        // No subscribers isn't an error for a change feed
+                        let _ = standings.send(update);
+                    }
+                }
+            }
+
+            let (game_events, telemetry): (Vec<_>, Vec<_>) = Event::from_packet(x, car_selection)
+                .into_iter()
+                .partition(Event::is_game_event);
+
+            // Sending only fails when nobody is subscribed
+            if sender.receiver_count() > 0 {
+                for ev in game_events.iter().chain(&telemetry) {
+                    let _ = sender.send(ev.clone());
+                }
+            }
+
+            if !game_events.is_empty() {
+                let mut sessions = sessions_clone.lock().unwrap();
+                for ev in &game_events {
+                    if let Some(message) = sessions.push(&header, ev) {
+                        // No subscribers isn't an error for live messages
+                        let _ = race_control.send(message);
+                    }
+                }
+            }
+            if !telemetry.is_empty() {
+                buffer.extend(&header, telemetry);
+            }
+        };
+
+        // The packet handler, and with it the storage writer's queue, is
+        // dropped when the task ends, letting the writer flush what is left
+        match source {
+            TelemetrySource::Udp { addr, capture } => {
+                let mut client = F1TelemetryClient::new(&addr).await?;
+                if let Some(path) = capture {
+                    client = client.with_capture(&path, tasks).await?;
+                }
 
-                        if matches!(
-                            ev_clone,
-                            Event::CarMotion(_) | Event::LapData(_) | Event::CarTelemetry(_)
-                        ) {
-                            let mut data = data_clone.lock().unwrap();
-                            (*data).push(ev_clone);
+                let client_handle = Arc::new(client);
+
+                let client_clone = client_handle.clone();
+                let errors = self.errors.clone();
+                tasks.spawn(async move {
+                    tokio::select! {
+                        _ = shutdown.cancelled() => debug!("Stopping telemetry capture..."),
+                        _ = client_clone.start(handle_packet, &errors) => (),
+                    }
+                });
+
+                Ok(Some(client_handle))
+            }
+            TelemetrySource::Replay { path, speed } => {
+                let replay = F1TelemetryReplay::new(path, speed);
+                let errors = self.errors.clone();
+                tasks.spawn(async move {
+                    tokio::select! {
+                        _ = shutdown.cancelled() => debug!("Stopping replay..."),
+                        result = replay.start(handle_packet, &errors) => {
+                            if let Err(e) = result {
+                                error!("Error replaying capture: {}", e);
+                            }
                         }
                     }
-                })
-                .await
-                .unwrap()
-        });
+                });
+
+                Ok(None)
+            }
+        }
     }
 
-    /// SSE for real-time telemetry
+    /// SSE for real-time telemetry and game events. `types` is a
+    /// comma-separated list of event types to receive, `car` keeps events of
+    /// one car plus those about the whole session (`car_index` is accepted
+    /// too, like the history endpoints name it), and `hz` caps
+    /// how often telemetry of each type and car is sent. Heartbeats report
+    /// whether the game is connected, and how many events were dropped when
+    /// the subscriber couldn't keep up, in which case they are sent even if
+    /// `types` leaves them out.
     #[oai(path = "/events", method = "get")]
-    async fn index(&self) -> EventStream<BoxStream<'static, Event>> {
+    async fn index(
+        &self,
+        types: Query<Option<String>>,
+        car: Query<Option<u8>>,
+        car_index: Query<Option<u8>>,
+        hz: Query<Option<f32>>,
+    ) -> GetEventsResponse {
+        if hz.0.is_some_and(|hz| hz.is_nan() || hz <= 0.0) {
+            return GetEventsResponse::BadRequest;
+        }
+        let car_index = match (car.0, car_index.0) {
+            (Some(car), Some(car_index)) if car != car_index => {
+                return GetEventsResponse::BadRequest
+            }
+            (car, car_index) => car.or(car_index),
+        };
+        let types = match types
+            .0
+            .as_deref()
+            .map(|types| {
+                types
+                    .split(',')
+                    .map(EventType::parse_from_parameter)
+                    .collect()
+            })
+            .transpose()
+        {
+            Ok(types) => types.unwrap_or_default(),
+            Err(_) => return GetEventsResponse::BadRequest,
+        };
+        let mut subscription = Subscription::new(types, car_index, hz.0);
+
         // Create a new receiver
         let mut receiver = self.sender.subscribe();
+        let liveness = self.liveness.clone();
+        let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
 
-        // Convert the receiver into a stream
+        // Convert the receiver into a stream of the events asked for
         let stream = async_stream::stream! {
-            while let Ok(event) = receiver.recv().await {
-                yield event;
+            loop {
+                let (event, lagged) = tokio::select! {
+                    _ = heartbeat.tick() => (liveness.heartbeat(0), false),
+                    event = receiver.recv() => match event {
+                        Ok(event) => (event, false),
+                        // The receiver has already skipped to the oldest event kept
+                        Err(RecvError::Lagged(n)) => (liveness.heartbeat(n), true),
+                        Err(RecvError::Closed) => break,
+                    },
+                };
+                // Lag is reported whatever the filter, so that subscribers
+                // know what they missed
+                if lagged || subscription.accepts(&event) {
+                    yield event;
+                }
             }
         };
 
-        EventStream::new(stream.boxed())
+        GetEventsResponse::Success(EventStream::new(stream.boxed()))
     }
 
+    /// Lap data for a session, defaulting to the current one. Times are
+    /// session times in seconds, `fields` is a comma-separated list of the
+    /// fields to return, and the `X-Next-Cursor` header holds the `cursor` of
+    /// the next page when `limit` cut the results short.
+    #[allow(clippy::too_many_arguments)]
     #[oai(path = "/get_lap_data", method = "get")]
-    async fn get_lap_data(&self, start_time: Query<Option<String>>) -> Result<GetLapDataResponse> {
-        let data = self.data.try_lock().unwrap();
+    async fn get_lap_data(
+        &self,
+        session_uid: Query<Option<String>>,
+        start_time: Query<Option<f32>>,
+        end_time: Query<Option<f32>>,
+        from_lap: Query<Option<u8>>,
+        to_lap: Query<Option<u8>>,
+        car_index: Query<Option<u8>>,
+        fields: Query<Option<String>>,
+        limit: Query<Option<usize>>,
+        cursor: Query<Option<String>>,
+    ) -> Result<GetLapDataResponse> {
+        let session_uid = match session_uid.0.map(|uid| uid.parse::<u64>()) {
+            Some(Ok(uid)) => Some(uid),
+            Some(Err(_)) => return Ok(GetLapDataResponse::BadRequest),
+            None => None,
+        };
+        let cursor = match cursor.0.as_deref().map(Cursor::parse) {
+            Some(None) => return Ok(GetLapDataResponse::BadRequest),
+            cursor => cursor.flatten(),
+        };
+        let filter = HistoryFilter {
+            start_time: start_time.0,
+            end_time: end_time.0,
+            from_lap: from_lap.0,
+            to_lap: to_lap.0,
+            car_index: car_index.0,
+        };
+        let fields = history::parse_fields(fields.0.as_deref());
 
-        let arr: Vec<LapDataEvent> = data
-            .iter()
-            .filter_map(|x| match x {
-                Event::LapData(d) => Some(d.clone()),
+        let snapshot = self.buffer.snapshot();
+        let Some(session_uid) = session_uid.or(snapshot.latest_session()) else {
+            return Ok(GetLapDataResponse::Success(
+                Json(Projected::new(Vec::new(), fields)),
+                None,
+            ));
+        };
+
+        let laps = snapshot
+            .range(session_uid, filter.start_time, filter.end_time)
+            .filter_map(|x| match &x.event {
+                Event::LapData(d)
+                    if filter.car(Some(d.metadata.car_index)) && filter.lap(d.current_lap_num) =>
+                {
+                    Some(d.clone())
+                }
                 _ => None,
-            })
-            .collect();
+            });
+        let (page, next) = history::paginate(laps, |d| d.metadata.timestamp, cursor, limit.0);
 
-        return Ok(GetLapDataResponse::Success(Json(arr)));
+        Ok(GetLapDataResponse::Success(
+            Json(Projected::new(page, fields)),
+            next,
+        ))
     }
 }
@@ -0,0 +1,165 @@
+// Query parameters shared by the history endpoints: filters, field
+// projection and cursor pagination over results ordered by session time.
+
+use poem_openapi::registry::{MetaSchemaRef, Registry};
+use poem_openapi::types::{ToJSON, Type};
+use serde_json::Value;
+use std::borrow::Cow;
+
+/// Session time, lap and car filters. Bounds are inclusive.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HistoryFilter {
+    pub start_time: Option<f32>,
+    pub end_time: Option<f32>,
+    pub from_lap: Option<u8>,
+    pub to_lap: Option<u8>,
+    pub car_index: Option<u8>,
+}
+
+impl HistoryFilter {
+    pub fn time(&self, session_time: f32) -> bool {
+        self.start_time.is_none_or(|t| session_time >= t)
+            && self.end_time.is_none_or(|t| session_time <= t)
+    }
+
+    pub fn lap(&self, lap_num: u8) -> bool {
+        self.from_lap.is_none_or(|l| lap_num >= l) && self.to_lap.is_none_or(|l| lap_num <= l)
+    }
+
+    pub fn car(&self, car_index: Option<u8>) -> bool {
+        self.car_index.is_none_or(|c| car_index == Some(c))
+    }
+}
+
+/// Where the previous page ended: the session time of its last item and how
+/// many items at exactly that time it held. Sent as `<time>:<count>`.
+#[derive(Clone, Copy, Debug)]
+pub struct Cursor {
+    session_time: f32,
+    seen: usize,
+}
+
+impl Cursor {
+    pub fn parse(value: &str) -> Option<Self> {
+        let (time, seen) = value.split_once(':')?;
+        Some(Self {
+            session_time: time.parse().ok()?,
+            seen: seen.parse().ok()?,
+        })
+    }
+}
+
+/// Take the page of `items` after `cursor`, returning it with the cursor of
+/// the next page if there are more items. `items` must be ordered by session time.
+pub fn paginate<T>(
+    items: impl IntoIterator<Item = T>,
+    session_time: impl Fn(&T) -> f32,
+    cursor: Option<Cursor>,
+    limit: Option<usize>,
+) -> (Vec<T>, Option<String>) {
+    let mut skipped = 0;
+    let mut items = items.into_iter().filter(|item| match cursor {
+        Some(c) => {
+            let t = session_time(item);
+            if t == c.session_time && skipped < c.seen {
+                skipped += 1;
+                false
+            } else {
+                t >= c.session_time
+            }
+        }
+        None => true,
+    });
+
+    let page: Vec<T> = match limit {
+        Some(limit) => items.by_ref().take(limit).collect(),
+        None => items.by_ref().collect(),
+    };
+    if items.next().is_none() {
+        return (page, None);
+    }
+
+    let next = page.last().map(|last| {
+        let t = session_time(last);
+        let mut seen = page.iter().filter(|item| session_time(item) == t).count();
+        if let Some(c) = cursor.filter(|c| c.session_time == t) {
+            seen += c.seen;
+        }
+        format!("{}:{}", t, seen)
+    });
+
+    (page, next)
+}
+
+/// Parse a comma-separated `fields` parameter
+pub fn parse_fields(value: Option<&str>) -> Option<Vec<String>> {
+    value.map(|v| {
+        v.split(',')
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .map(Into::into)
+            .collect()
+    })
+}
+
+/// A list that serialises only the requested top-level fields of each
+/// item, or all of them if no projection was asked for. It is documented
+/// with the schema of the full items.
+pub struct Projected<T> {
+    items: Vec<T>,
+    fields: Option<Vec<String>>,
+}
+
+impl<T> Projected<T> {
+    pub fn new(items: Vec<T>, fields: Option<Vec<String>>) -> Self {
+        Self { items, fields }
+    }
+}
+
+impl<T: Type> Type for Projected<T> {
+    const IS_REQUIRED: bool = true;
+
+    type RawValueType = Self;
+
+    type RawElementValueType = Self;
+
+    fn name() -> Cow<'static, str> {
+        Vec::<T>::name()
+    }
+
+    fn schema_ref() -> MetaSchemaRef {
+        Vec::<T>::schema_ref()
+    }
+
+    fn register(registry: &mut Registry) {
+        Vec::<T>::register(registry)
+    }
+
+    fn as_raw_value(&self) -> Option<&Self::RawValueType> {
+        Some(self)
+    }
+
+    fn raw_element_iter<'a>(
+        &'a self,
+    ) -> Box<dyn Iterator<Item = &'a Self::RawElementValueType> + 'a> {
+        Box::new(self.as_raw_value().into_iter())
+    }
+}
+
+impl<T: ToJSON> ToJSON for Projected<T> {
+    fn to_json(&self) -> Option<Value> {
+        let items = self
+            .items
+            .iter()
+            .filter_map(|item| {
+                let mut value = item.to_json()?;
+                if let (Some(fields), Value::Object(map)) = (&self.fields, &mut value) {
+                    map.retain(|key, _| fields.contains(key));
+                }
+                Some(value)
+            })
+            .collect();
+
+        Some(Value::Array(items))
+    }
+}
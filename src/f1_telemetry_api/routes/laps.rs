@@ -0,0 +1,99 @@
+use crate::f1_telemetry_api::laps::CompletedLap;
+use crate::f1_telemetry_api::routes::history::{self, Cursor, HistoryFilter, Projected};
+use crate::f1_telemetry_api::sessions::SessionManager;
+use crate::f1_telemetry_storage::Storage;
+use poem::error::InternalServerError;
+use poem::Result;
+use poem_openapi::param::Query;
+use poem_openapi::payload::Json;
+use poem_openapi::{ApiResponse, OpenApi};
+use std::sync::{Arc, Mutex};
+
+pub struct LapsApi {
+    sessions: Arc<Mutex<SessionManager>>,
+    storage: Storage,
+}
+
+#[derive(ApiResponse)]
+enum GetLapsResponse {
+    #[oai(status = 200)]
+    Success(
+        Json<Projected<CompletedLap>>,
+        #[oai(header = "X-Next-Cursor")] Option<String>,
+    ),
+    #[oai(status = 400)]
+    BadRequest,
+}
+
+#[OpenApi]
+impl LapsApi {
+    pub fn new(sessions: Arc<Mutex<SessionManager>>, storage: Storage) -> Self {
+        Self { sessions, storage }
+    }
+
+    /// Completed laps for a session, defaulting to the current one. Takes the
+    /// same filter, `fields` and pagination parameters as `/get_lap_data`,
+    /// with `start_time` and `end_time` bounding when laps were completed.
+    #[allow(clippy::too_many_arguments)]
+    #[oai(path = "/laps", method = "get")]
+    async fn get_laps(
+        &self,
+        session_uid: Query<Option<String>>,
+        start_time: Query<Option<f32>>,
+        end_time: Query<Option<f32>>,
+        from_lap: Query<Option<u8>>,
+        to_lap: Query<Option<u8>>,
+        car_index: Query<Option<u8>>,
+        fields: Query<Option<String>>,
+        limit: Query<Option<usize>>,
+        cursor: Query<Option<String>>,
+    ) -> Result<GetLapsResponse> {
+        let session_uid = match session_uid.0.map(|uid| uid.parse::<u64>()) {
+            Some(Ok(uid)) => Some(uid),
+            Some(Err(_)) => return Ok(GetLapsResponse::BadRequest),
+            None => None,
+        };
+        let cursor = match cursor.0.as_deref().map(Cursor::parse) {
+            Some(None) => return Ok(GetLapsResponse::BadRequest),
+            cursor => cursor.flatten(),
+        };
+        let filter = HistoryFilter {
+            start_time: start_time.0,
+            end_time: end_time.0,
+            from_lap: from_lap.0,
+            to_lap: to_lap.0,
+            car_index: car_index.0,
+        };
+        let fields = history::parse_fields(fields.0.as_deref());
+
+        let (session_uid, live) = {
+            let sessions = self.sessions.lock().unwrap();
+            let session_uid = session_uid.or(sessions.current());
+            (session_uid, session_uid.and_then(|uid| sessions.laps(uid)))
+        };
+
+        let laps = match (live, session_uid) {
+            (Some(laps), _) => laps,
+            // Sessions from earlier runs, or with more laps than are kept in
+            // memory, are read from storage
+            (None, Some(uid)) => self
+                .storage
+                .laps(uid, filter.car_index)
+                .await
+                .map_err(InternalServerError)?,
+            (None, None) => Vec::new(),
+        };
+
+        let laps = laps.into_iter().filter(|lap| {
+            filter.time(lap.session_time)
+                && filter.lap(lap.lap_num)
+                && filter.car(Some(lap.car_index))
+        });
+        let (page, next) = history::paginate(laps, |lap| lap.session_time, cursor, limit.0);
+
+        Ok(GetLapsResponse::Success(
+            Json(Projected::new(page, fields)),
+            next,
+        ))
+    }
+}
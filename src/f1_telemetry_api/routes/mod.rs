@@ -1 +1,9 @@
+pub mod admin;
 pub mod events;
+pub mod history;
+pub mod laps;
+pub mod race_control;
+pub mod reference;
+pub mod sessions;
+pub mod standings;
+pub mod websocket;
@@ -0,0 +1,135 @@
+use crate::f1_telemetry_api::events::{HeartbeatEvent, Liveness, HEARTBEAT_INTERVAL};
+use crate::f1_telemetry_api::race_control::RaceControlMessage;
+use crate::f1_telemetry_api::routes::history::{self, Cursor, HistoryFilter, Projected};
+use crate::f1_telemetry_api::sessions::SessionManager;
+use futures_util::{stream::BoxStream, StreamExt};
+use poem::Result;
+use poem_openapi::param::Query;
+use poem_openapi::payload::{EventStream, Json};
+use poem_openapi::{ApiResponse, OpenApi, Union};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::debug;
+
+pub struct RaceControlApi {
+    sender: Arc<broadcast::Sender<RaceControlMessage>>,
+    sessions: Arc<Mutex<SessionManager>>,
+    liveness: Arc<Liveness>,
+}
+
+/// A race control message, or a heartbeat with the number of messages a
+/// subscriber that fell behind missed
+#[derive(Union, Clone, Debug)]
+#[oai(one_of)]
+pub enum RaceControlUpdate {
+    Message(RaceControlMessage),
+    Heartbeat(HeartbeatEvent),
+}
+
+#[derive(ApiResponse)]
+enum GetRaceControlResponse {
+    #[oai(status = 200)]
+    Success(
+        Json<Projected<RaceControlMessage>>,
+        #[oai(header = "X-Next-Cursor")] Option<String>,
+    ),
+    #[oai(status = 400)]
+    BadRequest,
+}
+
+#[OpenApi]
+impl RaceControlApi {
+    pub fn new(
+        capacity: usize,
+        sessions: Arc<Mutex<SessionManager>>,
+        liveness: Arc<Liveness>,
+    ) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+
+        Self {
+            sender: Arc::new(sender),
+            sessions,
+            liveness,
+        }
+    }
+
+    /// Channel race control messages are broadcast on as they happen
+    pub fn sender(&self) -> Arc<broadcast::Sender<RaceControlMessage>> {
+        self.sender.clone()
+    }
+
+    /// Race control messages of a session so far, defaulting to the current
+    /// one. Takes the filter, `fields` and pagination parameters of
+    /// `/get_lap_data`, except for laps. `car_index` matches either car involved.
+    #[allow(clippy::too_many_arguments)]
+    #[oai(path = "/race_control", method = "get")]
+    async fn get_race_control(
+        &self,
+        session_uid: Query<Option<String>>,
+        start_time: Query<Option<f32>>,
+        end_time: Query<Option<f32>>,
+        car_index: Query<Option<u8>>,
+        fields: Query<Option<String>>,
+        limit: Query<Option<usize>>,
+        cursor: Query<Option<String>>,
+    ) -> Result<GetRaceControlResponse> {
+        let session_uid = match session_uid.0.map(|uid| uid.parse::<u64>()) {
+            Some(Ok(uid)) => Some(uid),
+            Some(Err(_)) => return Ok(GetRaceControlResponse::BadRequest),
+            None => None,
+        };
+        let cursor = match cursor.0.as_deref().map(Cursor::parse) {
+            Some(None) => return Ok(GetRaceControlResponse::BadRequest),
+            cursor => cursor.flatten(),
+        };
+        let filter = HistoryFilter {
+            start_time: start_time.0,
+            end_time: end_time.0,
+            car_index: car_index.0,
+            ..Default::default()
+        };
+        let fields = history::parse_fields(fields.0.as_deref());
+
+        let messages = {
+            let sessions = self.sessions.lock().unwrap();
+            session_uid
+                .or(sessions.current())
+                .map(|uid| sessions.race_control(uid))
+                .unwrap_or_default()
+        };
+
+        let messages = messages.into_iter().filter(|m| {
+            filter.time(m.timestamp) && (filter.car(m.car_index) || filter.car(m.other_car_index))
+        });
+        let (page, next) = history::paginate(messages, |m| m.timestamp, cursor, limit.0);
+
+        Ok(GetRaceControlResponse::Success(
+            Json(Projected::new(page, fields)),
+            next,
+        ))
+    }
+
+    /// SSE for race control messages of the current session as they happen.
+    /// A subscriber that falls behind is sent a heartbeat with the number of
+    /// messages it missed, which are still in the history.
+    #[oai(path = "/race_control/stream", method = "get")]
+    async fn stream(&self) -> EventStream<BoxStream<'static, RaceControlUpdate>> {
+        let mut receiver = self.sender.subscribe();
+        let liveness = self.liveness.clone();
+
+        let stream = async_stream::stream! {
+            loop {
+                match receiver.recv().await {
+                    Ok(message) => yield RaceControlUpdate::Message(message),
+                    Err(RecvError::Lagged(n)) => {
+                        debug!("Race control subscriber missed {} messages", n);
+                        yield RaceControlUpdate::Heartbeat(liveness.status(n));
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        };
+
+        EventStream::new(stream.boxed()).keep_alive(HEARTBEAT_INTERVAL)
+    }
+}
@@ -0,0 +1,36 @@
+use crate::f1_telemetry_api::reference::{
+    self, DriverInfo, NationalityInfo, SessionTypeInfo, TeamInfo, TrackInfo,
+};
+use poem_openapi::payload::Json;
+use poem_openapi::OpenApi;
+
+/// Static lookup tables, so clients don't have to hard-code the game's ids
+pub struct ReferenceApi;
+
+#[OpenApi]
+impl ReferenceApi {
+    #[oai(path = "/reference/tracks", method = "get")]
+    async fn tracks(&self) -> Json<Vec<TrackInfo>> {
+        Json(reference::tracks())
+    }
+
+    #[oai(path = "/reference/teams", method = "get")]
+    async fn teams(&self) -> Json<Vec<TeamInfo>> {
+        Json(reference::teams())
+    }
+
+    #[oai(path = "/reference/drivers", method = "get")]
+    async fn drivers(&self) -> Json<Vec<DriverInfo>> {
+        Json(reference::drivers())
+    }
+
+    #[oai(path = "/reference/nationalities", method = "get")]
+    async fn nationalities(&self) -> Json<Vec<NationalityInfo>> {
+        Json(reference::nationalities())
+    }
+
+    #[oai(path = "/reference/session_types", method = "get")]
+    async fn session_types(&self) -> Json<Vec<SessionTypeInfo>> {
+        Json(reference::session_types())
+    }
+}
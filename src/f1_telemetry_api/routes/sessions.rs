@@ -0,0 +1,84 @@
+use crate::f1_telemetry_api::sessions::{SessionManager, SessionSummary};
+use crate::f1_telemetry_storage::Storage;
+use poem::error::InternalServerError;
+use poem::Result;
+use poem_openapi::param::Path;
+use poem_openapi::payload::Json;
+use poem_openapi::{ApiResponse, OpenApi};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+pub struct SessionsApi {
+    sessions: Arc<Mutex<SessionManager>>,
+    storage: Storage,
+}
+
+#[derive(ApiResponse)]
+enum GetSessionsResponse {
+    #[oai(status = 200)]
+    Success(Json<Vec<SessionSummary>>),
+}
+
+#[derive(ApiResponse)]
+enum GetSessionResponse {
+    #[oai(status = 200)]
+    Success(Json<SessionSummary>),
+    #[oai(status = 400)]
+    BadRequest,
+    #[oai(status = 404)]
+    NotFound,
+}
+
+#[OpenApi]
+impl SessionsApi {
+    pub fn new(sessions: Arc<Mutex<SessionManager>>, storage: Storage) -> Self {
+        Self { sessions, storage }
+    }
+
+    /// Sessions seen by this process and those stored from earlier runs
+    #[oai(path = "/sessions", method = "get")]
+    async fn get_sessions(&self) -> Result<GetSessionsResponse> {
+        let stored = self.storage.sessions().await.map_err(InternalServerError)?;
+
+        // Sessions from this run are more up to date than what has been stored
+        let live: HashMap<String, SessionSummary> = self
+            .sessions
+            .lock()
+            .unwrap()
+            .summaries()
+            .into_iter()
+            .map(|s| (s.session_uid.clone(), s))
+            .collect();
+
+        let mut sessions: Vec<SessionSummary> = stored
+            .into_iter()
+            .filter(|s| !live.contains_key(&s.session_uid))
+            .collect();
+        sessions.extend(live.into_values());
+        sessions.sort_by(|a, b| b.last_seen_at.cmp(&a.last_seen_at));
+
+        Ok(GetSessionsResponse::Success(Json(sessions)))
+    }
+
+    #[oai(path = "/sessions/:uid", method = "get")]
+    async fn get_session(&self, uid: Path<String>) -> Result<GetSessionResponse> {
+        let Ok(uid) = uid.parse::<u64>() else {
+            return Ok(GetSessionResponse::BadRequest);
+        };
+
+        let live = self.sessions.lock().unwrap().summary(uid);
+        if let Some(session) = live {
+            return Ok(GetSessionResponse::Success(Json(session)));
+        }
+
+        match self
+            .storage
+            .session(uid)
+            .await
+            .map_err(InternalServerError)?
+        {
+            Some(session) => Ok(GetSessionResponse::Success(Json(session))),
+            None => Ok(GetSessionResponse::NotFound),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::f1_telemetry_api::events::HEARTBEAT_INTERVAL;
+use crate::f1_telemetry_api::sessions::SessionManager;
+use crate::f1_telemetry_api::standings::{Standing, StandingsUpdate};
+use futures_util::{stream::BoxStream, StreamExt};
+use poem::Result;
+use poem_openapi::param::Query;
+use poem_openapi::payload::{EventStream, Json};
+use poem_openapi::{ApiResponse, OpenApi};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+pub struct StandingsApi {
+    sender: Arc<broadcast::Sender<StandingsUpdate>>,
+    sessions: Arc<Mutex<SessionManager>>,
+}
+
+#[derive(ApiResponse)]
+enum GetStandingsResponse {
+    #[oai(status = 200)]
+    Success(Json<Vec<Standing>>),
+    #[oai(status = 400)]
+    BadRequest,
+}
+
+// Every row of the current session's timing tower
+pub fn snapshot(sessions: &Mutex<SessionManager>) -> Option<StandingsUpdate> {
+    let sessions = sessions.lock().unwrap();
+    sessions.current().map(|uid| StandingsUpdate {
+        session_uid: uid.to_string(),
+        standings: sessions.standings(uid),
+        removed: Vec::new(),
+    })
+}
+
+#[OpenApi]
+impl StandingsApi {
+    pub fn new(capacity: usize, sessions: Arc<Mutex<SessionManager>>) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+
+        Self {
+            sender: Arc::new(sender),
+            sessions,
+        }
+    }
+
+    /// Channel changes to the timing tower are broadcast on
+    pub fn sender(&self) -> Arc<broadcast::Sender<StandingsUpdate>> {
+        self.sender.clone()
+    }
+
+    /// Timing tower of a session, defaulting to the current one
+    #[oai(path = "/standings", method = "get")]
+    async fn get_standings(
+        &self,
+        session_uid: Query<Option<String>>,
+    ) -> Result<GetStandingsResponse> {
+        let session_uid = match session_uid.0.map(|uid| uid.parse::<u64>()) {
+            Some(Ok(uid)) => Some(uid),
+            Some(Err(_)) => return Ok(GetStandingsResponse::BadRequest),
+            None => None,
+        };
+
+        let sessions = self.sessions.lock().unwrap();
+        let standings = session_uid
+            .or(sessions.current())
+            .map(|uid| sessions.standings(uid))
+            .unwrap_or_default();
+
+        Ok(GetStandingsResponse::Success(Json(standings)))
+    }
+
+    /// SSE of the timing tower. The first message holds every row of the
+    /// current session, the following ones only the rows that changed and
+    /// the car indices of those that were removed. Rows whose only change is
+    /// the gaps to the cars ahead are sent at most once a second of session
+    /// time. A subscriber that falls behind is sent every row again.
+    #[oai(path = "/standings/stream", method = "get")]
+    async fn stream(&self) -> EventStream<BoxStream<'static, StandingsUpdate>> {
+        let mut receiver = self.sender.subscribe();
+        let sessions = self.sessions.clone();
+
+        let stream = async_stream::stream! {
+            if let Some(snapshot) = snapshot(&sessions) {
+                yield snapshot;
+            }
+            loop {
+                match receiver.recv().await {
+                    Ok(update) => yield update,
+                    Err(RecvError::Lagged(_)) => {
+                        if let Some(snapshot) = snapshot(&sessions) {
+                            yield snapshot;
+                        }
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        };
+
+        EventStream::new(stream.boxed()).keep_alive(HEARTBEAT_INTERVAL)
+    }
+}
@@ -0,0 +1,274 @@
+use crate::f1_telemetry_api::events::{
+    Event, EventType, Liveness, Subscription, HEARTBEAT_INTERVAL,
+};
+use crate::f1_telemetry_api::race_control::RaceControlMessage;
+use crate::f1_telemetry_api::routes::standings;
+use crate::f1_telemetry_api::sessions::SessionManager;
+use crate::f1_telemetry_api::standings::StandingsUpdate;
+use futures_util::{SinkExt, StreamExt};
+use poem::web::websocket::{Message, WebSocket};
+use poem::web::{Data, Query};
+use poem::{handler, IntoResponse};
+use poem_openapi::types::{ParseFromParameter, ToJSON};
+use serde::{Deserialize, Deserializer};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+use tracing::debug;
+
+// Channels besides the event types
+const RACE_CONTROL: &str = "race_control";
+const STANDINGS: &str = "standings";
+
+/// Sources the WebSocket endpoint forwards to its clients
+pub struct WebSocketApi {
+    events: Arc<broadcast::Sender<Event>>,
+    standings: Arc<broadcast::Sender<StandingsUpdate>>,
+    race_control: Arc<broadcast::Sender<RaceControlMessage>>,
+    sessions: Arc<Mutex<SessionManager>>,
+    liveness: Arc<Liveness>,
+}
+
+impl WebSocketApi {
+    pub fn new(
+        events: Arc<broadcast::Sender<Event>>,
+        standings: Arc<broadcast::Sender<StandingsUpdate>>,
+        race_control: Arc<broadcast::Sender<RaceControlMessage>>,
+        sessions: Arc<Mutex<SessionManager>>,
+        liveness: Arc<Liveness>,
+    ) -> Self {
+        Self {
+            events,
+            standings,
+            race_control,
+            sessions,
+            liveness,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Format {
+    #[default]
+    Json,
+    // MessagePack, with the same structure as the JSON messages
+    Binary,
+}
+
+#[derive(Deserialize)]
+struct ConnectParams {
+    #[serde(default)]
+    format: Format,
+}
+
+/// Messages clients send, as JSON text frames
+#[derive(Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum ClientMessage {
+    /// Start receiving `channels`. `car_index` and `hz` replace the filters
+    /// of the connection when given, as for `/events`, and clear them when
+    /// `null`.
+    Subscribe {
+        channels: Vec<String>,
+        #[serde(default, deserialize_with = "nullable")]
+        car_index: Option<Option<u8>>,
+        #[serde(default, deserialize_with = "nullable")]
+        hz: Option<Option<f32>>,
+    },
+    Unsubscribe {
+        channels: Vec<String>,
+    },
+}
+
+// Tells a `null` field, `Some(None)`, apart from a missing one, `None`
+fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
+}
+
+// Channels and filters of one connection
+#[derive(Default)]
+struct Channels {
+    filter: Subscription,
+    race_control: bool,
+    standings: bool,
+}
+
+impl Channels {
+    fn apply(&mut self, message: ClientMessage) -> Result<(), String> {
+        let (channels, subscribe) = match message {
+            ClientMessage::Subscribe {
+                channels,
+                car_index,
+                hz,
+            } => {
+                if hz.flatten().is_some_and(|hz| hz.is_nan() || hz <= 0.0) {
+                    return Err("hz must be positive".into());
+                }
+                if let Some(car_index) = car_index {
+                    self.filter.car_index = car_index;
+                }
+                if let Some(hz) = hz {
+                    self.filter.hz = hz;
+                }
+                (channels, true)
+            }
+            ClientMessage::Unsubscribe { channels } => (channels, false),
+        };
+
+        for channel in channels {
+            match channel.as_str() {
+                RACE_CONTROL => self.race_control = subscribe,
+                STANDINGS => self.standings = subscribe,
+                name => {
+                    let event_type = EventType::parse_from_parameter(name)
+                        .map_err(|_| format!("Unknown channel: {}", name))?;
+                    self.filter.types.retain(|t| *t != event_type);
+                    if subscribe {
+                        self.filter.types.push(event_type);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn names(&self) -> Vec<Value> {
+        let mut names: Vec<Value> = self
+            .filter
+            .types
+            .iter()
+            .filter_map(|t| t.to_json())
+            .collect();
+        names.extend(self.race_control.then(|| RACE_CONTROL.into()));
+        names.extend(self.standings.then(|| STANDINGS.into()));
+        names
+    }
+}
+
+// Heartbeat for clients subscribed to the heartbeat channel, or to any
+// event type when it reports events they missed
+fn heartbeat_message(channels: &mut Channels, liveness: &Liveness, dropped: u64) -> Option<Value> {
+    let heartbeat = liveness.heartbeat(dropped);
+    let subscribed = !channels.filter.types.is_empty();
+    (subscribed && (dropped > 0 || channels.filter.accepts(&heartbeat))).then(|| {
+        json!({
+            "channel": heartbeat.event_type().to_json(),
+            "data": heartbeat.to_json(),
+        })
+    })
+}
+
+fn standings_message(update: &StandingsUpdate) -> Value {
+    json!({ "channel": STANDINGS, "data": update.to_json() })
+}
+
+fn encode(format: Format, message: &Value) -> Message {
+    match format {
+        Format::Json => Message::Text(message.to_string()),
+        Format::Binary => {
+            Message::Binary(rmp_serde::to_vec_named(message).expect("JSON values encode"))
+        }
+    }
+}
+
+/// Bidirectional real-time feed. Clients send
+/// `{"action": "subscribe", "channels": [...]}` or `"unsubscribe"` to pick
+/// from the event types plus `race_control` and `standings`, and receive
+/// `{"channel": ..., "data": ...}` messages, in MessagePack with `?format=binary`.
+/// Subscribing to `standings` sends every row of the timing tower first.
+#[handler]
+pub fn websocket(
+    ws: WebSocket,
+    Query(params): Query<ConnectParams>,
+    api: Data<&Arc<WebSocketApi>>,
+) -> impl IntoResponse {
+    let api = api.0.clone();
+    let format = params.format;
+
+    ws.on_upgrade(move |socket| async move {
+        let (mut sink, mut stream) = socket.split();
+        let mut events = api.events.subscribe();
+        let mut standings = api.standings.subscribe();
+        let mut race_control = api.race_control.subscribe();
+        let mut channels = Channels::default();
+        let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+
+        loop {
+            let reply = tokio::select! {
+                message = stream.next() => match message {
+                    Some(Ok(Message::Text(text))) => {
+                        let had_standings = channels.standings;
+                        let result = serde_json::from_str::<ClientMessage>(&text)
+                            .map_err(|e| format!("Invalid message: {}", e))
+                            .and_then(|message| channels.apply(message));
+                        let reply = match result {
+                            Ok(()) => json!({ "channel": "subscriptions", "data": channels.names() }),
+                            Err(e) => json!({ "channel": "error", "data": e }),
+                        };
+                        if sink.send(encode(format, &reply)).await.is_err() {
+                            break;
+                        }
+
+                        // New standings subscribers start from every row
+                        (channels.standings && !had_standings)
+                            .then(|| standings::snapshot(&api.sessions))
+                            .flatten()
+                            .map(|snapshot| standings_message(&snapshot))
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => None,
+                },
+                _ = heartbeat.tick() => heartbeat_message(&mut channels, &api.liveness, 0),
+                event = events.recv() => match event {
+                    Ok(event) if !channels.filter.types.is_empty() && channels.filter.accepts(&event) => {
+                        Some(json!({
+                            "channel": event.event_type().to_json(),
+                            "data": event.to_json(),
+                        }))
+                    }
+                    Ok(_) => None,
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        debug!("WebSocket client missed {} events", n);
+                        heartbeat_message(&mut channels, &api.liveness, n)
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                update = standings.recv() => match update {
+                    Ok(update) if channels.standings => Some(standings_message(&update)),
+                    // Changes were missed, so start over from every row
+                    Err(broadcast::error::RecvError::Lagged(_)) if channels.standings => {
+                        standings::snapshot(&api.sessions).map(|snapshot| standings_message(&snapshot))
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => None,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+                message = race_control.recv() => match message {
+                    Ok(message) if channels.race_control => {
+                        Some(json!({ "channel": RACE_CONTROL, "data": message.to_json() }))
+                    }
+                    // As on the SSE stream, a heartbeat tells how many
+                    // messages were missed. They are still in the history.
+                    Err(broadcast::error::RecvError::Lagged(n)) if channels.race_control => {
+                        debug!("WebSocket client missed {} race control messages", n);
+                        let heartbeat = api.liveness.status(n);
+                        Some(json!({ "channel": RACE_CONTROL, "data": heartbeat.to_json() }))
+                    }
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => None,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            };
+
+            if let Some(reply) = reply {
+                if sink.send(encode(format, &reply)).await.is_err() {
+                    break;
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,262 @@
+use crate::f1_telemetry_api::events::Event;
+use crate::f1_telemetry_api::laps::CompletedLap;
+use crate::f1_telemetry_api::race_control::{self, RaceControlMessage};
+use crate::f1_telemetry_api::reference;
+use crate::f1_telemetry_api::standings::{Standing, StandingsUpdate, TimingTower};
+use crate::f1_telemetry_client::packets::car_status::PacketCarStatusData;
+use crate::f1_telemetry_client::packets::enums::{Formula, SessionType, Track, Weather};
+use crate::f1_telemetry_client::packets::header::PacketHeader;
+use crate::f1_telemetry_client::packets::lap_data::PacketLapData;
+use crate::f1_telemetry_client::packets::participants::PacketParticipantsData;
+use crate::f1_telemetry_client::packets::session_data::PacketSessionData;
+use chrono::Utc;
+use poem_openapi::Object;
+use std::collections::{HashMap, VecDeque};
+
+// Sessions kept in memory. Once there are more, the one seen least recently
+// is forgotten, though its laps are still in storage.
+const MAX_SESSIONS: usize = 16;
+
+// Laps kept in memory per session, a long race of a full grid. Past that
+// the session's laps are read from storage instead.
+const MAX_LAPS: usize = 2048;
+
+// Race control messages kept per session, the oldest are dropped past that
+const MAX_RACE_CONTROL_MESSAGES: usize = 1024;
+
+#[derive(Object, Clone, Debug)]
+pub struct SessionSummary {
+    // Serialised as a string since u64 doesn't fit in a JavaScript number
+    pub session_uid: String,
+    pub is_current: bool,
+    pub track: Option<Track>,
+    pub session_type: Option<SessionType>,
+    pub formula: Option<Formula>,
+    pub weather: Option<Weather>,
+    pub total_laps: Option<u8>,
+    pub track_length: Option<u16>,
+    pub first_seen_at: String,
+    pub last_seen_at: String,
+}
+
+impl SessionSummary {
+    fn new(session_uid: u64) -> Self {
+        let now = Utc::now().to_rfc3339();
+        Self {
+            session_uid: session_uid.to_string(),
+            is_current: false,
+            track: None,
+            session_type: None,
+            formula: None,
+            weather: None,
+            total_laps: None,
+            track_length: None,
+            first_seen_at: now.clone(),
+            last_seen_at: now,
+        }
+    }
+}
+
+struct SessionState {
+    summary: SessionSummary,
+    // Race control messages of the game events, tagged with the frame
+    // identifier of their packet. Drivers are named when the event arrives,
+    // since the participants can change later on.
+    race_control: VecDeque<(u32, RaceControlMessage)>,
+    laps: VecDeque<CompletedLap>,
+    // Whether laps were dropped for going over MAX_LAPS
+    laps_trimmed: bool,
+    driver_names: Vec<String>, // Indexed by car, empty until Participants arrives
+    tower: TimingTower,
+}
+
+/// Groups incoming data by `PacketHeader::session_uid`, so that quitting to
+/// the menu and starting again doesn't mix the two sessions together
+#[derive(Default)]
+pub struct SessionManager {
+    sessions: HashMap<u64, SessionState>,
+    current: Option<u64>,
+}
+
+impl SessionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the session a packet belongs to and make it the current one
+    pub fn observe(&mut self, header: &PacketHeader) {
+        let state = self
+            .sessions
+            .entry(header.session_uid)
+            .or_insert_with(|| SessionState {
+                summary: SessionSummary::new(header.session_uid),
+                race_control: VecDeque::new(),
+                laps: VecDeque::new(),
+                laps_trimmed: false,
+                driver_names: Vec::new(),
+                tower: TimingTower::default(),
+            });
+
+        if self.current != Some(header.session_uid) {
+            state.summary.last_seen_at = Utc::now().to_rfc3339();
+            self.current = Some(header.session_uid);
+        }
+
+        if self.sessions.len() > MAX_SESSIONS {
+            let oldest = self
+                .sessions
+                .iter()
+                .filter(|(&uid, _)| uid != header.session_uid)
+                .min_by(|(_, a), (_, b)| a.summary.last_seen_at.cmp(&b.summary.last_seen_at))
+                .map(|(&uid, _)| uid);
+            if let Some(uid) = oldest {
+                self.sessions.remove(&uid);
+            }
+        }
+    }
+
+    pub fn update_metadata(&mut self, header: &PacketHeader, data: &PacketSessionData) {
+        if let Some(state) = self.sessions.get_mut(&header.session_uid) {
+            let summary = &mut state.summary;
+            summary.track = Some(data.track_id);
+            summary.session_type = Some(data.session_type);
+            summary.formula = Some(data.formula);
+            summary.weather = Some(data.weather);
+            summary.total_laps = Some(data.total_laps);
+            summary.track_length = Some(data.track_length);
+            summary.last_seen_at = Utc::now().to_rfc3339();
+        }
+    }
+
+    /// Resolve driver names, preferring the name shown in game over the
+    /// Driver IDs appendix, which doesn't cover human players
+    pub fn update_participants(&mut self, header: &PacketHeader, data: &PacketParticipantsData) {
+        if let Some(state) = self.sessions.get_mut(&header.session_uid) {
+            state.driver_names = data
+                .participants
+                .iter()
+                .enumerate()
+                .map(|(i, p)| {
+                    if !p.name.is_empty() {
+                        p.name.clone()
+                    } else {
+                        reference::driver_name(p.driver_id)
+                            .map(Into::into)
+                            .unwrap_or_else(|| format!("Car {}", i))
+                    }
+                })
+                .collect();
+            state.tower.on_participants(data);
+        }
+    }
+
+    pub fn update_lap_data(&mut self, header: &PacketHeader, data: &PacketLapData) {
+        if let Some(state) = self.sessions.get_mut(&header.session_uid) {
+            state.tower.on_lap_data(data);
+        }
+    }
+
+    pub fn update_car_status(&mut self, header: &PacketHeader, data: &PacketCarStatusData) {
+        if let Some(state) = self.sessions.get_mut(&header.session_uid) {
+            state.tower.on_car_status(data);
+        }
+    }
+
+    /// Timing tower of a session in race order
+    pub fn standings(&self, session_uid: u64) -> Vec<Standing> {
+        self.sessions
+            .get(&session_uid)
+            .map(|state| state.tower.standings(&state.driver_names))
+            .unwrap_or_default()
+    }
+
+    /// Rows of a session's timing tower that changed since the last call,
+    /// or `None` if nothing did
+    pub fn standings_changes(&mut self, header: &PacketHeader) -> Option<StandingsUpdate> {
+        let session_uid = header.session_uid;
+        let state = self.sessions.get_mut(&session_uid)?;
+        let (standings, removed) = state
+            .tower
+            .changes(&state.driver_names, header.session_time);
+        (!standings.is_empty() || !removed.is_empty()).then(|| StandingsUpdate {
+            session_uid: session_uid.to_string(),
+            standings,
+            removed,
+        })
+    }
+
+    /// Record the race control message of a game event, if it makes one
+    pub fn push(&mut self, header: &PacketHeader, event: &Event) -> Option<RaceControlMessage> {
+        let state = self.sessions.get_mut(&header.session_uid)?;
+        let message = race_control::message(event, &state.driver_names)?;
+        if state.race_control.len() == MAX_RACE_CONTROL_MESSAGES {
+            state.race_control.pop_front();
+        }
+        state
+            .race_control
+            .push_back((header.frame_identifier, message.clone()));
+        Some(message)
+    }
+
+    /// Drop race control messages and laps recorded after the frame a flashback rewound to
+    pub fn discard_after(&mut self, session_uid: u64, frame_identifier: u32) {
+        if let Some(state) = self.sessions.get_mut(&session_uid) {
+            state
+                .race_control
+                .retain(|(frame, _)| *frame <= frame_identifier);
+            state
+                .laps
+                .retain(|lap| lap.frame_identifier <= frame_identifier);
+        }
+    }
+
+    pub fn push_lap(&mut self, session_uid: u64, lap: CompletedLap) {
+        if let Some(state) = self.sessions.get_mut(&session_uid) {
+            if state.laps.len() == MAX_LAPS {
+                state.laps.pop_front();
+                state.laps_trimmed = true;
+            }
+            state.laps.push_back(lap);
+        }
+    }
+
+    pub fn current(&self) -> Option<u64> {
+        self.current
+    }
+
+    /// Laps completed in a session, in the order they were driven, or `None`
+    /// if the session isn't in memory or some of its laps were dropped
+    pub fn laps(&self, session_uid: u64) -> Option<Vec<CompletedLap>> {
+        self.sessions
+            .get(&session_uid)
+            .filter(|state| !state.laps_trimmed)
+            .map(|state| state.laps.iter().cloned().collect())
+    }
+
+    /// Race control messages of a session, ordered by session time
+    pub fn race_control(&self, session_uid: u64) -> Vec<RaceControlMessage> {
+        let mut messages: Vec<_> = self
+            .sessions
+            .get(&session_uid)
+            .into_iter()
+            .flat_map(|state| &state.race_control)
+            .map(|(_, message)| message.clone())
+            .collect();
+        messages.sort_by(|a, b| a.timestamp.total_cmp(&b.timestamp));
+        messages
+    }
+
+    pub fn summary(&self, session_uid: u64) -> Option<SessionSummary> {
+        self.sessions.get(&session_uid).map(|state| SessionSummary {
+            is_current: self.current == Some(session_uid),
+            ..state.summary.clone()
+        })
+    }
+
+    pub fn summaries(&self) -> Vec<SessionSummary> {
+        self.sessions
+            .keys()
+            .filter_map(|&uid| self.summary(uid))
+            .collect()
+    }
+}
@@ -0,0 +1,243 @@
+use crate::f1_telemetry_api::reference;
+use crate::f1_telemetry_client::packets::car_status::PacketCarStatusData;
+use crate::f1_telemetry_client::packets::enums::{
+    ActualTyreCompound, DriverStatus, PitStatus, ResultStatus, VisualTyreCompound,
+};
+use crate::f1_telemetry_client::packets::lap_data::{LapData, PacketLapData};
+use crate::f1_telemetry_client::packets::participants::PacketParticipantsData;
+use poem_openapi::Object;
+
+// Session time in seconds between two updates of the gaps between cars,
+// which change with nearly every lap data packet
+const GAP_INTERVAL: f32 = 1.0;
+
+/// One row of the timing tower
+#[derive(Object, Clone, Debug, PartialEq)]
+pub struct Standing {
+    pub position: u8,
+    pub car_index: u8,
+    pub driver_name: String,
+    pub team_id: Option<u8>,
+    pub team_name: Option<String>,
+    pub race_number: Option<u8>,
+    pub current_lap_num: u8,
+    pub last_lap_time_in_ms: u32,
+    pub delta_to_car_in_front_in_ms: u32,
+    pub delta_to_race_leader_in_ms: u32,
+    pub pit_status: PitStatus,
+    pub num_pit_stops: u8,
+    pub penalties: u8, // Accumulated time penalties in seconds
+    pub total_warnings: u8,
+    pub num_unserved_drive_through_pens: u8,
+    pub num_unserved_stop_go_pens: u8,
+    // Tyre details come from Car Status, which may not have been seen yet
+    pub tyre_actual_compound: Option<ActualTyreCompound>,
+    pub tyre_visual_compound: Option<VisualTyreCompound>,
+    pub tyre_age_laps: Option<u8>,
+    pub driver_status: DriverStatus,
+    pub result_status: ResultStatus,
+}
+
+/// Rows of the timing tower that changed, or all of them at the start of a stream
+#[derive(Object, Clone, Debug)]
+pub struct StandingsUpdate {
+    // Serialised as a string since u64 doesn't fit in a JavaScript number
+    pub session_uid: String,
+    pub standings: Vec<Standing>,
+    // Car indices of the rows that left the tower, empty when all rows are sent
+    pub removed: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct CarEntry {
+    lap: Option<LapData>,
+    team_id: Option<u8>,
+    race_number: Option<u8>,
+    tyre_actual_compound: Option<ActualTyreCompound>,
+    tyre_visual_compound: Option<VisualTyreCompound>,
+    tyre_age_laps: Option<u8>,
+}
+
+/// Combines lap data, participants and car status of all cars into
+/// standings sorted by race position
+#[derive(Default)]
+pub struct TimingTower {
+    cars: [CarEntry; 22],
+    // Rows as last returned by `changes`
+    published: Vec<Standing>,
+    // Session time gaps were last updated at
+    gaps_published_at: Option<f32>,
+}
+
+impl TimingTower {
+    pub fn on_lap_data(&mut self, data: &PacketLapData) {
+        for (car, lap) in self.cars.iter_mut().zip(data.lap_data.iter()) {
+            car.lap = Some(*lap);
+        }
+    }
+
+    pub fn on_participants(&mut self, data: &PacketParticipantsData) {
+        for (car, p) in self.cars.iter_mut().zip(data.participants.iter()) {
+            car.team_id = Some(p.team_id);
+            car.race_number = Some(p.race_number);
+        }
+    }
+
+    pub fn on_car_status(&mut self, data: &PacketCarStatusData) {
+        for (car, status) in self.cars.iter_mut().zip(data.car_status_data.iter()) {
+            car.tyre_actual_compound = Some(status.actual_tyre_compound);
+            car.tyre_visual_compound = Some(status.visual_tyre_compound);
+            car.tyre_age_laps = Some(status.tyres_age_laps);
+        }
+    }
+
+    /// Cars taking part in the session, in race order. Driver names are
+    /// looked up by car index, falling back to "Car N".
+    pub fn standings(&self, driver_names: &[String]) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self
+            .cars
+            .iter()
+            .enumerate()
+            .filter_map(|(i, car)| {
+                let lap = car.lap?;
+                if lap.car_position == 0
+                    || matches!(
+                        lap.result_status,
+                        ResultStatus::Invalid | ResultStatus::Inactive
+                    )
+                {
+                    return None;
+                }
+
+                Some(Standing {
+                    position: lap.car_position,
+                    car_index: i as u8,
+                    driver_name: driver_names
+                        .get(i)
+                        .cloned()
+                        .unwrap_or_else(|| format!("Car {}", i)),
+                    team_id: car.team_id,
+                    team_name: car.team_id.and_then(reference::team_name).map(Into::into),
+                    race_number: car.race_number,
+                    current_lap_num: lap.current_lap_num,
+                    last_lap_time_in_ms: lap.last_lap_time_in_ms,
+                    delta_to_car_in_front_in_ms: lap.delta_to_car_in_front_in_ms(),
+                    delta_to_race_leader_in_ms: lap.delta_to_race_leader_in_ms(),
+                    pit_status: lap.pit_status,
+                    num_pit_stops: lap.num_pit_stops,
+                    penalties: lap.penalties,
+                    total_warnings: lap.total_warnings,
+                    num_unserved_drive_through_pens: lap.num_unserved_drive_through_pens,
+                    num_unserved_stop_go_pens: lap.num_unserved_stop_go_pens,
+                    tyre_actual_compound: car.tyre_actual_compound,
+                    tyre_visual_compound: car.tyre_visual_compound,
+                    tyre_age_laps: car.tyre_age_laps,
+                    driver_status: lap.driver_status,
+                    result_status: lap.result_status,
+                })
+            })
+            .collect();
+
+        standings.sort_by_key(|s| (s.position, s.car_index));
+        standings
+    }
+
+    /// Rows that changed since they were last returned, in race order, and
+    /// the car indices of the rows that are gone since. A change of the gaps
+    /// alone only counts once `GAP_INTERVAL` has passed since the gaps were
+    /// last updated.
+    pub fn changes(
+        &mut self,
+        driver_names: &[String],
+        session_time: f32,
+    ) -> (Vec<Standing>, Vec<u8>) {
+        let standings = self.standings(driver_names);
+
+        // A session time earlier than the last update means the game rewound
+        let gaps_due = self
+            .gaps_published_at
+            .is_none_or(|t| session_time - t >= GAP_INTERVAL || session_time < t);
+        if gaps_due {
+            self.gaps_published_at = Some(session_time);
+        }
+
+        let changed: Vec<Standing> = standings
+            .iter()
+            .filter(
+                |s| match self.published.iter().find(|p| p.car_index == s.car_index) {
+                    Some(published) if gaps_due => published != *s,
+                    Some(published) => without_gaps(published) != without_gaps(s),
+                    None => true,
+                },
+            )
+            .cloned()
+            .collect();
+        let removed: Vec<u8> = self
+            .published
+            .iter()
+            .map(|s| s.car_index)
+            .filter(|&car_index| standings.iter().all(|s| s.car_index != car_index))
+            .collect();
+
+        self.published.retain(|p| {
+            !removed.contains(&p.car_index) && changed.iter().all(|s| s.car_index != p.car_index)
+        });
+        self.published.extend(changed.iter().cloned());
+        (changed, removed)
+    }
+}
+
+// A row with the gaps to the cars ahead left out, to tell changes in race
+// order, pit stops, tyres, penalties and status from the gaps that change
+// all the time
+fn without_gaps(standing: &Standing) -> Standing {
+    Standing {
+        delta_to_car_in_front_in_ms: 0,
+        delta_to_race_leader_in_ms: 0,
+        ..standing.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Lap data of two running cars, the second `gap` ms behind the first
+    fn lap_data(gap: u16, pit_status: PitStatus) -> PacketLapData {
+        let mut data = PacketLapData {
+            lap_data: [LapData::default(); 22],
+            time_trial_pb_car_idx: 255,
+            time_trial_rival_car_idx: 255,
+        };
+        for (i, lap) in data.lap_data.iter_mut().take(2).enumerate() {
+            lap.car_position = i as u8 + 1;
+            lap.result_status = ResultStatus::Active;
+        }
+        data.lap_data[1].delta_to_car_in_front_ms_part = gap;
+        data.lap_data[1].delta_to_race_leader_ms_part = gap;
+        data.lap_data[1].pit_status = pit_status;
+        data
+    }
+
+    #[test]
+    fn gaps_alone_are_updated_once_per_interval() {
+        let mut tower = TimingTower::default();
+        tower.on_lap_data(&lap_data(500, PitStatus::None));
+        assert_eq!(tower.changes(&[], 10.0).0.len(), 2);
+
+        tower.on_lap_data(&lap_data(600, PitStatus::None));
+        assert!(tower.changes(&[], 10.5).0.is_empty());
+
+        // Other changes are sent straight away, with the latest gaps
+        tower.on_lap_data(&lap_data(700, PitStatus::Pitting));
+        let (changed, _) = tower.changes(&[], 10.6);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].delta_to_car_in_front_in_ms, 700);
+
+        tower.on_lap_data(&lap_data(800, PitStatus::Pitting));
+        assert!(tower.changes(&[], 10.9).0.is_empty());
+        let (changed, _) = tower.changes(&[], 11.0);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].delta_to_race_leader_in_ms, 800);
+    }
+}
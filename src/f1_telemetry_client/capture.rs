@@ -0,0 +1,130 @@
+use super::MAX_DATAGRAM_SIZE;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::time::Instant;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
+use tokio::sync::mpsc;
+use tokio_util::task::TaskTracker;
+use tracing::error;
+
+// Capture files start with this magic, followed by one record per datagram:
+//   u64 LE  microseconds since the capture started
+//   u32 LE  datagram length
+//   [u8]    raw datagram, header included
+const MAGIC: &[u8; 8] = b"F124CAP1";
+
+/// Appends raw UDP datagrams, with their receive time, to a capture file.
+/// The file is written on a task of its own, so receiving never waits on it.
+pub struct CaptureWriter {
+    sender: mpsc::UnboundedSender<Vec<u8>>,
+    started: Instant,
+}
+
+impl CaptureWriter {
+    /// Create the file and start writing to it on a task of `tasks`, which
+    /// runs until the writer is dropped and every record queued is written
+    pub async fn create(path: &Path, tasks: &TaskTracker) -> io::Result<Self> {
+        let mut file = BufWriter::new(File::create(path).await?);
+        file.write_all(MAGIC).await?;
+        file.flush().await?;
+
+        let (sender, mut receiver) = mpsc::unbounded_channel::<Vec<u8>>();
+        tasks.spawn(async move {
+            let written: io::Result<()> = async {
+                while let Some(record) = receiver.recv().await {
+                    file.write_all(&record).await?;
+                    // Flushed once the queue is empty, so that a busy
+                    // capture is written in large chunks
+                    while let Ok(record) = receiver.try_recv() {
+                        file.write_all(&record).await?;
+                    }
+                    file.flush().await?;
+                }
+                Ok(())
+            }
+            .await;
+
+            if let Err(e) = written {
+                error!("Error writing capture, no longer capturing: {}", e);
+            }
+        });
+
+        Ok(Self {
+            sender,
+            started: Instant::now(),
+        })
+    }
+
+    /// Queue a datagram for the file, unless writing it has failed
+    pub fn write(&self, datagram: &[u8]) {
+        let timestamp = self.started.elapsed().as_micros() as u64;
+
+        let mut record = Vec::with_capacity(12 + datagram.len());
+        record.extend_from_slice(&timestamp.to_le_bytes());
+        record.extend_from_slice(&(datagram.len() as u32).to_le_bytes());
+        record.extend_from_slice(datagram);
+
+        // The writer task has logged why it stopped
+        let _ = self.sender.send(record);
+    }
+}
+
+/// A datagram read back from a capture file
+pub struct CaptureRecord {
+    pub timestamp_us: u64,
+    pub datagram: Vec<u8>,
+}
+
+/// Reads the records of a capture file in the order they were received
+pub struct CaptureReader {
+    reader: BufReader<File>,
+}
+
+impl CaptureReader {
+    pub async fn open(path: &Path) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path).await?);
+
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic).await?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "Not an F1 24 telemetry capture file",
+            ));
+        }
+
+        Ok(Self { reader })
+    }
+
+    /// The next record, or `None` at the end of the file
+    pub async fn next_record(&mut self) -> Option<io::Result<CaptureRecord>> {
+        let mut header = [0u8; 12];
+        match self.reader.read_exact(&mut header).await {
+            Ok(_) => (),
+            // A clean end of file, or a capture that was cut off mid-record
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return None,
+            Err(e) => return Some(Err(e)),
+        }
+
+        let timestamp_us = u64::from_le_bytes(header[..8].try_into().unwrap());
+        let len = u32::from_le_bytes(header[8..].try_into().unwrap()) as usize;
+        // Nothing longer was ever received, so the file is corrupt
+        if len > MAX_DATAGRAM_SIZE {
+            return Some(Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Capture record of {} bytes is longer than a datagram", len),
+            )));
+        }
+
+        let mut datagram = vec![0u8; len];
+        match self.reader.read_exact(&mut datagram).await {
+            Ok(_) => Some(Ok(CaptureRecord {
+                timestamp_us,
+                datagram,
+            })),
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
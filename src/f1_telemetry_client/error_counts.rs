@@ -0,0 +1,58 @@
+use super::packets::error::PacketError;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// Datagrams discarded for one kind of decoding error
+#[derive(Debug, Clone)]
+pub struct ErrorCount {
+    pub kind: &'static str,
+    pub count: u64,
+    // Message and time of the most recent error of this kind
+    pub last_error: Option<String>,
+    pub last_seen_at: Option<DateTime<Utc>>,
+}
+
+/// Counts the datagrams that couldn't be decoded, per kind of error, so a
+/// game patch that changes a layout shows up as a climbing count
+#[derive(Default)]
+pub struct ErrorCounts {
+    counts: Mutex<HashMap<&'static str, ErrorCount>>,
+}
+
+impl ErrorCounts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, error: &PacketError) {
+        let kind = error.kind();
+        let mut counts = self.counts.lock().unwrap();
+        let count = counts.entry(kind).or_insert(ErrorCount {
+            kind,
+            count: 0,
+            last_error: None,
+            last_seen_at: None,
+        });
+
+        count.count += 1;
+        count.last_error = Some(error.to_string());
+        count.last_seen_at = Some(Utc::now());
+    }
+
+    /// Counts of every kind of error, including those never seen
+    pub fn counts(&self) -> Vec<ErrorCount> {
+        let counts = self.counts.lock().unwrap();
+        PacketError::KINDS
+            .iter()
+            .map(|&kind| {
+                counts.get(kind).cloned().unwrap_or(ErrorCount {
+                    kind,
+                    count: 0,
+                    last_error: None,
+                    last_seen_at: None,
+                })
+            })
+            .collect()
+    }
+}
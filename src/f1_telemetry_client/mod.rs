@@ -1,91 +1,292 @@
-mod packets;
+mod capture;
+mod error_counts;
+// Packet structs mirror the full UDP spec, so not every decoded field is consumed
+#[allow(dead_code)]
+pub mod packets;
+mod replay;
 
+use capture::CaptureWriter;
+pub use error_counts::ErrorCounts;
+use packets::car_damage::PacketCarDamageData;
 use packets::car_motion_data::PacketMotionData;
-use packets::car_telemetry::PacketCarTelemetry;
+use packets::car_setups::PacketCarSetupData;
+use packets::car_status::PacketCarStatusData;
+use packets::car_telemetry::PacketCarTelemetryData;
+use packets::error::PacketError;
+use packets::event::PacketEventData;
+use packets::final_classification::PacketFinalClassificationData;
 use packets::header::PacketHeader;
 use packets::lap_data::PacketLapData;
+use packets::lobby_info::PacketLobbyInfoData;
+use packets::motion_ex::PacketMotionExData;
+use packets::participants::PacketParticipantsData;
 use packets::session_data::PacketSessionData;
-use packets::{header::PacketType, PacketSize};
+use packets::session_history::PacketSessionHistoryData;
+use packets::time_trial::PacketTimeTrialData;
+use packets::tyre_sets::PacketTyreSetsData;
+use packets::writer::{PacketWriter, ToPacket};
+use packets::{header::PacketType, parse, PacketSize};
+pub use replay::{F1TelemetryReplay, ReplaySpeed};
 use std::error::Error;
-use std::sync::Arc;
-use tokio::net::UdpSocket;
-use tokio::sync::Mutex;
+use std::io;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::net::{lookup_host, UdpSocket};
+use tokio::sync::Notify;
+use tokio_util::task::TaskTracker;
 use tracing::{debug, error, info};
 
+// Largest datagram received, bigger than any packet the game sends
+const MAX_DATAGRAM_SIZE: usize = 2048;
+
 pub struct F1TelemetryClient {
-    socket: Arc<UdpSocket>,
-    running: Arc<Mutex<bool>>,
+    socket: Mutex<Arc<UdpSocket>>,
+    paused: AtomicBool,
+    // Wakes the receive loop up when the socket is replaced
+    rebound: Notify,
+    capture: Option<CaptureWriter>,
     // data: Arc<Mutex<Vec<TelemetryPacket>>>,
 }
 
+/// Where telemetry packets come from
+#[derive(Debug, Clone)]
+pub enum TelemetrySource {
+    /// Live UDP data from the game, optionally recorded to a capture file
+    Udp {
+        addr: String,
+        capture: Option<PathBuf>,
+    },
+    /// A previously recorded capture file
+    Replay { path: PathBuf, speed: ReplaySpeed },
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone)]
 pub enum TelemetryPacket {
     Session((PacketHeader, PacketSessionData)),
     Motion((PacketHeader, PacketMotionData)),
-    CarTelemetry((PacketHeader, PacketCarTelemetry)),
+    CarTelemetry((PacketHeader, PacketCarTelemetryData)),
     LapData((PacketHeader, PacketLapData)),
+    Event((PacketHeader, PacketEventData)),
+    Participants((PacketHeader, PacketParticipantsData)),
+    CarSetups((PacketHeader, PacketCarSetupData)),
+    CarStatus((PacketHeader, PacketCarStatusData)),
+    FinalClassification((PacketHeader, PacketFinalClassificationData)),
+    LobbyInfo((PacketHeader, PacketLobbyInfoData)),
+    CarDamage((PacketHeader, PacketCarDamageData)),
+    SessionHistory((PacketHeader, PacketSessionHistoryData)),
+    TyreSets((PacketHeader, PacketTyreSetsData)),
+    MotionEx((PacketHeader, PacketMotionExData)),
+    TimeTrial((PacketHeader, PacketTimeTrialData)),
+}
+
+impl TelemetryPacket {
+    pub fn header(&self) -> &PacketHeader {
+        match self {
+            Self::Session((header, _))
+            | Self::Motion((header, _))
+            | Self::CarTelemetry((header, _))
+            | Self::LapData((header, _))
+            | Self::Event((header, _))
+            | Self::Participants((header, _))
+            | Self::CarSetups((header, _))
+            | Self::CarStatus((header, _))
+            | Self::FinalClassification((header, _))
+            | Self::LobbyInfo((header, _))
+            | Self::CarDamage((header, _))
+            | Self::SessionHistory((header, _))
+            | Self::TyreSets((header, _))
+            | Self::MotionEx((header, _))
+            | Self::TimeTrial((header, _)) => header,
+        }
+    }
 }
 
+// Encodes the packet as an F1 24 datagram
+impl ToPacket for TelemetryPacket {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(self.header());
+        match self {
+            Self::Session((_, data)) => writer.write(data),
+            Self::Motion((_, data)) => writer.write(data),
+            Self::CarTelemetry((_, data)) => writer.write(data),
+            Self::LapData((_, data)) => writer.write(data),
+            Self::Event((_, data)) => writer.write(data),
+            Self::Participants((_, data)) => writer.write(data),
+            Self::CarSetups((_, data)) => writer.write(data),
+            Self::CarStatus((_, data)) => writer.write(data),
+            Self::FinalClassification((_, data)) => writer.write(data),
+            Self::LobbyInfo((_, data)) => writer.write(data),
+            Self::CarDamage((_, data)) => writer.write(data),
+            Self::SessionHistory((_, data)) => writer.write(data),
+            Self::TyreSets((_, data)) => writer.write(data),
+            Self::MotionEx((_, data)) => writer.write(data),
+            Self::TimeTrial((_, data)) => writer.write(data),
+        }
+    }
+}
+
+// Newest version of each packet type the layouts describe
+const MAX_PACKET_VERSION: u8 = 1;
+
 impl TryFrom<&[u8]> for TelemetryPacket {
-    type Error = String;
+    type Error = PacketError;
 
     fn try_from(value: &[u8]) -> Result<TelemetryPacket, Self::Error> {
         let header = PacketHeader::try_from(value)?;
+        if header.packet_version > MAX_PACKET_VERSION {
+            return Err(PacketError::UnsupportedVersion {
+                packet_type: header.packet_id,
+                version: header.packet_version,
+            });
+        }
 
+        let format = header.format();
         debug!(
-            "Received packet \"{:?}\" of size {}",
+            "Received packet \"{:?}\" ({:?}) of size {}",
             header.packet_id,
-            value.len() - PacketHeader::size()
+            format,
+            value.len() - PacketHeader::size(format)
         );
 
-        let bytes = &value[PacketHeader::size()..];
+        let bytes = &value[PacketHeader::size(format)..];
 
         match header.packet_id {
-            PacketType::Motion => Ok(Self::Motion((header, PacketMotionData::try_from(bytes)?))),
-            PacketType::Session => Ok(Self::Session((header, PacketSessionData::try_from(bytes)?))),
-            PacketType::CarTelemetry => Ok(Self::CarTelemetry((
-                header,
-                PacketCarTelemetry::try_from(&bytes[..PacketCarTelemetry::size()])?,
-            ))),
-            PacketType::LapData => Ok(Self::LapData((header, PacketLapData::try_from(bytes)?))),
-            _ => Err(format!("Unsupported packet type {:?}", header.packet_id)),
+            PacketType::Motion => Ok(Self::Motion((header, parse(bytes, format)?))),
+            PacketType::Session => Ok(Self::Session((header, parse(bytes, format)?))),
+            PacketType::CarTelemetry => Ok(Self::CarTelemetry((header, parse(bytes, format)?))),
+            PacketType::LapData => Ok(Self::LapData((header, parse(bytes, format)?))),
+            PacketType::Event => Ok(Self::Event((header, parse(bytes, format)?))),
+            PacketType::Participants => Ok(Self::Participants((header, parse(bytes, format)?))),
+            PacketType::CarSetups => Ok(Self::CarSetups((header, parse(bytes, format)?))),
+            PacketType::CarStatus => Ok(Self::CarStatus((header, parse(bytes, format)?))),
+            PacketType::FinalClassification => {
+                Ok(Self::FinalClassification((header, parse(bytes, format)?)))
+            }
+            PacketType::LobbyInfo => Ok(Self::LobbyInfo((header, parse(bytes, format)?))),
+            PacketType::CarDamage => Ok(Self::CarDamage((header, parse(bytes, format)?))),
+            PacketType::SessionHistory => Ok(Self::SessionHistory((header, parse(bytes, format)?))),
+            PacketType::TyreSets => Ok(Self::TyreSets((header, parse(bytes, format)?))),
+            PacketType::MotionEx => Ok(Self::MotionEx((header, parse(bytes, format)?))),
+            PacketType::TimeTrial => Ok(Self::TimeTrial((header, parse(bytes, format)?))),
         }
     }
 }
 
+// Decode a datagram, counting it if it can't be
+fn decode(datagram: &[u8], errors: &ErrorCounts) -> Option<TelemetryPacket> {
+    TelemetryPacket::try_from(datagram)
+        .inspect_err(|e| {
+            debug!("Discarding datagram: {}", e);
+            errors.record(e);
+        })
+        .ok()
+}
+
 impl F1TelemetryClient {
     pub async fn new(addr: &str) -> Result<Self, Box<dyn Error>> {
-        let socket = UdpSocket::bind(addr).await?;
+        let socket = UdpSocket::bind(addr)
+            .await
+            .map_err(|e| format!("Error binding UDP listener to {}: {}", addr, e))?;
         Ok(Self {
-            socket: Arc::new(socket),
-            running: Arc::new(Mutex::new(true)),
+            socket: Mutex::new(Arc::new(socket)),
+            paused: AtomicBool::new(false),
+            rebound: Notify::new(),
+            capture: None,
             // data: Arc::new(Mutex::new(Vec::new())),
         })
     }
 
-    pub async fn start<F>(&self, f: F) -> Result<(), Box<dyn Error>>
+    /// Record every datagram received to a capture file for later replay,
+    /// written on a task of `tasks`
+    pub async fn with_capture(
+        mut self,
+        path: &Path,
+        tasks: &TaskTracker,
+    ) -> Result<Self, Box<dyn Error>> {
+        self.capture = Some(CaptureWriter::create(path, tasks).await?);
+        info!("Capturing raw telemetry to {}", path.display());
+        Ok(self)
+    }
+
+    /// Receive packets until the returned future is dropped, counting those
+    /// that can't be decoded in `errors`
+    pub async fn start<F>(&self, f: F, errors: &ErrorCounts)
     where
-        F: Fn(TelemetryPacket) -> (),
+        F: Fn(TelemetryPacket),
     {
         info!("Listening for F1 24 telemetry data...");
-        let mut buf = [0u8; 2048];
-
-        while *self.running.lock().await {
-            match self.socket.recv(&mut buf).await {
-                Ok(size) => match TelemetryPacket::try_from(&buf[..size]) {
-                    Ok(p) => f(p),
-                    // Err(e) => {
-                    //     error!("Error processing packet: {}", e);
-                    // }
-                    Err(_) => (),
+        let mut buf = [0u8; MAX_DATAGRAM_SIZE];
+
+        loop {
+            let socket = self.socket.lock().unwrap().clone();
+            let size = tokio::select! {
+                // Start over on the new socket
+                _ = self.rebound.notified() => continue,
+                received = socket.recv(&mut buf) => match received {
+                    Ok(size) => size,
+                    Err(e) => {
+                        error!("Error receiving data: {}", e);
+                        continue;
+                    }
                 },
-                Err(e) => error!("Error receiving data: {}", e),
+            };
+
+            // Datagrams keep being read while paused so that the game's
+            // packets don't pile up in the socket
+            if self.is_paused() {
+                continue;
+            }
+
+            if let Some(capture) = &self.capture {
+                capture.write(&buf[..size]);
+            }
+
+            if let Some(p) = decode(&buf[..size], errors) {
+                f(p)
             }
         }
-        Ok(())
     }
 
-    pub async fn stop(&self) {
-        let mut running = self.running.lock().await;
-        *running = false;
+    /// Address the listener is bound to
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.lock().unwrap().local_addr()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Discard datagrams until resumed
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+        info!("Telemetry listener paused");
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+        info!("Telemetry listener resumed");
+    }
+
+    /// Listen on another address. The current socket is kept if the new
+    /// one can't be bound.
+    pub async fn rebind(&self, addr: &str) -> io::Result<SocketAddr> {
+        let addr = lookup_host(addr)
+            .await?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "No address to bind"))?;
+        if self.local_addr()? == addr {
+            return Ok(addr);
+        }
+
+        let socket = UdpSocket::bind(addr).await?;
+        let addr = socket.local_addr()?;
+        *self.socket.lock().unwrap() = Arc::new(socket);
+        self.rebound.notify_one();
+        info!("Telemetry listener bound to {}", addr);
+
+        Ok(addr)
     }
 }
@@ -1,3 +1,7 @@
+use super::error::PacketError;
+use super::format::PacketFormat;
+use super::reader::{FromPacket, PacketReader};
+use super::writer::{PacketWriter, ToPacket};
 use super::PacketSize;
 
 #[derive(Debug, Clone, Copy)]
@@ -25,88 +29,82 @@ pub struct CarDamageData {
     pub engine_seized: u8,           // Engine seized, 0 = OK, 1 = fault
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct PacketCarDamageData {
     pub car_damage_data: Vec<CarDamageData>, // Car damage data for all cars
 }
 
 impl PacketSize for PacketCarDamageData {
-    fn size() -> usize {
-        953
+    fn size(_format: PacketFormat) -> usize {
+        924 // Size specified in the UDP spec, excluding the header
     }
 }
 
-impl TryFrom<&[u8]> for PacketCarDamageData {
-    type Error = String;
-
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() < PacketCarDamageData::size() {
-            return Err("Packet too short for PacketCarDamageData".into());
-        }
-
-        let mut car_damage_data = Vec::with_capacity(22);
-        let mut offset = 0;
-
-        // Parse damage data for each car
-        for _ in 0..22 {
-            // Parse tyre wear (4 f32 values)
-            let mut tyres_wear = [0.0f32; 4];
-            for i in 0..4 {
-                tyres_wear[i] = f32::from_le_bytes([
-                    bytes[offset + i * 4],
-                    bytes[offset + i * 4 + 1],
-                    bytes[offset + i * 4 + 2],
-                    bytes[offset + i * 4 + 3],
-                ]);
-            }
-            offset += 16;
-
-            // Parse tyre damage (4 u8 values)
-            let tyres_damage = [
-                bytes[offset],
-                bytes[offset + 1],
-                bytes[offset + 2],
-                bytes[offset + 3],
-            ];
-            offset += 4;
+impl FromPacket for PacketCarDamageData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            car_damage_data: reader.vec(22)?,
+        })
+    }
+}
 
-            // Parse brakes damage (4 u8 values)
-            let brakes_damage = [
-                bytes[offset],
-                bytes[offset + 1],
-                bytes[offset + 2],
-                bytes[offset + 3],
-            ];
-            offset += 4;
+impl ToPacket for PacketCarDamageData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.vec(&self.car_damage_data, 22);
+    }
+}
 
-            let damage = CarDamageData {
-                tyres_wear,
-                tyres_damage,
-                brakes_damage,
-                front_left_wing_damage: bytes[offset],
-                front_right_wing_damage: bytes[offset + 1],
-                rear_wing_damage: bytes[offset + 2],
-                floor_damage: bytes[offset + 3],
-                diffuser_damage: bytes[offset + 4],
-                sidepod_damage: bytes[offset + 5],
-                drs_fault: bytes[offset + 6],
-                ers_fault: bytes[offset + 7],
-                gear_box_damage: bytes[offset + 8],
-                engine_damage: bytes[offset + 9],
-                engine_mguh_wear: bytes[offset + 10],
-                engine_es_wear: bytes[offset + 11],
-                engine_ce_wear: bytes[offset + 12],
-                engine_ice_wear: bytes[offset + 13],
-                engine_mguk_wear: bytes[offset + 14],
-                engine_tc_wear: bytes[offset + 15],
-                engine_blown: bytes[offset + 16],
-                engine_seized: bytes[offset + 17],
-            };
-            car_damage_data.push(damage);
-            offset += 18; // Remaining size of each car damage data block
-        }
+impl FromPacket for CarDamageData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            tyres_wear: reader.read()?,
+            tyres_damage: reader.read()?,
+            brakes_damage: reader.read()?,
+            front_left_wing_damage: reader.read()?,
+            front_right_wing_damage: reader.read()?,
+            rear_wing_damage: reader.read()?,
+            floor_damage: reader.read()?,
+            diffuser_damage: reader.read()?,
+            sidepod_damage: reader.read()?,
+            drs_fault: reader.read()?,
+            ers_fault: reader.read()?,
+            gear_box_damage: reader.read()?,
+            engine_damage: reader.read()?,
+            engine_mguh_wear: reader.read()?,
+            engine_es_wear: reader.read()?,
+            engine_ce_wear: reader.read()?,
+            engine_ice_wear: reader.read()?,
+            engine_mguk_wear: reader.read()?,
+            engine_tc_wear: reader.read()?,
+            engine_blown: reader.read()?,
+            engine_seized: reader.read()?,
+        })
+    }
+}
 
-        Ok(PacketCarDamageData { car_damage_data })
+impl ToPacket for CarDamageData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.tyres_wear);
+        writer.write(&self.tyres_damage);
+        writer.write(&self.brakes_damage);
+        writer.write(&self.front_left_wing_damage);
+        writer.write(&self.front_right_wing_damage);
+        writer.write(&self.rear_wing_damage);
+        writer.write(&self.floor_damage);
+        writer.write(&self.diffuser_damage);
+        writer.write(&self.sidepod_damage);
+        writer.write(&self.drs_fault);
+        writer.write(&self.ers_fault);
+        writer.write(&self.gear_box_damage);
+        writer.write(&self.engine_damage);
+        writer.write(&self.engine_mguh_wear);
+        writer.write(&self.engine_es_wear);
+        writer.write(&self.engine_ce_wear);
+        writer.write(&self.engine_ice_wear);
+        writer.write(&self.engine_mguk_wear);
+        writer.write(&self.engine_tc_wear);
+        writer.write(&self.engine_blown);
+        writer.write(&self.engine_seized);
     }
 }
 
@@ -138,10 +136,32 @@ impl Default for CarDamageData {
     }
 }
 
-impl Default for PacketCarDamageData {
-    fn default() -> Self {
-        PacketCarDamageData {
-            car_damage_data: Vec::new(),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::f1_telemetry_client::packets::header::PacketType;
+    use crate::f1_telemetry_client::packets::testing::{
+        assert_encodes_to, assert_round_trips_as_f1_24, datagram, earlier_datagram,
+    };
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn f1_24_datagrams_round_trip(
+            datagram in datagram(
+                PacketFormat::F1_24,
+                PacketType::CarDamage,
+                PacketCarDamageData::size,
+            ),
+        ) {
+            assert_encodes_to(&datagram, &datagram)?;
+        }
+
+        #[test]
+        fn earlier_games_round_trip_as_f1_24(
+            datagram in earlier_datagram(PacketType::CarDamage, PacketCarDamageData::size),
+        ) {
+            assert_round_trips_as_f1_24(&datagram, PacketCarDamageData::size)?;
         }
     }
 }
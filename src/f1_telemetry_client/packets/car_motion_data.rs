@@ -1,3 +1,7 @@
+use super::error::PacketError;
+use super::format::PacketFormat;
+use super::reader::{FromPacket, PacketReader};
+use super::writer::{PacketWriter, ToPacket};
 use super::PacketSize;
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -41,7 +45,7 @@ pub struct CarMotionData {
 }
 
 impl PacketSize for CarMotionData {
-    fn size() -> usize {
+    fn size(_format: PacketFormat) -> usize {
         60
     }
 }
@@ -53,57 +57,103 @@ pub struct PacketMotionData {
 }
 
 impl PacketSize for PacketMotionData {
-    fn size() -> usize {
-        CarMotionData::size() * 22
+    fn size(format: PacketFormat) -> usize {
+        match format {
+            PacketFormat::F1_22 => CarMotionData::size(format) * 22 + 120,
+            PacketFormat::F1_23 | PacketFormat::F1_24 => CarMotionData::size(format) * 22,
+        }
     }
 }
 
-impl TryFrom<&[u8]> for CarMotionData {
-    type Error = String;
-
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() < CarMotionData::size() {
-            return Err("Buffer too small for CarMotionData".into());
-        }
-
+impl FromPacket for CarMotionData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
         Ok(Self {
-            world_position_x: f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
-            world_position_y: f32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
-            world_position_z: f32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]),
-            world_velocity_x: f32::from_le_bytes([bytes[12], bytes[13], bytes[14], bytes[15]]),
-            world_velocity_y: f32::from_le_bytes([bytes[16], bytes[17], bytes[18], bytes[19]]),
-            world_velocity_z: f32::from_le_bytes([bytes[20], bytes[21], bytes[22], bytes[23]]),
-            world_forward_dir_x: i16::from_le_bytes([bytes[24], bytes[25]]),
-            world_forward_dir_y: i16::from_le_bytes([bytes[26], bytes[27]]),
-            world_forward_dir_z: i16::from_le_bytes([bytes[28], bytes[29]]),
-            world_right_dir_x: i16::from_le_bytes([bytes[30], bytes[31]]),
-            world_right_dir_y: i16::from_le_bytes([bytes[32], bytes[33]]),
-            world_right_dir_z: i16::from_le_bytes([bytes[34], bytes[35]]),
-            g_force_lateral: f32::from_le_bytes([bytes[36], bytes[37], bytes[38], bytes[39]]),
-            g_force_longitudinal: f32::from_le_bytes([bytes[40], bytes[41], bytes[42], bytes[43]]),
-            g_force_vertical: f32::from_le_bytes([bytes[44], bytes[45], bytes[46], bytes[47]]),
-            yaw: f32::from_le_bytes([bytes[48], bytes[49], bytes[50], bytes[51]]),
-            pitch: f32::from_le_bytes([bytes[52], bytes[53], bytes[54], bytes[55]]),
-            roll: f32::from_le_bytes([bytes[56], bytes[57], bytes[58], bytes[59]]),
+            world_position_x: reader.read()?,
+            world_position_y: reader.read()?,
+            world_position_z: reader.read()?,
+            world_velocity_x: reader.read()?,
+            world_velocity_y: reader.read()?,
+            world_velocity_z: reader.read()?,
+            world_forward_dir_x: reader.read()?,
+            world_forward_dir_y: reader.read()?,
+            world_forward_dir_z: reader.read()?,
+            world_right_dir_x: reader.read()?,
+            world_right_dir_y: reader.read()?,
+            world_right_dir_z: reader.read()?,
+            g_force_lateral: reader.read()?,
+            g_force_longitudinal: reader.read()?,
+            g_force_vertical: reader.read()?,
+            yaw: reader.read()?,
+            pitch: reader.read()?,
+            roll: reader.read()?,
         })
     }
 }
 
-impl TryFrom<&[u8]> for PacketMotionData {
-    type Error = String;
+impl ToPacket for CarMotionData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.world_position_x);
+        writer.write(&self.world_position_y);
+        writer.write(&self.world_position_z);
+        writer.write(&self.world_velocity_x);
+        writer.write(&self.world_velocity_y);
+        writer.write(&self.world_velocity_z);
+        writer.write(&self.world_forward_dir_x);
+        writer.write(&self.world_forward_dir_y);
+        writer.write(&self.world_forward_dir_z);
+        writer.write(&self.world_right_dir_x);
+        writer.write(&self.world_right_dir_y);
+        writer.write(&self.world_right_dir_z);
+        writer.write(&self.g_force_lateral);
+        writer.write(&self.g_force_longitudinal);
+        writer.write(&self.g_force_vertical);
+        writer.write(&self.yaw);
+        writer.write(&self.pitch);
+        writer.write(&self.roll);
+    }
+}
 
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() < PacketMotionData::size() {
-            return Err("Buffer too small for PacketMotionData".into());
+impl FromPacket for PacketMotionData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        let car_motion_data = reader.read()?;
+        // F1 22 follows with the player's suspension and wheel data, which
+        // later games send in the motion ex packet instead
+        if reader.format() == PacketFormat::F1_22 {
+            reader.skip(120)?;
         }
 
-        let mut car_motion_data = [CarMotionData::default(); 22];
-        for i in 0..22 {
-            let start = i * CarMotionData::size();
-            car_motion_data[i] =
-                CarMotionData::try_from(&bytes[start..start + size_of::<CarMotionData>()])?;
+        Ok(Self { car_motion_data })
+    }
+}
+
+impl ToPacket for PacketMotionData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.car_motion_data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::f1_telemetry_client::packets::header::PacketType;
+    use crate::f1_telemetry_client::packets::testing::{
+        assert_encodes_to, assert_round_trips_as_f1_24, datagram, earlier_datagram,
+    };
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn f1_24_datagrams_round_trip(
+            datagram in datagram(PacketFormat::F1_24, PacketType::Motion, PacketMotionData::size),
+        ) {
+            assert_encodes_to(&datagram, &datagram)?;
         }
 
-        Ok(Self { car_motion_data })
+        #[test]
+        fn earlier_games_round_trip_as_f1_24(
+            datagram in earlier_datagram(PacketType::Motion, PacketMotionData::size),
+        ) {
+            assert_round_trips_as_f1_24(&datagram, PacketMotionData::size)?;
+        }
     }
 }
@@ -1,3 +1,7 @@
+use super::error::PacketError;
+use super::format::PacketFormat;
+use super::reader::{FromPacket, PacketReader};
+use super::writer::{PacketWriter, ToPacket};
 use super::PacketSize;
 
 #[derive(Debug, Clone, Copy)]
@@ -34,113 +38,90 @@ pub struct PacketCarSetupData {
 }
 
 impl PacketSize for PacketCarSetupData {
-    fn size() -> usize {
-        1133 // Size specified in the UDP spec
+    fn size(format: PacketFormat) -> usize {
+        // Size specified in the UDP spec, excluding the header
+        match format {
+            PacketFormat::F1_22 | PacketFormat::F1_23 => 1078,
+            PacketFormat::F1_24 => 1104,
+        }
     }
 }
 
-impl TryFrom<&[u8]> for PacketCarSetupData {
-    type Error = String;
-
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() < PacketCarSetupData::size() {
-            return Err("Packet too short for PacketCarSetupData".into());
-        }
-
-        let mut car_setups = Vec::with_capacity(22);
-        let mut offset = 0;
-
-        // Parse setup data for each car
-        for _ in 0..22 {
-            let setup = CarSetupData {
-                front_wing: bytes[offset],
-                rear_wing: bytes[offset + 1],
-                on_throttle: bytes[offset + 2],
-                off_throttle: bytes[offset + 3],
-                front_camber: f32::from_le_bytes([
-                    bytes[offset + 4],
-                    bytes[offset + 5],
-                    bytes[offset + 6],
-                    bytes[offset + 7],
-                ]),
-                rear_camber: f32::from_le_bytes([
-                    bytes[offset + 8],
-                    bytes[offset + 9],
-                    bytes[offset + 10],
-                    bytes[offset + 11],
-                ]),
-                front_toe: f32::from_le_bytes([
-                    bytes[offset + 12],
-                    bytes[offset + 13],
-                    bytes[offset + 14],
-                    bytes[offset + 15],
-                ]),
-                rear_toe: f32::from_le_bytes([
-                    bytes[offset + 16],
-                    bytes[offset + 17],
-                    bytes[offset + 18],
-                    bytes[offset + 19],
-                ]),
-                front_suspension: bytes[offset + 20],
-                rear_suspension: bytes[offset + 21],
-                front_anti_roll_bar: bytes[offset + 22],
-                rear_anti_roll_bar: bytes[offset + 23],
-                front_suspension_height: bytes[offset + 24],
-                rear_suspension_height: bytes[offset + 25],
-                brake_pressure: bytes[offset + 26],
-                brake_bias: bytes[offset + 27],
-                engine_braking: bytes[offset + 28],
-                rear_left_tyre_pressure: f32::from_le_bytes([
-                    bytes[offset + 29],
-                    bytes[offset + 30],
-                    bytes[offset + 31],
-                    bytes[offset + 32],
-                ]),
-                rear_right_tyre_pressure: f32::from_le_bytes([
-                    bytes[offset + 33],
-                    bytes[offset + 34],
-                    bytes[offset + 35],
-                    bytes[offset + 36],
-                ]),
-                front_left_tyre_pressure: f32::from_le_bytes([
-                    bytes[offset + 37],
-                    bytes[offset + 38],
-                    bytes[offset + 39],
-                    bytes[offset + 40],
-                ]),
-                front_right_tyre_pressure: f32::from_le_bytes([
-                    bytes[offset + 41],
-                    bytes[offset + 42],
-                    bytes[offset + 43],
-                    bytes[offset + 44],
-                ]),
-                ballast: bytes[offset + 45],
-                fuel_load: f32::from_le_bytes([
-                    bytes[offset + 46],
-                    bytes[offset + 47],
-                    bytes[offset + 48],
-                    bytes[offset + 49],
-                ]),
-            };
-            car_setups.push(setup);
-            offset += 50; // Size of each car setup data block
-        }
+impl FromPacket for PacketCarSetupData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            car_setups: reader.vec(22)?,
+            next_front_wing_value: reader.since(PacketFormat::F1_24)?,
+        })
+    }
+}
 
-        // Parse next front wing value (after all car setups)
-        let next_front_wing_value = f32::from_le_bytes([
-            bytes[offset],
-            bytes[offset + 1],
-            bytes[offset + 2],
-            bytes[offset + 3],
-        ]);
+impl ToPacket for PacketCarSetupData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.vec(&self.car_setups, 22);
+        writer.write(&self.next_front_wing_value);
+    }
+}
 
-        Ok(PacketCarSetupData {
-            car_setups,
-            next_front_wing_value,
+// Engine braking and the next front wing value are new in F1 24
+impl FromPacket for CarSetupData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            front_wing: reader.read()?,
+            rear_wing: reader.read()?,
+            on_throttle: reader.read()?,
+            off_throttle: reader.read()?,
+            front_camber: reader.read()?,
+            rear_camber: reader.read()?,
+            front_toe: reader.read()?,
+            rear_toe: reader.read()?,
+            front_suspension: reader.read()?,
+            rear_suspension: reader.read()?,
+            front_anti_roll_bar: reader.read()?,
+            rear_anti_roll_bar: reader.read()?,
+            front_suspension_height: reader.read()?,
+            rear_suspension_height: reader.read()?,
+            brake_pressure: reader.read()?,
+            brake_bias: reader.read()?,
+            engine_braking: reader.since(PacketFormat::F1_24)?,
+            rear_left_tyre_pressure: reader.read()?,
+            rear_right_tyre_pressure: reader.read()?,
+            front_left_tyre_pressure: reader.read()?,
+            front_right_tyre_pressure: reader.read()?,
+            ballast: reader.read()?,
+            fuel_load: reader.read()?,
         })
     }
 }
 
+impl ToPacket for CarSetupData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.front_wing);
+        writer.write(&self.rear_wing);
+        writer.write(&self.on_throttle);
+        writer.write(&self.off_throttle);
+        writer.write(&self.front_camber);
+        writer.write(&self.rear_camber);
+        writer.write(&self.front_toe);
+        writer.write(&self.rear_toe);
+        writer.write(&self.front_suspension);
+        writer.write(&self.rear_suspension);
+        writer.write(&self.front_anti_roll_bar);
+        writer.write(&self.rear_anti_roll_bar);
+        writer.write(&self.front_suspension_height);
+        writer.write(&self.rear_suspension_height);
+        writer.write(&self.brake_pressure);
+        writer.write(&self.brake_bias);
+        writer.write(&self.engine_braking);
+        writer.write(&self.rear_left_tyre_pressure);
+        writer.write(&self.rear_right_tyre_pressure);
+        writer.write(&self.front_left_tyre_pressure);
+        writer.write(&self.front_right_tyre_pressure);
+        writer.write(&self.ballast);
+        writer.write(&self.fuel_load);
+    }
+}
+
 impl Default for CarSetupData {
     fn default() -> Self {
         CarSetupData {
@@ -179,3 +160,33 @@ impl Default for PacketCarSetupData {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::f1_telemetry_client::packets::header::PacketType;
+    use crate::f1_telemetry_client::packets::testing::{
+        assert_encodes_to, assert_round_trips_as_f1_24, datagram, earlier_datagram,
+    };
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn f1_24_datagrams_round_trip(
+            datagram in datagram(
+                PacketFormat::F1_24,
+                PacketType::CarSetups,
+                PacketCarSetupData::size,
+            ),
+        ) {
+            assert_encodes_to(&datagram, &datagram)?;
+        }
+
+        #[test]
+        fn earlier_games_round_trip_as_f1_24(
+            datagram in earlier_datagram(PacketType::CarSetups, PacketCarSetupData::size),
+        ) {
+            assert_round_trips_as_f1_24(&datagram, PacketCarSetupData::size)?;
+        }
+    }
+}
@@ -1,9 +1,12 @@
-use tracing::info;
-
+use super::enums::SurfaceType;
+use super::error::PacketError;
+use super::format::PacketFormat;
+use super::reader::{FromPacket, PacketReader};
+use super::writer::{PacketWriter, ToPacket};
 use super::PacketSize;
 
 #[derive(Debug, Clone, Copy, Default)]
-pub struct PacketCarTelemetry {
+pub struct CarTelemetryData {
     pub speed: u16,                 // Speed of car in km/h
     pub throttle: f32,              // Amount of throttle applied (0.0 to 1.0)
     pub steer: f32,                 // Steering (-1.0 for full left to 1.0 for full right)
@@ -19,59 +22,124 @@ pub struct PacketCarTelemetry {
     pub tyre_inner_temp: [u8; 4],   // Tyre inner temperatures
     pub engine_temperature: u16,
     pub tyre_pressure: [f32; 4],
-    pub surface_type: [u8; 4],
+    pub surface_type: [SurfaceType; 4], // Driving surface (FL, FR, RL, RR)
 }
 
-impl PacketSize for PacketCarTelemetry {
-    fn size() -> usize {
+impl PacketSize for CarTelemetryData {
+    fn size(_format: PacketFormat) -> usize {
         60
     }
 }
 
-fn read_f32_bytes(bytes: &[u8], offset: usize) -> f32 {
-    f32::from_le_bytes([
-        bytes[offset],
-        bytes[offset + 1],
-        bytes[offset + 2],
-        bytes[offset + 3],
-    ])
+impl FromPacket for CarTelemetryData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            speed: reader.read()?,
+            throttle: reader.read()?,
+            steer: reader.read()?,
+            brake: reader.read()?,
+            clutch: reader.read()?,
+            gear: reader.read()?,
+            engine_rpm: reader.read()?,
+            drs: reader.read()?,
+            rev_lights_percent: reader.read()?,
+            rev_lights_bit_value: reader.read()?,
+            brake_temp: reader.read()?,
+            tyre_surface_temp: reader.read()?,
+            tyre_inner_temp: reader.read()?,
+            engine_temperature: reader.read()?,
+            tyre_pressure: reader.read()?,
+            surface_type: reader.read()?,
+        })
+    }
+}
+
+impl ToPacket for CarTelemetryData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.speed);
+        writer.write(&self.throttle);
+        writer.write(&self.steer);
+        writer.write(&self.brake);
+        writer.write(&self.clutch);
+        writer.write(&self.gear);
+        writer.write(&self.engine_rpm);
+        writer.write(&self.drs);
+        writer.write(&self.rev_lights_percent);
+        writer.write(&self.rev_lights_bit_value);
+        writer.write(&self.brake_temp);
+        writer.write(&self.tyre_surface_temp);
+        writer.write(&self.tyre_inner_temp);
+        writer.write(&self.engine_temperature);
+        writer.write(&self.tyre_pressure);
+        writer.write(&self.surface_type);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PacketCarTelemetryData {
+    // Telemetry for all cars on track
+    pub car_telemetry_data: [CarTelemetryData; 22],
+    // Index of MFD panel open - 255 = MFD closed
+    // Single player, race - 0 = Car setup, 1 = Pits, 2 = Damage, 3 = Engine, 4 = Temperatures
+    pub mfd_panel_index: u8,
+    // See above
+    pub mfd_panel_index_secondary_player: u8,
+    // Suggested gear for the player (1-8), 0 if no gear suggested
+    pub suggested_gear: i8,
+}
+
+impl PacketSize for PacketCarTelemetryData {
+    fn size(format: PacketFormat) -> usize {
+        CarTelemetryData::size(format) * 22 + 3
+    }
+}
+
+impl FromPacket for PacketCarTelemetryData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            car_telemetry_data: reader.read()?,
+            mfd_panel_index: reader.read()?,
+            mfd_panel_index_secondary_player: reader.read()?,
+            suggested_gear: reader.read()?,
+        })
+    }
 }
 
-impl TryFrom<&[u8]> for PacketCarTelemetry {
-    type Error = String;
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() < PacketCarTelemetry::size() {
-            // Minimum size for car telemetry data
-            return Err("Packet too short for PacketCarTelemetry".into());
+impl ToPacket for PacketCarTelemetryData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.car_telemetry_data);
+        writer.write(&self.mfd_panel_index);
+        writer.write(&self.mfd_panel_index_secondary_player);
+        writer.write(&self.suggested_gear);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::f1_telemetry_client::packets::header::PacketType;
+    use crate::f1_telemetry_client::packets::testing::{
+        assert_encodes_to, assert_round_trips_as_f1_24, datagram, earlier_datagram,
+    };
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn f1_24_datagrams_round_trip(
+            datagram in datagram(
+                PacketFormat::F1_24,
+                PacketType::CarTelemetry,
+                PacketCarTelemetryData::size,
+            ),
+        ) {
+            assert_encodes_to(&datagram, &datagram)?;
         }
 
-        return Ok(PacketCarTelemetry {
-            speed: u16::from_le_bytes([bytes[0], bytes[1]]),
-            throttle: f32::from_le_bytes([bytes[2], bytes[3], bytes[4], bytes[5]]),
-            steer: f32::from_le_bytes([bytes[6], bytes[7], bytes[8], bytes[9]]),
-            brake: f32::from_le_bytes([bytes[10], bytes[11], bytes[12], bytes[13]]),
-            clutch: bytes[14],
-            gear: bytes[15] as i8,
-            engine_rpm: u16::from_le_bytes([bytes[16], bytes[17]]),
-            drs: bytes[18],
-            rev_lights_percent: bytes[19],
-            rev_lights_bit_value: u16::from_le_bytes([bytes[20], bytes[21]]),
-            brake_temp: [
-                u16::from_le_bytes([bytes[22], bytes[23]]),
-                u16::from_le_bytes([bytes[24], bytes[25]]),
-                u16::from_le_bytes([bytes[26], bytes[27]]),
-                u16::from_le_bytes([bytes[28], bytes[29]]),
-            ],
-            tyre_surface_temp: [bytes[30], bytes[31], bytes[32], bytes[33]],
-            tyre_inner_temp: [bytes[34], bytes[35], bytes[36], bytes[37]],
-            engine_temperature: u16::from_le_bytes([bytes[38], bytes[39]]),
-            tyre_pressure: [
-                read_f32_bytes(bytes, 40),
-                read_f32_bytes(bytes, 44),
-                read_f32_bytes(bytes, 48),
-                read_f32_bytes(bytes, 52),
-            ],
-            surface_type: [bytes[56], bytes[57], bytes[58], bytes[59]],
-        });
+        #[test]
+        fn earlier_games_round_trip_as_f1_24(
+            datagram in earlier_datagram(PacketType::CarTelemetry, PacketCarTelemetryData::size),
+        ) {
+            assert_round_trips_as_f1_24(&datagram, PacketCarTelemetryData::size)?;
+        }
     }
 }
@@ -0,0 +1,604 @@
+// Typed versions of the numeric codes used throughout the packets, taken from
+// the appendices and field comments of the F1 24 UDP specification, plus the
+// codes of F1 22 and 23 that F1 24 dropped. Team, driver and nationality ids
+// stay numeric; they are identities rather than codes with behaviour attached.
+
+use super::error::PacketError;
+use super::format::PacketFormat;
+use super::reader::{FromPacket, PacketReader};
+use super::writer::{PacketWriter, ToPacket};
+
+/// Declares an enum over a packet field's raw codes. Codes missing from the
+/// spec decode to `Unknown` with the raw value, so decoding never fails and
+/// the original value is never lost.
+macro_rules! packet_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident: $repr:ty {
+            $($value:literal => $variant:ident = $api_name:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)*
+            Unknown($repr),
+        }
+
+        impl $name {
+            /// Names used in the API, in declaration order, followed by "unknown"
+            pub const NAMES: &'static [&'static str] = &[$($api_name,)* "unknown"];
+
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $api_name,)*
+                    Self::Unknown(_) => "unknown",
+                }
+            }
+
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $($api_name => Some(Self::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl From<$repr> for $name {
+            fn from(value: $repr) -> Self {
+                match value {
+                    $($value => Self::$variant,)*
+                    other => Self::Unknown(other),
+                }
+            }
+        }
+
+        impl From<$name> for $repr {
+            fn from(value: $name) -> Self {
+                match value {
+                    $($name::$variant => $value,)*
+                    $name::Unknown(other) => other,
+                }
+            }
+        }
+
+        // Same as decoding a zeroed buffer
+        impl Default for $name {
+            fn default() -> Self {
+                Self::from(0)
+            }
+        }
+
+        impl FromPacket for $name {
+            fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+                Ok(Self::from(reader.read::<$repr>()?))
+            }
+        }
+
+        impl ToPacket for $name {
+            fn write(&self, writer: &mut PacketWriter) {
+                writer.write(&<$repr>::from(*self));
+            }
+        }
+    };
+}
+
+packet_enum! {
+    /// Track IDs appendix. -1 is documented as unknown.
+    pub enum Track: i8 {
+        0 => Melbourne = "melbourne",
+        1 => PaulRicard = "paul_ricard",
+        2 => Shanghai = "shanghai",
+        3 => Sakhir = "sakhir",
+        4 => Catalunya = "catalunya",
+        5 => Monaco = "monaco",
+        6 => Montreal = "montreal",
+        7 => Silverstone = "silverstone",
+        8 => Hockenheim = "hockenheim",
+        9 => Hungaroring = "hungaroring",
+        10 => Spa = "spa",
+        11 => Monza = "monza",
+        12 => Singapore = "singapore",
+        13 => Suzuka = "suzuka",
+        14 => AbuDhabi = "abu_dhabi",
+        15 => Texas = "texas",
+        16 => Brazil = "brazil",
+        17 => Austria = "austria",
+        18 => Sochi = "sochi",
+        19 => Mexico = "mexico",
+        20 => Baku = "baku",
+        21 => SakhirShort = "sakhir_short",
+        22 => SilverstoneShort = "silverstone_short",
+        23 => TexasShort = "texas_short",
+        24 => SuzukaShort = "suzuka_short",
+        25 => Hanoi = "hanoi",
+        26 => Zandvoort = "zandvoort",
+        27 => Imola = "imola",
+        28 => Portimao = "portimao",
+        29 => Jeddah = "jeddah",
+        30 => Miami = "miami",
+        31 => LasVegas = "las_vegas",
+        32 => Losail = "losail",
+    }
+}
+
+packet_enum! {
+    /// Session types appendix. 0 is documented as unknown.
+    pub enum SessionType: u8 {
+        1 => Practice1 = "practice_1",
+        2 => Practice2 = "practice_2",
+        3 => Practice3 = "practice_3",
+        4 => ShortPractice = "short_practice",
+        5 => Qualifying1 = "qualifying_1",
+        6 => Qualifying2 = "qualifying_2",
+        7 => Qualifying3 = "qualifying_3",
+        8 => ShortQualifying = "short_qualifying",
+        9 => OneShotQualifying = "one_shot_qualifying",
+        10 => SprintShootout1 = "sprint_shootout_1",
+        11 => SprintShootout2 = "sprint_shootout_2",
+        12 => SprintShootout3 = "sprint_shootout_3",
+        13 => ShortSprintShootout = "short_sprint_shootout",
+        14 => OneShotSprintShootout = "one_shot_sprint_shootout",
+        15 => Race = "race",
+        16 => Race2 = "race_2",
+        17 => Race3 = "race_3",
+        18 => TimeTrial = "time_trial",
+    }
+}
+
+impl SessionType {
+    /// Decode a code of `format`'s session types appendix. F1 24 inserted the
+    /// sprint shootouts before the races, moving the codes after them up by
+    /// five.
+    pub fn from_format(value: u8, format: PacketFormat) -> Self {
+        match (format, value) {
+            (PacketFormat::F1_22 | PacketFormat::F1_23, 10..=13) => Self::from(value + 5),
+            (PacketFormat::F1_22 | PacketFormat::F1_23, 14..) => Self::Unknown(value),
+            _ => Self::from(value),
+        }
+    }
+}
+
+packet_enum! {
+    /// Game Mode IDs appendix
+    pub enum GameMode: u8 {
+        0 => EventMode = "event_mode",
+        3 => GrandPrix = "grand_prix",
+        4 => GrandPrix23 = "grand_prix_23",
+        5 => TimeTrial = "time_trial",
+        6 => Splitscreen = "splitscreen",
+        7 => OnlineCustom = "online_custom",
+        8 => OnlineLeague = "online_league",
+        11 => CareerInvitational = "career_invitational",
+        12 => ChampionshipInvitational = "championship_invitational",
+        13 => Championship = "championship",
+        14 => OnlineChampionship = "online_championship",
+        15 => OnlineWeeklyEvent = "online_weekly_event",
+        17 => StoryMode = "story_mode",
+        19 => Career22 = "career_22",
+        20 => Career22Online = "career_22_online",
+        21 => Career23 = "career_23",
+        22 => Career23Online = "career_23_online",
+        23 => DriverCareer24 = "driver_career_24",
+        24 => Career24Online = "career_24_online",
+        25 => MyTeamCareer24 = "my_team_career_24",
+        26 => CuratedCareer24 = "curated_career_24",
+        127 => Benchmark = "benchmark",
+    }
+}
+
+packet_enum! {
+    /// Ruleset IDs appendix
+    pub enum Ruleset: u8 {
+        0 => PracticeAndQualifying = "practice_and_qualifying",
+        1 => Race = "race",
+        2 => TimeTrial = "time_trial",
+        4 => TimeAttack = "time_attack",
+        6 => CheckpointChallenge = "checkpoint_challenge",
+        8 => Autocross = "autocross",
+        9 => Drift = "drift",
+        10 => AverageSpeedZone = "average_speed_zone",
+        11 => RivalDuel = "rival_duel",
+    }
+}
+
+packet_enum! {
+    /// Surface types appendix: what each wheel is in contact with
+    pub enum SurfaceType: u8 {
+        0 => Tarmac = "tarmac",
+        1 => RumbleStrip = "rumble_strip",
+        2 => Concrete = "concrete",
+        3 => Rock = "rock",
+        4 => Gravel = "gravel",
+        5 => Mud = "mud",
+        6 => Sand = "sand",
+        7 => Grass = "grass",
+        8 => Water = "water",
+        9 => Cobblestone = "cobblestone",
+        10 => Metal = "metal",
+        11 => Ridged = "ridged",
+    }
+}
+
+packet_enum! {
+    /// Penalty types appendix
+    pub enum PenaltyType: u8 {
+        0 => DriveThrough = "drive_through",
+        1 => StopGo = "stop_go",
+        2 => GridPenalty = "grid_penalty",
+        3 => PenaltyReminder = "penalty_reminder",
+        4 => TimePenalty = "time_penalty",
+        5 => Warning = "warning",
+        6 => Disqualified = "disqualified",
+        7 => RemovedFromFormationLap = "removed_from_formation_lap",
+        8 => ParkedTooLongTimer = "parked_too_long_timer",
+        9 => TyreRegulations = "tyre_regulations",
+        10 => ThisLapInvalidated = "this_lap_invalidated",
+        11 => ThisAndNextLapInvalidated = "this_and_next_lap_invalidated",
+        12 => ThisLapInvalidatedWithoutReason = "this_lap_invalidated_without_reason",
+        13 => ThisAndNextLapInvalidatedWithoutReason = "this_and_next_lap_invalidated_without_reason",
+        14 => ThisAndPreviousLapInvalidated = "this_and_previous_lap_invalidated",
+        15 => ThisAndPreviousLapInvalidatedWithoutReason = "this_and_previous_lap_invalidated_without_reason",
+        16 => Retired = "retired",
+        17 => BlackFlagTimer = "black_flag_timer",
+    }
+}
+
+packet_enum! {
+    /// Infringement types appendix
+    pub enum InfringementType: u8 {
+        0 => BlockingBySlowDriving = "blocking_by_slow_driving",
+        1 => BlockingByWrongWayDriving = "blocking_by_wrong_way_driving",
+        2 => ReversingOffTheStartLine = "reversing_off_the_start_line",
+        3 => BigCollision = "big_collision",
+        4 => SmallCollision = "small_collision",
+        5 => CollisionFailedToHandBackPositionSingle = "collision_failed_to_hand_back_position_single",
+        6 => CollisionFailedToHandBackPositionMultiple = "collision_failed_to_hand_back_position_multiple",
+        7 => CornerCuttingGainedTime = "corner_cutting_gained_time",
+        8 => CornerCuttingOvertakeSingle = "corner_cutting_overtake_single",
+        9 => CornerCuttingOvertakeMultiple = "corner_cutting_overtake_multiple",
+        10 => CrossedPitExitLane = "crossed_pit_exit_lane",
+        11 => IgnoringBlueFlags = "ignoring_blue_flags",
+        12 => IgnoringYellowFlags = "ignoring_yellow_flags",
+        13 => IgnoringDriveThrough = "ignoring_drive_through",
+        14 => TooManyDriveThroughs = "too_many_drive_throughs",
+        15 => DriveThroughReminderServeWithinNLaps = "drive_through_reminder_serve_within_n_laps",
+        16 => DriveThroughReminderServeThisLap = "drive_through_reminder_serve_this_lap",
+        17 => PitLaneSpeeding = "pit_lane_speeding",
+        18 => ParkedForTooLong = "parked_for_too_long",
+        19 => IgnoringTyreRegulations = "ignoring_tyre_regulations",
+        20 => TooManyPenalties = "too_many_penalties",
+        21 => MultipleWarnings = "multiple_warnings",
+        22 => ApproachingDisqualification = "approaching_disqualification",
+        23 => TyreRegulationsSelectSingle = "tyre_regulations_select_single",
+        24 => TyreRegulationsSelectMultiple = "tyre_regulations_select_multiple",
+        25 => LapInvalidatedCornerCutting = "lap_invalidated_corner_cutting",
+        26 => LapInvalidatedRunningWide = "lap_invalidated_running_wide",
+        27 => CornerCuttingRanWideGainedTimeMinor = "corner_cutting_ran_wide_gained_time_minor",
+        28 => CornerCuttingRanWideGainedTimeSignificant = "corner_cutting_ran_wide_gained_time_significant",
+        29 => CornerCuttingRanWideGainedTimeExtreme = "corner_cutting_ran_wide_gained_time_extreme",
+        30 => LapInvalidatedWallRiding = "lap_invalidated_wall_riding",
+        31 => LapInvalidatedFlashbackUsed = "lap_invalidated_flashback_used",
+        32 => LapInvalidatedResetToTrack = "lap_invalidated_reset_to_track",
+        33 => BlockingThePitlane = "blocking_the_pitlane",
+        34 => JumpStart = "jump_start",
+        35 => SafetyCarToCarCollision = "safety_car_to_car_collision",
+        36 => SafetyCarIllegalOvertake = "safety_car_illegal_overtake",
+        37 => SafetyCarExceedingAllowedPace = "safety_car_exceeding_allowed_pace",
+        38 => VirtualSafetyCarExceedingAllowedPace = "virtual_safety_car_exceeding_allowed_pace",
+        39 => FormationLapBelowAllowedSpeed = "formation_lap_below_allowed_speed",
+        40 => FormationLapParking = "formation_lap_parking",
+        41 => RetiredMechanicalFailure = "retired_mechanical_failure",
+        42 => RetiredTerminallyDamaged = "retired_terminally_damaged",
+        43 => SafetyCarFallingTooFarBack = "safety_car_falling_too_far_back",
+        44 => BlackFlagTimer = "black_flag_timer",
+        45 => UnservedStopGoPenalty = "unserved_stop_go_penalty",
+        46 => UnservedDriveThroughPenalty = "unserved_drive_through_penalty",
+        47 => EngineComponentChange = "engine_component_change",
+        48 => GearboxChange = "gearbox_change",
+        49 => ParcFermeChange = "parc_ferme_change",
+        50 => LeagueGridPenalty = "league_grid_penalty",
+        51 => RetryPenalty = "retry_penalty",
+        52 => IllegalTimeGain = "illegal_time_gain",
+        53 => MandatoryPitstop = "mandatory_pitstop",
+        54 => AttributeAssigned = "attribute_assigned",
+    }
+}
+
+packet_enum! {
+    /// Why a car retired, from the RTMT event
+    pub enum RetirementReason: u8 {
+        0 => Invalid = "invalid",
+        1 => Retired = "retired",
+        2 => Finished = "finished",
+        3 => TerminalDamage = "terminal_damage",
+        4 => Inactive = "inactive",
+        5 => NotEnoughLapsCompleted = "not_enough_laps_completed",
+        6 => BlackFlagged = "black_flagged",
+        7 => RedFlagged = "red_flagged",
+        8 => MechanicalFailure = "mechanical_failure",
+        9 => SessionSkipped = "session_skipped",
+        10 => SessionSimulated = "session_simulated",
+    }
+}
+
+packet_enum! {
+    /// Why race control disabled DRS, from the DRSD event
+    pub enum DrsDisabledReason: u8 {
+        0 => WetTrack = "wet_track",
+        1 => SafetyCarDeployed = "safety_car_deployed",
+        2 => RedFlag = "red_flag",
+        3 => MinLapNotReached = "min_lap_not_reached",
+    }
+}
+
+packet_enum! {
+    pub enum Weather: u8 {
+        0 => Clear = "clear",
+        1 => LightCloud = "light_cloud",
+        2 => Overcast = "overcast",
+        3 => LightRain = "light_rain",
+        4 => HeavyRain = "heavy_rain",
+        5 => Storm = "storm",
+    }
+}
+
+packet_enum! {
+    /// Forecast change in track or air temperature
+    pub enum TemperatureChange: i8 {
+        0 => Up = "up",
+        1 => Down = "down",
+        2 => NoChange = "no_change",
+    }
+}
+
+packet_enum! {
+    /// Supercars and F2 2021 are only in F1 22 and 23, the other codes mean
+    /// the same in every game
+    pub enum Formula: u8 {
+        0 => F1Modern = "f1_modern",
+        1 => F1Classic = "f1_classic",
+        2 => F2 = "f2",
+        3 => F1Generic = "f1_generic",
+        4 => Beta = "beta",
+        5 => Supercars = "supercars",
+        6 => Esports = "esports",
+        7 => F22021 = "f2_2021",
+        8 => F1World = "f1_world",
+        9 => F1Elimination = "f1_elimination",
+    }
+}
+
+packet_enum! {
+    /// Safety car currently out, also used by the SCAR event
+    pub enum SafetyCarStatus: u8 {
+        0 => NoSafetyCar = "no_safety_car",
+        1 => Full = "full",
+        2 => Virtual = "virtual",
+        3 => FormationLap = "formation_lap",
+    }
+}
+
+packet_enum! {
+    pub enum SafetyCarEventType: u8 {
+        0 => Deployed = "deployed",
+        1 => Returning = "returning",
+        2 => Returned = "returned",
+        3 => ResumeRace = "resume_race",
+    }
+}
+
+packet_enum! {
+    /// Marshal zone and vehicle flags
+    pub enum FiaFlag: i8 {
+        -1 => Invalid = "invalid",
+        0 => None = "none",
+        1 => Green = "green",
+        2 => Blue = "blue",
+        3 => Yellow = "yellow",
+    }
+}
+
+packet_enum! {
+    pub enum DriverStatus: u8 {
+        0 => InGarage = "in_garage",
+        1 => FlyingLap = "flying_lap",
+        2 => InLap = "in_lap",
+        3 => OutLap = "out_lap",
+        4 => OnTrack = "on_track",
+    }
+}
+
+packet_enum! {
+    pub enum PitStatus: u8 {
+        0 => None = "none",
+        1 => Pitting = "pitting",
+        2 => InPitArea = "in_pit_area",
+    }
+}
+
+packet_enum! {
+    pub enum ResultStatus: u8 {
+        0 => Invalid = "invalid",
+        1 => Inactive = "inactive",
+        2 => Active = "active",
+        3 => Finished = "finished",
+        4 => DidNotFinish = "did_not_finish",
+        5 => Disqualified = "disqualified",
+        6 => NotClassified = "not_classified",
+        7 => Retired = "retired",
+    }
+}
+
+packet_enum! {
+    /// Compound actually fitted, which can differ from what's shown on screen
+    pub enum ActualTyreCompound: u8 {
+        16 => C5 = "c5",
+        17 => C4 = "c4",
+        18 => C3 = "c3",
+        19 => C2 = "c2",
+        20 => C1 = "c1",
+        21 => C0 = "c0",
+        7 => Inter = "inter",
+        8 => Wet = "wet",
+        9 => ClassicDry = "classic_dry",
+        10 => ClassicWet = "classic_wet",
+        11 => F2SuperSoft = "f2_super_soft",
+        12 => F2Soft = "f2_soft",
+        13 => F2Medium = "f2_medium",
+        14 => F2Hard = "f2_hard",
+        15 => F2Wet = "f2_wet",
+    }
+}
+
+packet_enum! {
+    /// Compound as shown on screen
+    pub enum VisualTyreCompound: u8 {
+        16 => Soft = "soft",
+        17 => Medium = "medium",
+        18 => Hard = "hard",
+        7 => Inter = "inter",
+        8 => Wet = "wet",
+        15 => F2Wet = "f2_wet",
+        19 => F2SuperSoft = "f2_super_soft",
+        20 => F2Soft = "f2_soft",
+        21 => F2Medium = "f2_medium",
+        22 => F2Hard = "f2_hard",
+    }
+}
+
+packet_enum! {
+    pub enum ErsDeployMode: u8 {
+        0 => None = "none",
+        1 => Medium = "medium",
+        2 => Hotlap = "hotlap",
+        3 => Overtake = "overtake",
+    }
+}
+
+packet_enum! {
+    pub enum FuelMix: u8 {
+        0 => Lean = "lean",
+        1 => Standard = "standard",
+        2 => Rich = "rich",
+        3 => Max = "max",
+    }
+}
+
+packet_enum! {
+    pub enum TractionControl: u8 {
+        0 => Off = "off",
+        1 => Medium = "medium",
+        2 => Full = "full",
+    }
+}
+
+packet_enum! {
+    pub enum Platform: u8 {
+        1 => Steam = "steam",
+        3 => PlayStation = "playstation",
+        4 => Xbox = "xbox",
+        6 => Origin = "origin",
+    }
+}
+
+packet_enum! {
+    pub enum ReadyStatus: u8 {
+        0 => NotReady = "not_ready",
+        1 => Ready = "ready",
+        2 => Spectating = "spectating",
+    }
+}
+
+packet_enum! {
+    pub enum SessionLength: u8 {
+        0 => None = "none",
+        2 => VeryShort = "very_short",
+        3 => Short = "short",
+        4 => Medium = "medium",
+        5 => MediumLong = "medium_long",
+        6 => Long = "long",
+        7 => Full = "full",
+    }
+}
+
+/// Button flags appendix: buttons held on the controlling device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ButtonFlags(pub u32);
+
+impl FromPacket for ButtonFlags {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self(reader.read()?))
+    }
+}
+
+impl ToPacket for ButtonFlags {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.0);
+    }
+}
+
+impl ButtonFlags {
+    pub const CROSS_OR_A: u32 = 0x0000_0001;
+    pub const TRIANGLE_OR_Y: u32 = 0x0000_0002;
+    pub const CIRCLE_OR_B: u32 = 0x0000_0004;
+    pub const SQUARE_OR_X: u32 = 0x0000_0008;
+    pub const DPAD_LEFT: u32 = 0x0000_0010;
+    pub const DPAD_RIGHT: u32 = 0x0000_0020;
+    pub const DPAD_UP: u32 = 0x0000_0040;
+    pub const DPAD_DOWN: u32 = 0x0000_0080;
+    pub const OPTIONS_OR_MENU: u32 = 0x0000_0100;
+    pub const L1_OR_LB: u32 = 0x0000_0200;
+    pub const R1_OR_RB: u32 = 0x0000_0400;
+    pub const L2_OR_LT: u32 = 0x0000_0800;
+    pub const R2_OR_RT: u32 = 0x0000_1000;
+    pub const LEFT_STICK_CLICK: u32 = 0x0000_2000;
+    pub const RIGHT_STICK_CLICK: u32 = 0x0000_4000;
+    pub const RIGHT_STICK_LEFT: u32 = 0x0000_8000;
+    pub const RIGHT_STICK_RIGHT: u32 = 0x0001_0000;
+    pub const RIGHT_STICK_UP: u32 = 0x0002_0000;
+    pub const RIGHT_STICK_DOWN: u32 = 0x0004_0000;
+    pub const SPECIAL: u32 = 0x0008_0000;
+    // UDP Action 1 to 12 follow on from here, one bit each
+    pub const UDP_ACTION_1: u32 = 0x0010_0000;
+
+    pub fn contains(&self, flag: u32) -> bool {
+        self.0 & flag == flag
+    }
+
+    /// Whether UDP action `n` (1 to 12) is held
+    pub fn udp_action(&self, n: u8) -> bool {
+        (1..=12).contains(&n) && self.contains(Self::UDP_ACTION_1 << (n - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_types_of_earlier_games_are_moved_past_the_sprint_shootouts() {
+        for format in [PacketFormat::F1_22, PacketFormat::F1_23] {
+            assert_eq!(
+                SessionType::from_format(9, format),
+                SessionType::OneShotQualifying
+            );
+            assert_eq!(SessionType::from_format(10, format), SessionType::Race);
+            assert_eq!(SessionType::from_format(12, format), SessionType::Race3);
+            assert_eq!(SessionType::from_format(13, format), SessionType::TimeTrial);
+            assert_eq!(
+                SessionType::from_format(14, format),
+                SessionType::Unknown(14)
+            );
+        }
+
+        assert_eq!(
+            SessionType::from_format(10, PacketFormat::F1_24),
+            SessionType::SprintShootout1
+        );
+        assert_eq!(
+            SessionType::from_format(15, PacketFormat::F1_24),
+            SessionType::Race
+        );
+    }
+}
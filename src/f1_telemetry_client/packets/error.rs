@@ -0,0 +1,88 @@
+use super::header::PacketType;
+use std::fmt;
+
+/// Why a datagram couldn't be decoded
+#[derive(Debug, Clone)]
+pub enum PacketError {
+    /// Fewer bytes than the layout needs. `expected` is the size of the
+    /// layout, or for a bare reader where the field that ran out ends.
+    TooShort {
+        expected: usize,
+        actual: usize,
+    },
+    UnknownPacketId(u8),
+    /// A game other than those there are layouts for
+    UnsupportedFormat(u16),
+    /// A newer version of a packet than the layout is for
+    UnsupportedVersion {
+        packet_type: PacketType,
+        version: u8,
+    },
+    /// A code that must be known to read the rest of the packet
+    InvalidEnumValue {
+        field: &'static str,
+        value: String,
+    },
+    InvalidUtf8 {
+        offset: usize,
+    },
+}
+
+impl PacketError {
+    /// Names of the kinds of error, as returned by `kind`
+    pub const KINDS: &'static [&'static str] = &[
+        "too_short",
+        "unknown_packet_id",
+        "unsupported_format",
+        "unsupported_version",
+        "invalid_enum_value",
+        "invalid_utf8",
+    ];
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::TooShort { .. } => "too_short",
+            Self::UnknownPacketId(_) => "unknown_packet_id",
+            Self::UnsupportedFormat(_) => "unsupported_format",
+            Self::UnsupportedVersion { .. } => "unsupported_version",
+            Self::InvalidEnumValue { .. } => "invalid_enum_value",
+            Self::InvalidUtf8 { .. } => "invalid_utf8",
+        }
+    }
+}
+
+impl fmt::Display for PacketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort { expected, actual } => write!(
+                f,
+                "Packet too short: expected at least {} bytes, got {}",
+                expected, actual
+            ),
+            Self::UnknownPacketId(id) => write!(f, "Invalid packet type: {}", id),
+            Self::UnsupportedFormat(format) => {
+                write!(
+                    f,
+                    "Unsupported packet format: {} (expected 2022, 2023 or 2024)",
+                    format
+                )
+            }
+            Self::UnsupportedVersion {
+                packet_type,
+                version,
+            } => write!(
+                f,
+                "Unsupported version {} of {:?} packets",
+                version, packet_type
+            ),
+            Self::InvalidEnumValue { field, value } => {
+                write!(f, "Invalid value for {}: {}", field, value)
+            }
+            Self::InvalidUtf8 { offset } => {
+                write!(f, "Invalid UTF-8 in string at offset {}", offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PacketError {}
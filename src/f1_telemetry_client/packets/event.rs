@@ -1,3 +1,11 @@
+use super::enums::{
+    ButtonFlags, DrsDisabledReason, InfringementType, PenaltyType, RetirementReason,
+    SafetyCarEventType, SafetyCarStatus,
+};
+use super::error::PacketError;
+use super::format::PacketFormat;
+use super::reader::{FromPacket, PacketReader};
+use super::writer::{PacketWriter, ToPacket};
 use super::PacketSize;
 
 #[derive(Debug, Clone, Copy)]
@@ -9,6 +17,12 @@ pub struct FastestLap {
 #[derive(Debug, Clone, Copy)]
 pub struct Retirement {
     pub vehicle_idx: u8,
+    pub reason: Option<RetirementReason>, // Not sent by the games decoded
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DrsDisabled {
+    pub reason: Option<DrsDisabledReason>, // Not sent by the games decoded
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -23,8 +37,8 @@ pub struct RaceWinner {
 
 #[derive(Debug, Clone, Copy)]
 pub struct Penalty {
-    pub penalty_type: u8,
-    pub infringement_type: u8,
+    pub penalty_type: PenaltyType,
+    pub infringement_type: InfringementType,
     pub vehicle_idx: u8,
     pub other_vehicle_idx: u8,
     pub time: u8,
@@ -65,7 +79,7 @@ pub struct Flashback {
 
 #[derive(Debug, Clone, Copy)]
 pub struct Buttons {
-    pub button_status: u32,
+    pub button_status: ButtonFlags,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -76,8 +90,8 @@ pub struct Overtake {
 
 #[derive(Debug, Clone, Copy)]
 pub struct SafetyCar {
-    pub safety_car_type: u8,
-    pub event_type: u8,
+    pub safety_car_type: SafetyCarStatus,
+    pub event_type: SafetyCarEventType,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -88,17 +102,24 @@ pub struct Collision {
 
 #[derive(Debug, Clone, Copy)]
 pub enum EventDataDetails {
+    SessionStarted,
+    SessionEnded,
     FastestLap(FastestLap),
     Retirement(Retirement),
+    DrsEnabled,
+    DrsDisabled(DrsDisabled),
     TeamMateInPits(TeamMateInPits),
+    ChequeredFlag,
     RaceWinner(RaceWinner),
     Penalty(Penalty),
     SpeedTrap(SpeedTrap),
     StartLights(StartLights),
+    LightsOut,
     DriveThroughPenaltyServed(DriveThroughPenaltyServed),
     StopGoPenaltyServed(StopGoPenaltyServed),
     Flashback(Flashback),
     Buttons(Buttons),
+    RedFlag,
     Overtake(Overtake),
     SafetyCar(SafetyCar),
     Collision(Collision),
@@ -111,89 +132,93 @@ pub struct PacketEventData {
 }
 
 impl PacketSize for PacketEventData {
-    fn size() -> usize {
-        45 // Size specified in the UDP spec
+    fn size(_format: PacketFormat) -> usize {
+        16 // Size specified in the UDP spec, excluding the header
     }
 }
 
-impl TryFrom<&[u8]> for PacketEventData {
-    type Error = String;
-
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() < PacketEventData::size() {
-            return Err("Packet too short for PacketEventData".into());
-        }
+impl FromPacket for PacketEventData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        let event_string_code: [u8; 4] = reader.read()?;
+        let invalid_code = || PacketError::InvalidEnumValue {
+            field: "event_string_code",
+            value: String::from_utf8_lossy(&event_string_code).into_owned(),
+        };
+        let event_code = std::str::from_utf8(&event_string_code).map_err(|_| invalid_code())?;
 
-        let event_string_code = [bytes[0], bytes[1], bytes[2], bytes[3]];
-        let event_code =
-            std::str::from_utf8(&event_string_code).map_err(|_| "Invalid event string code")?;
+        // The details are a union, only as much of it is read as the event uses
+        let mut reader = reader.sub(12)?;
 
         let event_details = match event_code {
+            "SSTA" => EventDataDetails::SessionStarted,
+            "SEND" => EventDataDetails::SessionEnded,
             "FTLP" => EventDataDetails::FastestLap(FastestLap {
-                vehicle_idx: bytes[4],
-                lap_time: f32::from_le_bytes([bytes[5], bytes[6], bytes[7], bytes[8]]),
+                vehicle_idx: reader.read()?,
+                lap_time: reader.read()?,
             }),
+            // None of F1 22, 23 or 24 send the reasons later games add to
+            // these, the byte after them is the union's padding
             "RTMT" => EventDataDetails::Retirement(Retirement {
-                vehicle_idx: bytes[4],
+                vehicle_idx: reader.read()?,
+                reason: None,
             }),
+            "DRSE" => EventDataDetails::DrsEnabled,
+            "DRSD" => EventDataDetails::DrsDisabled(DrsDisabled { reason: None }),
             "TMPT" => EventDataDetails::TeamMateInPits(TeamMateInPits {
-                vehicle_idx: bytes[4],
+                vehicle_idx: reader.read()?,
             }),
+            "CHQF" => EventDataDetails::ChequeredFlag,
             "RCWN" => EventDataDetails::RaceWinner(RaceWinner {
-                vehicle_idx: bytes[4],
+                vehicle_idx: reader.read()?,
             }),
             "PENA" => EventDataDetails::Penalty(Penalty {
-                penalty_type: bytes[4],
-                infringement_type: bytes[5],
-                vehicle_idx: bytes[6],
-                other_vehicle_idx: bytes[7],
-                time: bytes[8],
-                lap_num: bytes[9],
-                places_gained: bytes[10],
+                penalty_type: reader.read()?,
+                infringement_type: reader.read()?,
+                vehicle_idx: reader.read()?,
+                other_vehicle_idx: reader.read()?,
+                time: reader.read()?,
+                lap_num: reader.read()?,
+                places_gained: reader.read()?,
             }),
             "SPTP" => EventDataDetails::SpeedTrap(SpeedTrap {
-                vehicle_idx: bytes[4],
-                speed: f32::from_le_bytes([bytes[5], bytes[6], bytes[7], bytes[8]]),
-                is_overall_fastest_in_session: bytes[9],
-                is_driver_fastest_in_session: bytes[10],
-                fastest_vehicle_idx_in_session: bytes[11],
-                fastest_speed_in_session: f32::from_le_bytes([
-                    bytes[12], bytes[13], bytes[14], bytes[15],
-                ]),
+                vehicle_idx: reader.read()?,
+                speed: reader.read()?,
+                is_overall_fastest_in_session: reader.read()?,
+                is_driver_fastest_in_session: reader.read()?,
+                fastest_vehicle_idx_in_session: reader.read()?,
+                fastest_speed_in_session: reader.read()?,
             }),
             "STLG" => EventDataDetails::StartLights(StartLights {
-                num_lights: bytes[4],
+                num_lights: reader.read()?,
             }),
+            "LGOT" => EventDataDetails::LightsOut,
             "DTSV" => EventDataDetails::DriveThroughPenaltyServed(DriveThroughPenaltyServed {
-                vehicle_idx: bytes[4],
+                vehicle_idx: reader.read()?,
             }),
             "SGSV" => EventDataDetails::StopGoPenaltyServed(StopGoPenaltyServed {
-                vehicle_idx: bytes[4],
+                vehicle_idx: reader.read()?,
             }),
             "FLBK" => EventDataDetails::Flashback(Flashback {
-                flashback_frame_identifier: u32::from_le_bytes([
-                    bytes[4], bytes[5], bytes[6], bytes[7],
-                ]),
-                flashback_session_time: f32::from_le_bytes([
-                    bytes[8], bytes[9], bytes[10], bytes[11],
-                ]),
+                flashback_frame_identifier: reader.read()?,
+                flashback_session_time: reader.read()?,
             }),
             "BUTN" => EventDataDetails::Buttons(Buttons {
-                button_status: u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+                button_status: reader.read()?,
             }),
+            "RDFL" => EventDataDetails::RedFlag,
             "OVTK" => EventDataDetails::Overtake(Overtake {
-                overtaking_vehicle_idx: bytes[4],
-                being_overtaken_vehicle_idx: bytes[5],
+                overtaking_vehicle_idx: reader.read()?,
+                being_overtaken_vehicle_idx: reader.read()?,
             }),
             "SCAR" => EventDataDetails::SafetyCar(SafetyCar {
-                safety_car_type: bytes[4],
-                event_type: bytes[5],
+                safety_car_type: reader.read()?,
+                event_type: reader.read()?,
             }),
             "COLL" => EventDataDetails::Collision(Collision {
-                vehicle1_idx: bytes[4],
-                vehicle2_idx: bytes[5],
+                vehicle1_idx: reader.read()?,
+                vehicle2_idx: reader.read()?,
             }),
-            _ => return Err(format!("Unknown event code: {}", event_code)),
+            _ => return Err(invalid_code()),
         };
 
         Ok(PacketEventData {
@@ -202,3 +227,161 @@ impl TryFrom<&[u8]> for PacketEventData {
         })
     }
 }
+
+impl ToPacket for PacketEventData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.event_string_code);
+
+        // The union is padded out to its full size
+        let start = writer.offset();
+        match &self.event_details {
+            EventDataDetails::SessionStarted
+            | EventDataDetails::SessionEnded
+            | EventDataDetails::DrsEnabled
+            | EventDataDetails::ChequeredFlag
+            | EventDataDetails::LightsOut
+            | EventDataDetails::RedFlag => {}
+            EventDataDetails::FastestLap(details) => {
+                writer.write(&details.vehicle_idx);
+                writer.write(&details.lap_time);
+            }
+            EventDataDetails::Retirement(details) => {
+                writer.write(&details.vehicle_idx);
+                if let Some(reason) = details.reason {
+                    writer.write(&reason);
+                }
+            }
+            EventDataDetails::DrsDisabled(details) => {
+                if let Some(reason) = details.reason {
+                    writer.write(&reason);
+                }
+            }
+            EventDataDetails::TeamMateInPits(details) => writer.write(&details.vehicle_idx),
+            EventDataDetails::RaceWinner(details) => writer.write(&details.vehicle_idx),
+            EventDataDetails::Penalty(details) => {
+                writer.write(&details.penalty_type);
+                writer.write(&details.infringement_type);
+                writer.write(&details.vehicle_idx);
+                writer.write(&details.other_vehicle_idx);
+                writer.write(&details.time);
+                writer.write(&details.lap_num);
+                writer.write(&details.places_gained);
+            }
+            EventDataDetails::SpeedTrap(details) => {
+                writer.write(&details.vehicle_idx);
+                writer.write(&details.speed);
+                writer.write(&details.is_overall_fastest_in_session);
+                writer.write(&details.is_driver_fastest_in_session);
+                writer.write(&details.fastest_vehicle_idx_in_session);
+                writer.write(&details.fastest_speed_in_session);
+            }
+            EventDataDetails::StartLights(details) => writer.write(&details.num_lights),
+            EventDataDetails::DriveThroughPenaltyServed(details) => {
+                writer.write(&details.vehicle_idx)
+            }
+            EventDataDetails::StopGoPenaltyServed(details) => writer.write(&details.vehicle_idx),
+            EventDataDetails::Flashback(details) => {
+                writer.write(&details.flashback_frame_identifier);
+                writer.write(&details.flashback_session_time);
+            }
+            EventDataDetails::Buttons(details) => writer.write(&details.button_status),
+            EventDataDetails::Overtake(details) => {
+                writer.write(&details.overtaking_vehicle_idx);
+                writer.write(&details.being_overtaken_vehicle_idx);
+            }
+            EventDataDetails::SafetyCar(details) => {
+                writer.write(&details.safety_car_type);
+                writer.write(&details.event_type);
+            }
+            EventDataDetails::Collision(details) => {
+                writer.write(&details.vehicle1_idx);
+                writer.write(&details.vehicle2_idx);
+            }
+        }
+        writer.zeros(12 - (writer.offset() - start));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::f1_telemetry_client::packets::header::{PacketHeader, PacketType};
+    use crate::f1_telemetry_client::packets::testing::{
+        assert_encodes_to, assert_round_trips_as_f1_24, datagram_with, earlier_formats,
+    };
+    use crate::f1_telemetry_client::TelemetryPacket;
+    use proptest::prelude::*;
+
+    // Every event code, with the bytes of the union its details use. The
+    // rest of the union is padding, written back as zeros.
+    const EVENTS: [(&[u8; 4], usize); 21] = [
+        (b"SSTA", 0),
+        (b"SEND", 0),
+        (b"FTLP", 5),
+        (b"RTMT", 1),
+        (b"DRSE", 0),
+        (b"DRSD", 0),
+        (b"TMPT", 1),
+        (b"CHQF", 0),
+        (b"RCWN", 1),
+        (b"PENA", 7),
+        (b"SPTP", 12),
+        (b"STLG", 1),
+        (b"LGOT", 0),
+        (b"DTSV", 1),
+        (b"SGSV", 1),
+        (b"FLBK", 8),
+        (b"BUTN", 4),
+        (b"RDFL", 0),
+        (b"OVTK", 2),
+        (b"SCAR", 2),
+        (b"COLL", 2),
+    ];
+
+    fn datagram(format: PacketFormat) -> BoxedStrategy<Vec<u8>> {
+        let body = (proptest::sample::select(&EVENTS[..]), any::<[u8; 12]>()).prop_map(
+            |((code, _), details)| {
+                let mut body = code.to_vec();
+                body.extend(details);
+                body
+            },
+        );
+        datagram_with(format, PacketType::Event, body)
+    }
+
+    proptest! {
+        #[test]
+        fn f1_24_datagrams_round_trip_but_for_padding(datagram in datagram(PacketFormat::F1_24)) {
+            let code = PacketHeader::size(PacketFormat::F1_24);
+            let (_, used) = EVENTS
+                .iter()
+                .find(|(c, _)| c[..] == datagram[code..code + 4])
+                .expect("generated from the codes");
+            let mut expected = datagram.clone();
+            expected[code + 4 + used..].fill(0);
+            assert_encodes_to(&datagram, &expected)?;
+        }
+
+        #[test]
+        fn unknown_event_codes_are_rejected(
+            mut datagram in datagram(PacketFormat::F1_24),
+            code in any::<[u8; 4]>(),
+        ) {
+            prop_assume!(EVENTS.iter().all(|(c, _)| **c != code));
+            let at = PacketHeader::size(PacketFormat::F1_24);
+            datagram[at..at + 4].copy_from_slice(&code);
+            let rejected = matches!(
+                TelemetryPacket::try_from(&datagram[..]),
+                Err(PacketError::InvalidEnumValue { field: "event_string_code", .. })
+            );
+            prop_assert!(rejected);
+        }
+
+        #[test]
+        fn earlier_games_round_trip_as_f1_24(
+            datagram in earlier_formats(PacketType::Event).prop_flat_map(datagram),
+        ) {
+            assert_round_trips_as_f1_24(&datagram, PacketEventData::size)?;
+        }
+    }
+}
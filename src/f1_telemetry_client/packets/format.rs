@@ -0,0 +1,35 @@
+use super::error::PacketError;
+use super::header::PacketType;
+
+/// Game whose packet layouts a datagram uses, from its `packet_format`
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PacketFormat {
+    F1_22,
+    F1_23,
+    F1_24,
+}
+
+impl PacketFormat {
+    /// Whether the game sends packets of this type at all
+    pub fn has(&self, packet_type: PacketType) -> bool {
+        match packet_type {
+            PacketType::TyreSets | PacketType::MotionEx => *self >= Self::F1_23,
+            PacketType::TimeTrial => *self >= Self::F1_24,
+            _ => true,
+        }
+    }
+}
+
+impl TryFrom<u16> for PacketFormat {
+    type Error = PacketError;
+
+    fn try_from(value: u16) -> Result<Self, PacketError> {
+        match value {
+            2022 => Ok(Self::F1_22),
+            2023 => Ok(Self::F1_23),
+            2024 => Ok(Self::F1_24),
+            _ => Err(PacketError::UnsupportedFormat(value)),
+        }
+    }
+}
@@ -1,6 +1,10 @@
-use super::PacketSize;
+use super::error::PacketError;
+use super::format::PacketFormat;
+use super::reader::{FromPacket, PacketReader};
+use super::writer::{PacketWriter, ToPacket};
+use super::{parse, PacketSize};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PacketType {
     Motion,
     Session,
@@ -20,9 +24,9 @@ pub enum PacketType {
 }
 
 impl TryFrom<u8> for PacketType {
-    type Error = String;
+    type Error = PacketError;
 
-    fn try_from(value: u8) -> Result<PacketType, String> {
+    fn try_from(value: u8) -> Result<PacketType, PacketError> {
         match value {
             0 => Ok(Self::Motion),
             1 => Ok(Self::Session),
@@ -39,14 +43,14 @@ impl TryFrom<u8> for PacketType {
             12 => Ok(Self::TyreSets),
             13 => Ok(Self::MotionEx),
             14 => Ok(Self::TimeTrial),
-            _ => Err(format!("Invalid packet type: {}", value)),
+            _ => Err(PacketError::UnknownPacketId(value)),
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct PacketHeader {
-    pub packet_format: u16,                     // 2024
+    pub packet_format: u16,                     // 2022, 2023 or 2024
     pub game_year: u8,                          // Game year - last two digits e.g. 24
     pub game_major_version: u8,                 // Game major version - "X.00"
     pub game_minor_version: u8,                 // Game minor version - "1.XX"
@@ -60,41 +64,177 @@ pub struct PacketHeader {
     pub secondary_player_car_index: Option<u8>, // Index of secondary player's car (255 if none)
 }
 
+impl PacketHeader {
+    /// Indices of the player's car and, in split-screen, the secondary player's car
+    pub fn player_car_indices(&self) -> Vec<usize> {
+        [Some(self.player_car_index), self.secondary_player_car_index]
+            .into_iter()
+            .flatten()
+            .map(usize::from)
+            .filter(|&i| i < 22)
+            .collect()
+    }
+
+    /// Game whose layouts the rest of the packet uses
+    pub fn format(&self) -> PacketFormat {
+        PacketFormat::try_from(self.packet_format).expect("checked when the header was read")
+    }
+}
+
 impl PacketSize for PacketHeader {
-    fn size() -> usize {
-        29
+    fn size(format: PacketFormat) -> usize {
+        match format {
+            PacketFormat::F1_22 => 24,
+            PacketFormat::F1_23 | PacketFormat::F1_24 => 29,
+        }
     }
 }
 
+// The header's own layout depends on the format it starts with
 impl TryFrom<&[u8]> for PacketHeader {
-    type Error = String;
+    type Error = PacketError;
 
-    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
-        if data.len() < PacketHeader::size() {
-            return Err("Packet too short for header".into());
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let packet_format = PacketReader::new(bytes, PacketFormat::F1_24)
+            .read::<u16>()
+            .map_err(|_| PacketError::TooShort {
+                expected: 2,
+                actual: bytes.len(),
+            })?;
+        let format = PacketFormat::try_from(packet_format)?;
+        let header: Self = parse(bytes, format)?;
+        if !format.has(header.packet_id) {
+            return Err(PacketError::UnknownPacketId(header.packet_id as u8));
         }
 
-        let packet_id: PacketType = data[6].try_into()?;
+        Ok(header)
+    }
+}
+
+impl FromPacket for PacketHeader {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        // F1 22 has neither the game year nor the overall frame identifier,
+        // which stand in as 22 and the frame identifier
+        let f1_22 = reader.format() == PacketFormat::F1_22;
+        let packet_format = reader.read()?;
+        let game_year = if f1_22 { 22 } else { reader.read()? };
+        let game_major_version = reader.read()?;
+        let game_minor_version = reader.read()?;
+        let packet_version = reader.read()?;
+        let packet_id = reader.read::<u8>()?.try_into()?;
+        let session_uid = reader.read()?;
+        let session_time = reader.read()?;
+        let frame_identifier = reader.read()?;
 
         Ok(PacketHeader {
-            packet_format: u16::from_le_bytes([data[0], data[1]]),
-            game_year: data[2],
-            game_major_version: data[3],
-            game_minor_version: data[4],
-            packet_version: data[5],
+            packet_format,
+            game_year,
+            game_major_version,
+            game_minor_version,
+            packet_version,
             packet_id,
-            session_uid: u64::from_le_bytes([
-                data[7], data[8], data[9], data[10], data[11], data[12], data[13], data[14],
-            ]),
-            session_time: f32::from_le_bytes([data[15], data[16], data[17], data[18]]),
-            frame_identifier: u32::from_le_bytes([data[19], data[20], data[21], data[22]]),
-            overall_frame_identifier: u32::from_le_bytes([data[23], data[24], data[25], data[26]]),
-            player_car_index: data[27],
-            secondary_player_car_index: if data[28] == 255 {
-                None
+            session_uid,
+            session_time,
+            frame_identifier,
+            overall_frame_identifier: if f1_22 {
+                frame_identifier
             } else {
-                Some(data[28])
+                reader.read()?
+            },
+            player_car_index: reader.read()?,
+            secondary_player_car_index: match reader.read()? {
+                255 => None,
+                index => Some(index),
             },
         })
     }
 }
+
+// Always written in the F1 24 layout, whatever game the header came from
+impl ToPacket for PacketHeader {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&2024u16);
+        writer.write(&self.game_year);
+        writer.write(&self.game_major_version);
+        writer.write(&self.game_minor_version);
+        writer.write(&self.packet_version);
+        writer.write(&(self.packet_id as u8));
+        writer.write(&self.session_uid);
+        writer.write(&self.session_time);
+        writer.write(&self.frame_identifier);
+        writer.write(&self.overall_frame_identifier);
+        writer.write(&self.player_car_index);
+        writer.write(&self.secondary_player_car_index.unwrap_or(255));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::f1_telemetry_client::packets::testing;
+    use proptest::prelude::*;
+
+    fn header(secondary_player_car_index: Option<u8>) -> PacketHeader {
+        PacketHeader {
+            packet_format: 2024,
+            game_year: 24,
+            game_major_version: 1,
+            game_minor_version: 5,
+            packet_version: 1,
+            packet_id: PacketType::LapData,
+            session_uid: 0x0123_4567_89ab_cdef,
+            session_time: 754.25,
+            frame_identifier: 40_500,
+            overall_frame_identifier: 41_000,
+            player_car_index: 3,
+            secondary_player_car_index,
+        }
+    }
+
+    #[test]
+    fn reads_back_an_f1_24_header() {
+        for secondary_player_car_index in [None, Some(7)] {
+            let header = header(secondary_player_car_index);
+            let bytes = header.to_bytes();
+
+            assert_eq!(bytes.len(), PacketHeader::size(PacketFormat::F1_24));
+            assert_eq!(bytes[28], secondary_player_car_index.unwrap_or(255));
+            assert_eq!(PacketHeader::try_from(&bytes[..]).unwrap(), header);
+        }
+    }
+
+    #[test]
+    fn writes_an_f1_22_header_in_the_f1_24_layout() {
+        let mut bytes = 2022u16.to_le_bytes().to_vec();
+        bytes.extend([1, 18, 1, PacketType::LapData as u8]);
+        bytes.extend(0x0123_4567_89ab_cdefu64.to_le_bytes());
+        bytes.extend(754.25f32.to_le_bytes());
+        bytes.extend(40_500u32.to_le_bytes());
+        bytes.extend([3, 255]);
+
+        let f1_22 = PacketHeader::try_from(&bytes[..]).unwrap();
+        let f1_24 = PacketHeader::try_from(&f1_22.to_bytes()[..]).unwrap();
+        assert_eq!(f1_24.format(), PacketFormat::F1_24);
+        assert_eq!(f1_24.game_year, 22);
+        assert_eq!(f1_24.overall_frame_identifier, 40_500);
+        assert_eq!(
+            PacketHeader {
+                packet_format: 2022,
+                ..f1_24
+            },
+            f1_22
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn f1_24_headers_round_trip(
+            bytes in (0..15u8).prop_flat_map(|id| {
+                testing::header(PacketFormat::F1_24, PacketType::try_from(id).unwrap())
+            }),
+        ) {
+            let header = PacketHeader::try_from(&bytes[..]).unwrap();
+            prop_assert_eq!(header.to_bytes(), bytes);
+        }
+    }
+}
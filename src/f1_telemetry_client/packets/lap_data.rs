@@ -1,3 +1,8 @@
+use super::enums::{DriverStatus, PitStatus, ResultStatus};
+use super::error::PacketError;
+use super::format::PacketFormat;
+use super::reader::{FromPacket, PacketReader};
+use super::writer::{PacketWriter, ToPacket};
 use super::PacketSize;
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -33,7 +38,7 @@ pub struct LapData {
     // Current lap number
     pub current_lap_num: u8,
     // 0 = none, 1 = pitting, 2 = in pit area
-    pub pit_status: u8,
+    pub pit_status: PitStatus,
     // Number of pit stops taken in this race
     pub num_pit_stops: u8,
     // 0 = sector1, 1 = sector2, 2 = sector3
@@ -53,9 +58,9 @@ pub struct LapData {
     // Grid position the vehicle started the race in
     pub grid_position: u8,
     // Status of driver - 0 = in garage, 1 = flying lap, 2 = in lap, 3 = out lap, 4 = on track
-    pub driver_status: u8,
+    pub driver_status: DriverStatus,
     // Result status - 0 = invalid, 1 = inactive, 2 = active, 3 = finished, 4 = dnf, 5 = dsq, 6 = not classified, 7 = retired
-    pub result_status: u8,
+    pub result_status: ResultStatus,
     // Pit lane timing, 0 = inactive, 1 = active
     pub pit_lane_timer_active: u8,
     // If active, the current time spent in the pit lane in ms
@@ -70,60 +75,125 @@ pub struct LapData {
     pub speed_trap_fastest_lap: u8,
 }
 
-impl PacketSize for LapData {
-    fn size() -> usize {
-        57
+impl LapData {
+    // Sector 1 time with the minute and millisecond parts combined
+    pub fn sector1_time_in_ms(&self) -> u32 {
+        u32::from(self.sector1_time_minutes_part) * 60_000 + u32::from(self.sector1_time_ms_part)
     }
-}
 
-impl TryFrom<&[u8]> for LapData {
-    type Error = String;
+    // Sector 2 time with the minute and millisecond parts combined
+    pub fn sector2_time_in_ms(&self) -> u32 {
+        u32::from(self.sector2_time_minutes_part) * 60_000 + u32::from(self.sector2_time_ms_part)
+    }
 
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() < LapData::size() {
-            return Err("Buffer too small for LapData".into());
+    // Gap to the car ahead with the minute and millisecond parts combined
+    pub fn delta_to_car_in_front_in_ms(&self) -> u32 {
+        u32::from(self.delta_to_car_in_front_minutes_part) * 60_000
+            + u32::from(self.delta_to_car_in_front_ms_part)
+    }
+
+    // Gap to the leader with the minute and millisecond parts combined
+    pub fn delta_to_race_leader_in_ms(&self) -> u32 {
+        u32::from(self.delta_to_race_leader_minutes_part) * 60_000
+            + u32::from(self.delta_to_race_leader_ms_part)
+    }
+}
+
+impl PacketSize for LapData {
+    fn size(format: PacketFormat) -> usize {
+        match format {
+            PacketFormat::F1_22 => 43,
+            PacketFormat::F1_23 => 50,
+            PacketFormat::F1_24 => 57,
         }
+    }
+}
 
+// F1 22 sends sector times in milliseconds only and no gaps; F1 23 sends
+// the gaps in milliseconds only. Parts an older game doesn't send are 0.
+impl FromPacket for LapData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
         Ok(Self {
-            last_lap_time_in_ms: u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
-            current_lap_time_in_ms: u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
-            sector1_time_ms_part: u16::from_le_bytes([bytes[8], bytes[9]]),
-            sector1_time_minutes_part: bytes[10],
-            sector2_time_ms_part: u16::from_le_bytes([bytes[11], bytes[12]]),
-            sector2_time_minutes_part: bytes[13],
-            delta_to_car_in_front_ms_part: u16::from_le_bytes([bytes[14], bytes[15]]),
-            delta_to_car_in_front_minutes_part: bytes[16],
-            delta_to_race_leader_ms_part: u16::from_le_bytes([bytes[17], bytes[18]]),
-            delta_to_race_leader_minutes_part: bytes[19],
-            lap_distance: f32::from_le_bytes([bytes[20], bytes[21], bytes[22], bytes[23]]),
-            total_distance: f32::from_le_bytes([bytes[24], bytes[25], bytes[26], bytes[27]]),
-            safety_car_delta: f32::from_le_bytes([bytes[28], bytes[29], bytes[30], bytes[31]]),
-            car_position: bytes[32],
-            current_lap_num: bytes[33],
-            pit_status: bytes[34],
-            num_pit_stops: bytes[35],
-            sector: bytes[36],
-            current_lap_invalid: bytes[37],
-            penalties: bytes[38],
-            total_warnings: bytes[39],
-            corner_cutting_warnings: bytes[40],
-            num_unserved_drive_through_pens: bytes[41],
-            num_unserved_stop_go_pens: bytes[42],
-            grid_position: bytes[43],
-            driver_status: bytes[44],
-            result_status: bytes[45],
-            pit_lane_timer_active: bytes[46],
-            pit_lane_time_in_lane_in_ms: u16::from_le_bytes([bytes[47], bytes[48]]),
-            pit_stop_timer_in_ms: u16::from_le_bytes([bytes[49], bytes[50]]),
-            pit_stop_should_serve_pen: bytes[51],
-            speed_trap_fastest_speed: f32::from_le_bytes([
-                bytes[52], bytes[53], bytes[54], bytes[55],
-            ]),
-            speed_trap_fastest_lap: bytes[56],
+            last_lap_time_in_ms: reader.read()?,
+            current_lap_time_in_ms: reader.read()?,
+            sector1_time_ms_part: reader.read()?,
+            sector1_time_minutes_part: reader.since(PacketFormat::F1_23)?,
+            sector2_time_ms_part: reader.read()?,
+            sector2_time_minutes_part: reader.since(PacketFormat::F1_23)?,
+            delta_to_car_in_front_ms_part: reader.since(PacketFormat::F1_23)?,
+            delta_to_car_in_front_minutes_part: reader.since(PacketFormat::F1_24)?,
+            delta_to_race_leader_ms_part: reader.since(PacketFormat::F1_23)?,
+            delta_to_race_leader_minutes_part: reader.since(PacketFormat::F1_24)?,
+            lap_distance: reader.read()?,
+            total_distance: reader.read()?,
+            safety_car_delta: reader.read()?,
+            car_position: reader.read()?,
+            current_lap_num: reader.read()?,
+            pit_status: reader.read()?,
+            num_pit_stops: reader.read()?,
+            sector: reader.read()?,
+            current_lap_invalid: reader.read()?,
+            penalties: reader.read()?,
+            total_warnings: reader.read()?,
+            corner_cutting_warnings: reader.since(PacketFormat::F1_23)?,
+            num_unserved_drive_through_pens: reader.read()?,
+            num_unserved_stop_go_pens: reader.read()?,
+            grid_position: reader.read()?,
+            driver_status: reader.read()?,
+            result_status: reader.read()?,
+            pit_lane_timer_active: reader.read()?,
+            pit_lane_time_in_lane_in_ms: reader.read()?,
+            pit_stop_timer_in_ms: reader.read()?,
+            pit_stop_should_serve_pen: reader.read()?,
+            speed_trap_fastest_speed: reader.since(PacketFormat::F1_24)?,
+            // Not set when the game doesn't send it
+            speed_trap_fastest_lap: if reader.format() >= PacketFormat::F1_24 {
+                reader.read()?
+            } else {
+                255
+            },
         })
     }
 }
 
+impl ToPacket for LapData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.last_lap_time_in_ms);
+        writer.write(&self.current_lap_time_in_ms);
+        writer.write(&self.sector1_time_ms_part);
+        writer.write(&self.sector1_time_minutes_part);
+        writer.write(&self.sector2_time_ms_part);
+        writer.write(&self.sector2_time_minutes_part);
+        writer.write(&self.delta_to_car_in_front_ms_part);
+        writer.write(&self.delta_to_car_in_front_minutes_part);
+        writer.write(&self.delta_to_race_leader_ms_part);
+        writer.write(&self.delta_to_race_leader_minutes_part);
+        writer.write(&self.lap_distance);
+        writer.write(&self.total_distance);
+        writer.write(&self.safety_car_delta);
+        writer.write(&self.car_position);
+        writer.write(&self.current_lap_num);
+        writer.write(&self.pit_status);
+        writer.write(&self.num_pit_stops);
+        writer.write(&self.sector);
+        writer.write(&self.current_lap_invalid);
+        writer.write(&self.penalties);
+        writer.write(&self.total_warnings);
+        writer.write(&self.corner_cutting_warnings);
+        writer.write(&self.num_unserved_drive_through_pens);
+        writer.write(&self.num_unserved_stop_go_pens);
+        writer.write(&self.grid_position);
+        writer.write(&self.driver_status);
+        writer.write(&self.result_status);
+        writer.write(&self.pit_lane_timer_active);
+        writer.write(&self.pit_lane_time_in_lane_in_ms);
+        writer.write(&self.pit_stop_timer_in_ms);
+        writer.write(&self.pit_stop_should_serve_pen);
+        writer.write(&self.speed_trap_fastest_speed);
+        writer.write(&self.speed_trap_fastest_lap);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PacketLapData {
     // Lap data for all cars on track
@@ -135,29 +205,51 @@ pub struct PacketLapData {
 }
 
 impl PacketSize for PacketLapData {
-    fn size() -> usize {
-        1256
+    fn size(format: PacketFormat) -> usize {
+        LapData::size(format) * 22 + 2
     }
 }
 
-impl TryFrom<&[u8]> for PacketLapData {
-    type Error = String;
+impl FromPacket for PacketLapData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            lap_data: reader.read()?,
+            time_trial_pb_car_idx: reader.read()?,
+            time_trial_rival_car_idx: reader.read()?,
+        })
+    }
+}
 
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() < PacketLapData::size() {
-            return Err("Buffer too small for PacketLapData".into());
-        }
+impl ToPacket for PacketLapData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.lap_data);
+        writer.write(&self.time_trial_pb_car_idx);
+        writer.write(&self.time_trial_rival_car_idx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::f1_telemetry_client::packets::header::PacketType;
+    use crate::f1_telemetry_client::packets::testing::{
+        assert_encodes_to, assert_round_trips_as_f1_24, datagram, earlier_datagram,
+    };
+    use proptest::prelude::*;
 
-        let mut lap_data = [LapData::default(); 22];
-        for i in 0..22 {
-            let start = i * LapData::size();
-            lap_data[i] = LapData::try_from(&bytes[start..start + LapData::size()])?;
+    proptest! {
+        #[test]
+        fn f1_24_datagrams_round_trip(
+            datagram in datagram(PacketFormat::F1_24, PacketType::LapData, PacketLapData::size),
+        ) {
+            assert_encodes_to(&datagram, &datagram)?;
         }
 
-        Ok(Self {
-            lap_data,
-            time_trial_pb_car_idx: bytes[22 * LapData::size()],
-            time_trial_rival_car_idx: bytes[(22 * LapData::size()) + 1],
-        })
+        #[test]
+        fn earlier_games_round_trip_as_f1_24(
+            datagram in earlier_datagram(PacketType::LapData, PacketLapData::size),
+        ) {
+            assert_round_trips_as_f1_24(&datagram, PacketLapData::size)?;
+        }
     }
 }
@@ -1,97 +1,163 @@
+use super::enums::{Platform, ReadyStatus};
+use super::error::PacketError;
+use super::format::PacketFormat;
+use super::reader::{FromPacket, PacketReader};
+use super::writer::{PacketWriter, ToPacket};
 use super::PacketSize;
 
 #[derive(Debug, Clone)]
 pub struct LobbyInfoData {
-    pub ai_controlled: u8, // Whether the vehicle is AI (1) or Human (0) controlled
-    pub team_id: u8,       // Team id - see appendix (255 if no team currently selected)
-    pub nationality: u8,   // Nationality of the driver
-    pub platform: u8,      // 1 = Steam, 3 = PlayStation, 4 = Xbox, 6 = Origin, 255 = unknown
-    pub name: String,      // Name of participant in UTF-8 format -- null terminated
+    pub ai_controlled: u8,  // Whether the vehicle is AI (1) or Human (0) controlled
+    pub team_id: u8,        // Team id - see appendix (255 if no team currently selected)
+    pub nationality: u8,    // Nationality of the driver
+    pub platform: Platform, // 1 = Steam, 3 = PlayStation, 4 = Xbox, 6 = Origin, 255 = unknown
+    pub name: String,       // Name of participant in UTF-8 format -- null terminated
     // Will be truncated with ... (U+2026) if too long
-    pub car_number: u8,        // Car number of the player
-    pub your_telemetry: u8,    // The player's UDP setting, 0 = restricted, 1 = public
-    pub show_online_names: u8, // The player's show online names setting, 0 = off, 1 = on
-    pub tech_level: u16,       // F1 World tech level
-    pub ready_status: u8,      // 0 = not ready, 1 = ready, 2 = spectating
+    pub car_number: u8,            // Car number of the player
+    pub your_telemetry: u8,        // The player's UDP setting, 0 = restricted, 1 = public
+    pub show_online_names: u8,     // The player's show online names setting, 0 = off, 1 = on
+    pub tech_level: u16,           // F1 World tech level
+    pub ready_status: ReadyStatus, // 0 = not ready, 1 = ready, 2 = spectating
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct PacketLobbyInfoData {
     pub num_players: u8,                   // Number of players in the lobby data
     pub lobby_players: Vec<LobbyInfoData>, // Data for all players in the lobby
 }
 
 impl PacketSize for PacketLobbyInfoData {
-    fn size() -> usize {
-        1306 // Size specified in the UDP spec
+    fn size(format: PacketFormat) -> usize {
+        // Size specified in the UDP spec, excluding the header
+        match format {
+            PacketFormat::F1_22 => 1167,
+            PacketFormat::F1_23 => 1189,
+            PacketFormat::F1_24 => 1277,
+        }
     }
 }
 
-impl TryFrom<&[u8]> for PacketLobbyInfoData {
-    type Error = String;
-
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() < PacketLobbyInfoData::size() {
-            return Err("Packet too short for PacketLobbyInfoData".into());
-        }
-
-        let num_players = bytes[0];
-        let mut lobby_players = Vec::with_capacity(22);
-        let mut offset = 1;
-
-        // Parse lobby data for each player
-        for _ in 0..22 {
-            // Process name as UTF-8 string with null termination
-            let name_bytes = &bytes[offset + 4..offset + 52]; // 48 bytes for name
-            let name_end = name_bytes.iter().position(|&x| x == 0).unwrap_or(48);
-            let name = String::from_utf8(name_bytes[..name_end].to_vec())
-                .map_err(|_| "Invalid UTF-8 in player name")?;
+impl FromPacket for PacketLobbyInfoData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            num_players: reader.read()?,
+            lobby_players: reader.vec(22)?,
+        })
+    }
+}
 
-            let data = LobbyInfoData {
-                ai_controlled: bytes[offset],
-                team_id: bytes[offset + 1],
-                nationality: bytes[offset + 2],
-                platform: bytes[offset + 3],
-                name,
-                car_number: bytes[offset + 52],
-                your_telemetry: bytes[offset + 53],
-                show_online_names: bytes[offset + 54],
-                tech_level: u16::from_le_bytes([bytes[offset + 55], bytes[offset + 56]]),
-                ready_status: bytes[offset + 57],
-            };
-            lobby_players.push(data);
-            offset += 58; // Size of each lobby player data block
-        }
+impl ToPacket for PacketLobbyInfoData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.num_players);
+        writer.vec(&self.lobby_players, 22);
+    }
+}
 
-        Ok(PacketLobbyInfoData {
-            num_players,
-            lobby_players,
+// F1 22 has no platform, and neither it nor F1 23 send the player's
+// settings or tech level
+impl FromPacket for LobbyInfoData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            ai_controlled: reader.read()?,
+            team_id: reader.read()?,
+            nationality: reader.read()?,
+            platform: if reader.format() >= PacketFormat::F1_23 {
+                reader.read()?
+            } else {
+                Platform::Unknown(255)
+            },
+            name: reader.string(48)?,
+            car_number: reader.read()?,
+            your_telemetry: reader.since(PacketFormat::F1_24)?,
+            show_online_names: reader.since(PacketFormat::F1_24)?,
+            tech_level: reader.since(PacketFormat::F1_24)?,
+            ready_status: reader.read()?,
         })
     }
 }
 
+impl ToPacket for LobbyInfoData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.ai_controlled);
+        writer.write(&self.team_id);
+        writer.write(&self.nationality);
+        writer.write(&self.platform);
+        writer.string(&self.name, 48);
+        writer.write(&self.car_number);
+        writer.write(&self.your_telemetry);
+        writer.write(&self.show_online_names);
+        writer.write(&self.tech_level);
+        writer.write(&self.ready_status);
+    }
+}
+
 impl Default for LobbyInfoData {
     fn default() -> Self {
         LobbyInfoData {
             ai_controlled: 0,
             team_id: 255,
             nationality: 0,
-            platform: 255,
+            platform: Platform::Unknown(255),
             name: String::new(),
             car_number: 0,
             your_telemetry: 0,
             show_online_names: 0,
             tech_level: 0,
-            ready_status: 0,
+            ready_status: ReadyStatus::NotReady,
         }
     }
 }
 
-impl Default for PacketLobbyInfoData {
-    fn default() -> Self {
-        PacketLobbyInfoData {
-            num_players: 0,
-            lobby_players: Vec::new(),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::f1_telemetry_client::packets::header::{PacketHeader, PacketType};
+    use crate::f1_telemetry_client::packets::testing::{
+        assert_encodes_to, assert_invalid_utf8, assert_round_trips_as_f1_24, body_with_names,
+        datagram_with, earlier_formats, written_names,
+    };
+    use proptest::prelude::*;
+
+    // Offsets of the players' names in the body
+    fn name_offsets(format: PacketFormat) -> Vec<usize> {
+        let stride = (PacketLobbyInfoData::size(format) - 1) / 22;
+        // F1 22 has no platform before the name
+        let name = if format == PacketFormat::F1_22 { 3 } else { 4 };
+        (0..22).map(|i| 1 + i * stride + name).collect()
+    }
+
+    fn datagram(format: PacketFormat) -> BoxedStrategy<Vec<u8>> {
+        let body = body_with_names(PacketLobbyInfoData::size(format), name_offsets(format));
+        datagram_with(format, PacketType::LobbyInfo, body)
+    }
+
+    proptest! {
+        #[test]
+        fn f1_24_datagrams_round_trip_but_for_names(datagram in datagram(PacketFormat::F1_24)) {
+            let expected = written_names(&datagram, &name_offsets(PacketFormat::F1_24));
+            assert_encodes_to(&datagram, &expected.expect("names are UTF-8"))?;
+        }
+
+        #[test]
+        fn arbitrary_names_round_trip_or_are_rejected(
+            mut datagram in datagram(PacketFormat::F1_24),
+            player in 0..22usize,
+            name in any::<[u8; 48]>(),
+        ) {
+            let at = name_offsets(PacketFormat::F1_24)[player]
+                + PacketHeader::size(PacketFormat::F1_24);
+            datagram[at..at + name.len()].copy_from_slice(&name);
+            match written_names(&datagram, &name_offsets(PacketFormat::F1_24)) {
+                Some(expected) => assert_encodes_to(&datagram, &expected)?,
+                None => assert_invalid_utf8(&datagram)?,
+            }
+        }
+
+        #[test]
+        fn earlier_games_round_trip_as_f1_24(
+            datagram in earlier_formats(PacketType::LobbyInfo).prop_flat_map(datagram),
+        ) {
+            assert_round_trips_as_f1_24(&datagram, PacketLobbyInfoData::size)?;
         }
     }
 }
@@ -3,17 +3,265 @@ pub mod car_motion_data;
 pub mod car_setups;
 pub mod car_status;
 pub mod car_telemetry;
+pub mod enums;
+pub mod error;
 pub mod event;
 pub mod final_classification;
+pub mod format;
 pub mod header;
 pub mod lap_data;
 pub mod lobby_info;
 pub mod motion_ex;
 pub mod participants;
+pub mod reader;
 pub mod session_data;
+pub mod session_history;
 pub mod time_trial;
 pub mod tyre_sets;
+pub mod writer;
 
+use error::PacketError;
+use format::PacketFormat;
+use reader::{FromPacket, PacketReader};
+
+/// Size in bytes of a layout in each game's packets
 pub trait PacketSize {
-    fn size() -> usize;
+    fn size(format: PacketFormat) -> usize;
+}
+
+/// Read a packet, or the part of one, whose layout is `T` in `format`
+pub fn parse<T: FromPacket + PacketSize>(
+    bytes: &[u8],
+    format: PacketFormat,
+) -> Result<T, PacketError> {
+    let mut reader = PacketReader::new(bytes, format);
+    let value = reader.read().map_err(|e| match e {
+        PacketError::TooShort { actual, .. } => PacketError::TooShort {
+            expected: T::size(format),
+            actual,
+        },
+        e => e,
+    })?;
+
+    // The fields read must add up to the size given for the layout
+    debug_assert_eq!(
+        reader.offset(),
+        T::size(format),
+        "layout of {} in {:?} doesn't match its size",
+        std::any::type_name::<T>(),
+        format
+    );
+
+    Ok(value)
+}
+
+// Strategies and checks for the round-trip property tests of each packet
+// type. Decoding and encoding an F1 24 datagram gives back its bytes, with
+// three exceptions:
+// - Whatever follows the terminator of a name is written back as zeros
+// - A name that fills its field without a terminator loses its last
+//   character, or more than one byte of it, to make room for one
+// - The bytes of the event details union an event doesn't use are written
+//   back as zeros
+// Earlier games' datagrams are encoded in the F1 24 layouts, so for them the
+// property is that the F1 24 datagram they encode to round-trips.
+#[cfg(test)]
+mod testing {
+    use super::error::PacketError;
+    use super::format::PacketFormat;
+    use super::header::{PacketHeader, PacketType};
+    use super::writer::ToPacket;
+    use super::PacketSize;
+    use crate::f1_telemetry_client::TelemetryPacket;
+    use proptest::prelude::*;
+    use proptest::test_runner::TestCaseError;
+
+    // Size of the name fields of participants and lobby players
+    const NAME_SIZE: usize = 48;
+
+    /// Headers of `packet_type` in `format`'s layout with arbitrary values in
+    /// every field that doesn't decide how the rest is read
+    pub fn header(format: PacketFormat, packet_type: PacketType) -> BoxedStrategy<Vec<u8>> {
+        let packet_format: u16 = match format {
+            PacketFormat::F1_22 => 2022,
+            PacketFormat::F1_23 => 2023,
+            PacketFormat::F1_24 => 2024,
+        };
+        (
+            any::<[u8; 3]>(),
+            0..=1u8,
+            any::<[u8; 8]>(),
+            any::<[u8; 4]>(),
+            any::<[u8; 4]>(),
+            any::<[u8; 4]>(),
+            any::<[u8; 2]>(),
+        )
+            .prop_map(
+                move |(versions, packet_version, uid, time, frame, overall_frame, players)| {
+                    let [year, major, minor] = versions;
+                    let mut bytes = packet_format.to_le_bytes().to_vec();
+                    if format != PacketFormat::F1_22 {
+                        bytes.push(year);
+                    }
+                    bytes.extend([major, minor, packet_version, packet_type as u8]);
+                    bytes.extend(uid);
+                    bytes.extend(time);
+                    bytes.extend(frame);
+                    if format != PacketFormat::F1_22 {
+                        bytes.extend(overall_frame);
+                    }
+                    bytes.extend(players);
+                    bytes
+                },
+            )
+            .boxed()
+    }
+
+    /// Datagrams of `packet_type` in `format` with an arbitrary body of the
+    /// size `size` gives for it
+    pub fn datagram(
+        format: PacketFormat,
+        packet_type: PacketType,
+        size: fn(PacketFormat) -> usize,
+    ) -> BoxedStrategy<Vec<u8>> {
+        let body = proptest::collection::vec(any::<u8>(), size(format));
+        datagram_with(format, packet_type, body)
+    }
+
+    /// Datagrams of `packet_type` in `format` with bodies from `body`
+    pub fn datagram_with(
+        format: PacketFormat,
+        packet_type: PacketType,
+        body: impl Strategy<Value = Vec<u8>> + 'static,
+    ) -> BoxedStrategy<Vec<u8>> {
+        (header(format, packet_type), body)
+            .prop_map(|(mut header, body)| {
+                header.extend(body);
+                header
+            })
+            .boxed()
+    }
+
+    /// Games before F1 24 that send `packet_type`
+    pub fn earlier_formats(packet_type: PacketType) -> impl Strategy<Value = PacketFormat> {
+        let formats: Vec<_> = [PacketFormat::F1_22, PacketFormat::F1_23]
+            .into_iter()
+            .filter(|f| f.has(packet_type))
+            .collect();
+        proptest::sample::select(formats)
+    }
+
+    /// Datagrams of `packet_type` from a game before F1 24, with arbitrary bodies
+    pub fn earlier_datagram(
+        packet_type: PacketType,
+        size: fn(PacketFormat) -> usize,
+    ) -> BoxedStrategy<Vec<u8>> {
+        earlier_formats(packet_type)
+            .prop_flat_map(move |format| datagram(format, packet_type, size))
+            .boxed()
+    }
+
+    /// Name fields as the game fills them, null-terminated with leftovers of
+    /// longer names after the terminator, or filling the field completely
+    fn name() -> BoxedStrategy<Vec<u8>> {
+        prop_oneof![
+            3 => ("[^\\x00]{0,20}", any::<[u8; NAME_SIZE]>()).prop_filter_map(
+                "longer than the field",
+                |(name, tail)| {
+                    let mut bytes = name.into_bytes();
+                    if bytes.len() >= NAME_SIZE {
+                        return None;
+                    }
+                    bytes.push(0);
+                    bytes.extend_from_slice(&tail[..NAME_SIZE - bytes.len()]);
+                    Some(bytes)
+                },
+            ),
+            1 => "[a-z]{48}|[a-z]{46}é".prop_map(String::into_bytes),
+        ]
+        .boxed()
+    }
+
+    /// How a name field is written back once decoded, or None if it isn't
+    /// UTF-8 and so can't be decoded
+    fn written_name(field: &[u8]) -> Option<Vec<u8>> {
+        let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+        let name = std::str::from_utf8(&field[..end]).ok()?;
+
+        let mut end = name.len().min(field.len() - 1);
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        let mut bytes = name.as_bytes()[..end].to_vec();
+        bytes.resize(field.len(), 0);
+        Some(bytes)
+    }
+
+    /// Bodies of `size` arbitrary bytes but for names, as the game fills
+    /// them, at each of `offsets`
+    pub fn body_with_names(size: usize, offsets: Vec<usize>) -> BoxedStrategy<Vec<u8>> {
+        let names = proptest::collection::vec(name(), offsets.len());
+        (proptest::collection::vec(any::<u8>(), size), names)
+            .prop_map(move |(mut body, names)| {
+                for (&at, name) in offsets.iter().zip(names) {
+                    body[at..at + NAME_SIZE].copy_from_slice(&name);
+                }
+                body
+            })
+            .boxed()
+    }
+
+    /// How an F1 24 datagram whose body has names at `offsets` is written
+    /// back once decoded, or None if one of the names isn't UTF-8
+    pub fn written_names(datagram: &[u8], offsets: &[usize]) -> Option<Vec<u8>> {
+        let mut bytes = datagram.to_vec();
+        for at in offsets {
+            let at = PacketHeader::size(PacketFormat::F1_24) + at;
+            let name = written_name(&datagram[at..at + NAME_SIZE])?;
+            bytes[at..at + NAME_SIZE].copy_from_slice(&name);
+        }
+        Some(bytes)
+    }
+
+    fn decode(datagram: &[u8]) -> Result<TelemetryPacket, TestCaseError> {
+        TelemetryPacket::try_from(datagram)
+            .map_err(|e| TestCaseError::fail(format!("datagram doesn't decode: {}", e)))
+    }
+
+    /// Decode a datagram and check it encodes to `expected`
+    pub fn assert_encodes_to(datagram: &[u8], expected: &[u8]) -> Result<(), TestCaseError> {
+        let packet = decode(datagram)?;
+        let bytes = packet.to_bytes();
+        let mismatch = bytes.iter().zip(expected).position(|(a, b)| a != b);
+        prop_assert_eq!(
+            (bytes.len(), mismatch),
+            (expected.len(), None),
+            "{:?} doesn't encode to the bytes expected",
+            packet.header().packet_id
+        );
+        Ok(())
+    }
+
+    /// Decode a datagram of an earlier game and check the F1 24 datagram it
+    /// encodes to has the size `size` gives and round-trips
+    pub fn assert_round_trips_as_f1_24(
+        datagram: &[u8],
+        size: fn(PacketFormat) -> usize,
+    ) -> Result<(), TestCaseError> {
+        let bytes = decode(datagram)?.to_bytes();
+        prop_assert_eq!(
+            bytes.len(),
+            PacketHeader::size(PacketFormat::F1_24) + size(PacketFormat::F1_24)
+        );
+        assert_encodes_to(&bytes, &bytes)
+    }
+
+    /// Check a datagram is rejected for a name that isn't UTF-8
+    pub fn assert_invalid_utf8(datagram: &[u8]) -> Result<(), TestCaseError> {
+        match TelemetryPacket::try_from(datagram) {
+            Err(PacketError::InvalidUtf8 { .. }) => Ok(()),
+            Err(e) => Err(TestCaseError::fail(format!("unexpected error: {}", e))),
+            Ok(_) => Err(TestCaseError::fail("invalid name was decoded")),
+        }
+    }
 }
@@ -1,3 +1,7 @@
+use super::error::PacketError;
+use super::format::PacketFormat;
+use super::reader::{FromPacket, PacketReader};
+use super::writer::{PacketWriter, ToPacket};
 use super::PacketSize;
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -31,107 +35,104 @@ pub struct PacketMotionExData {
 }
 
 impl PacketSize for PacketMotionExData {
-    fn size() -> usize {
-        237 // Size specified in the UDP spec
+    fn size(format: PacketFormat) -> usize {
+        // Size specified in the UDP spec, excluding the header
+        match format {
+            PacketFormat::F1_22 | PacketFormat::F1_23 => 188,
+            PacketFormat::F1_24 => 208,
+        }
     }
 }
 
-impl TryFrom<&[u8]> for PacketMotionExData {
-    type Error = String;
-
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() < PacketMotionExData::size() {
-            return Err("Packet too short for PacketMotionExData".into());
-        }
-
-        let mut offset = 0;
-
-        // Helper function to read array of 4 f32 values
-        let read_f32_array = |offset: &mut usize| -> [f32; 4] {
-            let mut array = [0.0f32; 4];
-            for i in 0..4 {
-                array[i] = f32::from_le_bytes([
-                    bytes[*offset],
-                    bytes[*offset + 1],
-                    bytes[*offset + 2],
-                    bytes[*offset + 3],
-                ]);
-                *offset += 4;
-            }
-            array
-        };
-
-        // Helper function to read single f32 value
-        let read_f32 = |offset: &mut usize| -> f32 {
-            let value = f32::from_le_bytes([
-                bytes[*offset],
-                bytes[*offset + 1],
-                bytes[*offset + 2],
-                bytes[*offset + 3],
-            ]);
-            *offset += 4;
-            value
-        };
-
-        // Read all wheel arrays
-        let suspension_position = read_f32_array(&mut offset);
-        let suspension_velocity = read_f32_array(&mut offset);
-        let suspension_acceleration = read_f32_array(&mut offset);
-        let wheel_speed = read_f32_array(&mut offset);
-        let wheel_slip_ratio = read_f32_array(&mut offset);
-        let wheel_slip_angle = read_f32_array(&mut offset);
-        let wheel_lat_force = read_f32_array(&mut offset);
-        let wheel_long_force = read_f32_array(&mut offset);
+// The aero heights, roll angles and chassis yaw are new in F1 24
+impl FromPacket for PacketMotionExData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            suspension_position: reader.read()?,
+            suspension_velocity: reader.read()?,
+            suspension_acceleration: reader.read()?,
+            wheel_speed: reader.read()?,
+            wheel_slip_ratio: reader.read()?,
+            wheel_slip_angle: reader.read()?,
+            wheel_lat_force: reader.read()?,
+            wheel_long_force: reader.read()?,
+            height_of_cog_above_ground: reader.read()?,
+            local_velocity_x: reader.read()?,
+            local_velocity_y: reader.read()?,
+            local_velocity_z: reader.read()?,
+            angular_velocity_x: reader.read()?,
+            angular_velocity_y: reader.read()?,
+            angular_velocity_z: reader.read()?,
+            angular_acceleration_x: reader.read()?,
+            angular_acceleration_y: reader.read()?,
+            angular_acceleration_z: reader.read()?,
+            front_wheels_angle: reader.read()?,
+            wheel_vert_force: reader.read()?,
+            front_aero_height: reader.since(PacketFormat::F1_24)?,
+            rear_aero_height: reader.since(PacketFormat::F1_24)?,
+            front_roll_angle: reader.since(PacketFormat::F1_24)?,
+            rear_roll_angle: reader.since(PacketFormat::F1_24)?,
+            chassis_yaw: reader.since(PacketFormat::F1_24)?,
+        })
+    }
+}
 
-        // Read single values
-        let height_of_cog_above_ground = read_f32(&mut offset);
-        let local_velocity_x = read_f32(&mut offset);
-        let local_velocity_y = read_f32(&mut offset);
-        let local_velocity_z = read_f32(&mut offset);
-        let angular_velocity_x = read_f32(&mut offset);
-        let angular_velocity_y = read_f32(&mut offset);
-        let angular_velocity_z = read_f32(&mut offset);
-        let angular_acceleration_x = read_f32(&mut offset);
-        let angular_acceleration_y = read_f32(&mut offset);
-        let angular_acceleration_z = read_f32(&mut offset);
-        let front_wheels_angle = read_f32(&mut offset);
+impl ToPacket for PacketMotionExData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.suspension_position);
+        writer.write(&self.suspension_velocity);
+        writer.write(&self.suspension_acceleration);
+        writer.write(&self.wheel_speed);
+        writer.write(&self.wheel_slip_ratio);
+        writer.write(&self.wheel_slip_angle);
+        writer.write(&self.wheel_lat_force);
+        writer.write(&self.wheel_long_force);
+        writer.write(&self.height_of_cog_above_ground);
+        writer.write(&self.local_velocity_x);
+        writer.write(&self.local_velocity_y);
+        writer.write(&self.local_velocity_z);
+        writer.write(&self.angular_velocity_x);
+        writer.write(&self.angular_velocity_y);
+        writer.write(&self.angular_velocity_z);
+        writer.write(&self.angular_acceleration_x);
+        writer.write(&self.angular_acceleration_y);
+        writer.write(&self.angular_acceleration_z);
+        writer.write(&self.front_wheels_angle);
+        writer.write(&self.wheel_vert_force);
+        writer.write(&self.front_aero_height);
+        writer.write(&self.rear_aero_height);
+        writer.write(&self.front_roll_angle);
+        writer.write(&self.rear_roll_angle);
+        writer.write(&self.chassis_yaw);
+    }
+}
 
-        // Read final wheel array
-        let wheel_vert_force = read_f32_array(&mut offset);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::f1_telemetry_client::packets::header::PacketType;
+    use crate::f1_telemetry_client::packets::testing::{
+        assert_encodes_to, assert_round_trips_as_f1_24, datagram, earlier_datagram,
+    };
+    use proptest::prelude::*;
 
-        // Read remaining single values
-        let front_aero_height = read_f32(&mut offset);
-        let rear_aero_height = read_f32(&mut offset);
-        let front_roll_angle = read_f32(&mut offset);
-        let rear_roll_angle = read_f32(&mut offset);
-        let chassis_yaw = read_f32(&mut offset);
+    proptest! {
+        #[test]
+        fn f1_24_datagrams_round_trip(
+            datagram in datagram(
+                PacketFormat::F1_24,
+                PacketType::MotionEx,
+                PacketMotionExData::size,
+            ),
+        ) {
+            assert_encodes_to(&datagram, &datagram)?;
+        }
 
-        Ok(PacketMotionExData {
-            suspension_position,
-            suspension_velocity,
-            suspension_acceleration,
-            wheel_speed,
-            wheel_slip_ratio,
-            wheel_slip_angle,
-            wheel_lat_force,
-            wheel_long_force,
-            height_of_cog_above_ground,
-            local_velocity_x,
-            local_velocity_y,
-            local_velocity_z,
-            angular_velocity_x,
-            angular_velocity_y,
-            angular_velocity_z,
-            angular_acceleration_x,
-            angular_acceleration_y,
-            angular_acceleration_z,
-            front_wheels_angle,
-            wheel_vert_force,
-            front_aero_height,
-            rear_aero_height,
-            front_roll_angle,
-            rear_roll_angle,
-            chassis_yaw,
-        })
+        #[test]
+        fn earlier_games_round_trip_as_f1_24(
+            datagram in earlier_datagram(PacketType::MotionEx, PacketMotionExData::size),
+        ) {
+            assert_round_trips_as_f1_24(&datagram, PacketMotionExData::size)?;
+        }
     }
 }
@@ -1,3 +1,8 @@
+use super::enums::Platform;
+use super::error::PacketError;
+use super::format::PacketFormat;
+use super::reader::{FromPacket, PacketReader};
+use super::writer::{PacketWriter, ToPacket};
 use super::PacketSize;
 
 #[derive(Debug, Clone)]
@@ -13,65 +18,83 @@ pub struct ParticipantData {
     pub your_telemetry: u8, // The player's UDP setting, 0 = restricted, 1 = public
     pub show_online_names: u8, // The player's show online names setting, 0 = off, 1 = on
     pub tech_level: u16,    // F1 World tech level
-    pub platform: u8,       // 1 = Steam, 3 = PlayStation, 4 = Xbox, 6 = Origin, 255 = unknown
+    pub platform: Platform, // 1 = Steam, 3 = PlayStation, 4 = Xbox, 6 = Origin, 255 = unknown
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct PacketParticipantsData {
     pub num_active_cars: u8,                // Number of active cars in the data
     pub participants: Vec<ParticipantData>, // List of participants
 }
 
 impl PacketSize for PacketParticipantsData {
-    fn size() -> usize {
-        1350 // Size specified in the UDP spec
+    fn size(format: PacketFormat) -> usize {
+        // Size specified in the UDP spec, excluding the header
+        match format {
+            PacketFormat::F1_22 => 1233,
+            PacketFormat::F1_23 => 1277,
+            PacketFormat::F1_24 => 1321,
+        }
     }
 }
 
-impl TryFrom<&[u8]> for PacketParticipantsData {
-    type Error = String;
-
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() < PacketParticipantsData::size() {
-            return Err("Packet too short for PacketParticipantsData".into());
-        }
-
-        let num_active_cars = bytes[0];
-        let mut offset = 1;
-        let mut participants = Vec::with_capacity(22);
-
-        for _ in 0..22 {
-            // Each participant data structure is fixed size
-            let name_bytes = &bytes[offset + 7..offset + 55]; // 48 bytes for name
-            let name_end = name_bytes.iter().position(|&x| x == 0).unwrap_or(48);
-            let name = String::from_utf8(name_bytes[..name_end].to_vec())
-                .map_err(|_| "Invalid UTF-8 in participant name")?;
-
-            participants.push(ParticipantData {
-                ai_controlled: bytes[offset],
-                driver_id: bytes[offset + 1],
-                network_id: bytes[offset + 2],
-                team_id: bytes[offset + 3],
-                my_team: bytes[offset + 4],
-                race_number: bytes[offset + 5],
-                nationality: bytes[offset + 6],
-                name,
-                your_telemetry: bytes[offset + 55],
-                show_online_names: bytes[offset + 56],
-                tech_level: u16::from_le_bytes([bytes[offset + 57], bytes[offset + 58]]),
-                platform: bytes[offset + 59],
-            });
+impl FromPacket for PacketParticipantsData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            num_active_cars: reader.read()?,
+            participants: reader.vec(22)?,
+        })
+    }
+}
 
-            offset += 60; // Size of each participant's data block
-        }
+impl ToPacket for PacketParticipantsData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.num_active_cars);
+        writer.vec(&self.participants, 22);
+    }
+}
 
-        Ok(PacketParticipantsData {
-            num_active_cars,
-            participants,
+// F1 22 ends after the telemetry setting; F1 23 has no tech level
+impl FromPacket for ParticipantData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            ai_controlled: reader.read()?,
+            driver_id: reader.read()?,
+            network_id: reader.read()?,
+            team_id: reader.read()?,
+            my_team: reader.read()?,
+            race_number: reader.read()?,
+            nationality: reader.read()?,
+            name: reader.string(48)?,
+            your_telemetry: reader.read()?,
+            show_online_names: reader.since(PacketFormat::F1_23)?,
+            tech_level: reader.since(PacketFormat::F1_24)?,
+            platform: if reader.format() >= PacketFormat::F1_23 {
+                reader.read()?
+            } else {
+                Platform::Unknown(255)
+            },
         })
     }
 }
 
+impl ToPacket for ParticipantData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.ai_controlled);
+        writer.write(&self.driver_id);
+        writer.write(&self.network_id);
+        writer.write(&self.team_id);
+        writer.write(&self.my_team);
+        writer.write(&self.race_number);
+        writer.write(&self.nationality);
+        writer.string(&self.name, 48);
+        writer.write(&self.your_telemetry);
+        writer.write(&self.show_online_names);
+        writer.write(&self.tech_level);
+        writer.write(&self.platform);
+    }
+}
+
 // Optional: Implement Default if needed
 impl Default for ParticipantData {
     fn default() -> Self {
@@ -87,16 +110,59 @@ impl Default for ParticipantData {
             your_telemetry: 0,
             show_online_names: 0,
             tech_level: 0,
-            platform: 255,
+            platform: Platform::Unknown(255),
         }
     }
 }
 
-impl Default for PacketParticipantsData {
-    fn default() -> Self {
-        PacketParticipantsData {
-            num_active_cars: 0,
-            participants: Vec::new(),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::f1_telemetry_client::packets::header::{PacketHeader, PacketType};
+    use crate::f1_telemetry_client::packets::testing::{
+        assert_encodes_to, assert_invalid_utf8, assert_round_trips_as_f1_24, body_with_names,
+        datagram_with, earlier_formats, written_names,
+    };
+    use proptest::prelude::*;
+
+    // Offsets of the participants' names in the body
+    fn name_offsets(format: PacketFormat) -> Vec<usize> {
+        let stride = (PacketParticipantsData::size(format) - 1) / 22;
+        (0..22).map(|i| 1 + i * stride + 7).collect()
+    }
+
+    fn datagram(format: PacketFormat) -> BoxedStrategy<Vec<u8>> {
+        let body = body_with_names(PacketParticipantsData::size(format), name_offsets(format));
+        datagram_with(format, PacketType::Participants, body)
+    }
+
+    proptest! {
+        #[test]
+        fn f1_24_datagrams_round_trip_but_for_names(datagram in datagram(PacketFormat::F1_24)) {
+            let expected = written_names(&datagram, &name_offsets(PacketFormat::F1_24));
+            assert_encodes_to(&datagram, &expected.expect("names are UTF-8"))?;
+        }
+
+        #[test]
+        fn arbitrary_names_round_trip_or_are_rejected(
+            mut datagram in datagram(PacketFormat::F1_24),
+            car in 0..22usize,
+            name in any::<[u8; 48]>(),
+        ) {
+            let at = name_offsets(PacketFormat::F1_24)[car]
+                + PacketHeader::size(PacketFormat::F1_24);
+            datagram[at..at + name.len()].copy_from_slice(&name);
+            match written_names(&datagram, &name_offsets(PacketFormat::F1_24)) {
+                Some(expected) => assert_encodes_to(&datagram, &expected)?,
+                None => assert_invalid_utf8(&datagram)?,
+            }
+        }
+
+        #[test]
+        fn earlier_games_round_trip_as_f1_24(
+            datagram in earlier_formats(PacketType::Participants).prop_flat_map(datagram),
+        ) {
+            assert_round_trips_as_f1_24(&datagram, PacketParticipantsData::size)?;
         }
     }
 }
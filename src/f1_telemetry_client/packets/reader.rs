@@ -0,0 +1,146 @@
+// Checked little-endian reading of packet data. Values are read in order
+// from a cursor, so layouts follow the field order of the spec instead of
+// hand-computed offsets, and running out of data is an error rather than
+// a panic.
+
+use super::error::PacketError;
+use super::format::PacketFormat;
+
+/// Cursor over the bytes of a packet
+pub struct PacketReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    // Offset of `bytes` within the packet, for errors of nested readers
+    base: usize,
+    // Game whose layouts are read, for types whose layout changed
+    format: PacketFormat,
+}
+
+impl<'a> PacketReader<'a> {
+    pub fn new(bytes: &'a [u8], format: PacketFormat) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+            base: 0,
+            format,
+        }
+    }
+
+    pub fn format(&self) -> PacketFormat {
+        self.format
+    }
+
+    /// Bytes read so far
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.bytes.len() - self.offset
+    }
+
+    /// Read a value of a type that knows its own layout
+    pub fn read<T: FromPacket>(&mut self) -> Result<T, PacketError> {
+        T::read(self)
+    }
+
+    /// Read `count` consecutive values
+    pub fn vec<T: FromPacket>(&mut self, count: usize) -> Result<Vec<T>, PacketError> {
+        (0..count).map(|_| T::read(self)).collect()
+    }
+
+    /// Take the next `len` raw bytes
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], PacketError> {
+        if self.remaining() < len {
+            return Err(PacketError::TooShort {
+                expected: self.base + self.offset + len,
+                actual: self.base + self.bytes.len(),
+            });
+        }
+
+        let bytes = &self.bytes[self.offset..self.offset + len];
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    pub fn skip(&mut self, len: usize) -> Result<(), PacketError> {
+        self.take(len).map(|_| ())
+    }
+
+    /// Take the next `len` bytes as a reader of their own, e.g. for a union
+    /// whose variants don't all use every byte
+    pub fn sub(&mut self, len: usize) -> Result<PacketReader<'a>, PacketError> {
+        let base = self.base + self.offset;
+        Ok(PacketReader {
+            bytes: self.take(len)?,
+            offset: 0,
+            base,
+            format: self.format,
+        })
+    }
+
+    /// Read a field first sent in `format`'s packets, or its default for
+    /// earlier games
+    pub fn since<T: FromPacket + Default>(
+        &mut self,
+        format: PacketFormat,
+    ) -> Result<T, PacketError> {
+        if self.format >= format {
+            self.read()
+        } else {
+            Ok(T::default())
+        }
+    }
+
+    /// Read `count` values into an array of `N`, leaving the rest at their
+    /// default, for arrays that grew in later games
+    pub fn padded<T: FromPacket + Default, const N: usize>(
+        &mut self,
+        count: usize,
+    ) -> Result<[T; N], PacketError> {
+        let mut items = self.vec(count)?;
+        items.resize_with(N, T::default);
+        match items.try_into() {
+            Ok(array) => Ok(array),
+            Err(_) => unreachable!("padded to {} items", N),
+        }
+    }
+
+    /// Read a null-terminated UTF-8 string stored in `len` bytes
+    pub fn string(&mut self, len: usize) -> Result<String, PacketError> {
+        let offset = self.base + self.offset;
+        let bytes = self.take(len)?;
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(len);
+
+        String::from_utf8(bytes[..end].to_vec()).map_err(|_| PacketError::InvalidUtf8 { offset })
+    }
+}
+
+/// Types that can be read from packet data
+pub trait FromPacket: Sized {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError>;
+}
+
+macro_rules! from_le_bytes {
+    ($($ty:ty),*) => {
+        $(
+            impl FromPacket for $ty {
+                fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+                    let bytes = reader.take(std::mem::size_of::<$ty>())?;
+                    Ok(<$ty>::from_le_bytes(bytes.try_into().expect("took the size of the type")))
+                }
+            }
+        )*
+    };
+}
+
+from_le_bytes!(u8, i8, u16, i16, u32, i32, u64, f32, f64);
+
+impl<T: FromPacket, const N: usize> FromPacket for [T; N] {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        match reader.vec(N)?.try_into() {
+            Ok(array) => Ok(array),
+            Err(_) => unreachable!("read exactly {} items", N),
+        }
+    }
+}
@@ -1,130 +1,110 @@
+use super::enums::{
+    FiaFlag, Formula, GameMode, Ruleset, SafetyCarStatus, SessionLength, SessionType,
+    TemperatureChange, Track, Weather,
+};
+use super::error::PacketError;
+use super::format::PacketFormat;
+use super::reader::{FromPacket, PacketReader};
+use super::writer::{PacketWriter, ToPacket};
 use super::PacketSize;
 
-#[derive(Debug, Clone, Copy)]
-enum ZoneFlag {
-    Invalid,
-    Unset,
-    Green,
-    Blue,
-    Yellow,
-}
-
-impl Default for ZoneFlag {
-    fn default() -> Self {
-        Self::Unset
-    }
-}
-
-impl TryFrom<i8> for ZoneFlag {
-    type Error = String;
-
-    fn try_from(value: i8) -> Result<Self, Self::Error> {
-        match value {
-            -1 => Ok(Self::Invalid),
-            0 => Ok(Self::Unset),
-            1 => Ok(Self::Green),
-            2 => Ok(Self::Blue),
-            3 => Ok(Self::Yellow),
-            _ => Err(format!("Invalid zone flag: {}", value)),
-        }
-    }
-}
-
-impl TryFrom<u8> for ZoneFlag {
-    type Error = String;
-
-    fn try_from(value: u8) -> Result<Self, Self::Error> {
-        Self::try_from(value as i8)
-    }
-}
-
 #[derive(Debug, Clone, Copy, Default)]
 pub struct MarshalZone {
     pub zone_start: f32, // 0..1 fraction through lap
-    pub zone_flag: ZoneFlag,
+    pub zone_flag: FiaFlag,
 }
 
-impl TryFrom<&[u8]> for MarshalZone {
-    type Error = String;
-
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() < MarshalZone::size() {
-            return Err("Buffer too small for MarshalZone".into());
-        }
+impl FromPacket for MarshalZone {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            zone_start: reader.read()?,
+            zone_flag: reader.read()?,
+        })
+    }
+}
 
-        return Ok(Self {
-            zone_start: f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
-            zone_flag: ZoneFlag::try_from(bytes[4])?,
-        });
+impl ToPacket for MarshalZone {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.zone_start);
+        writer.write(&self.zone_flag);
     }
 }
 
 impl PacketSize for MarshalZone {
-    fn size() -> usize {
+    fn size(_format: PacketFormat) -> usize {
         5
     }
 }
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct WeatherForecastSample {
-    pub session_type: u8,             // 0 = unknown, see appendix
-    pub time_offset: u8,              // Time in minutes the forecast is for
-    pub weather: u8, // 0 = clear, 1 = light cloud, 2 = overcast, 3 = light rain, 4 = heavy rain, 5 = storm
+    pub session_type: SessionType, // 0 = unknown, see appendix
+    pub time_offset: u8,           // Time in minutes the forecast is for
+    pub weather: Weather, // 0 = clear, 1 = light cloud, 2 = overcast, 3 = light rain, 4 = heavy rain, 5 = storm
     pub track_temperature: i8, // Track temp in Celsius
-    pub track_temperature_change: i8, // 0 = up, 1 = down, 2 = no change
+    pub track_temperature_change: TemperatureChange, // 0 = up, 1 = down, 2 = no change
     pub air_temperature: i8, // Air temp in Celsius
-    pub air_temperature_change: i8, // 0 = up, 1 = down, 2 = no change
+    pub air_temperature_change: TemperatureChange, // 0 = up, 1 = down, 2 = no change
     pub rain_percentage: u8, // Rain percentage (0-100)
 }
 
 impl PacketSize for WeatherForecastSample {
-    fn size() -> usize {
+    fn size(_format: PacketFormat) -> usize {
         8
     }
 }
 
-impl TryFrom<&[u8]> for WeatherForecastSample {
-    type Error = String;
-
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() < WeatherForecastSample::size() {
-            return Err("Buffer too small for WeatherForecastSample".into());
-        }
+impl FromPacket for WeatherForecastSample {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            session_type: SessionType::from_format(reader.read()?, reader.format()),
+            time_offset: reader.read()?,
+            weather: reader.read()?,
+            track_temperature: reader.read()?,
+            track_temperature_change: reader.read()?,
+            air_temperature: reader.read()?,
+            air_temperature_change: reader.read()?,
+            rain_percentage: reader.read()?,
+        })
+    }
+}
 
-        return Ok(Self {
-            session_type: bytes[0],
-            time_offset: bytes[1],
-            weather: bytes[2],
-            track_temperature: bytes[3] as i8,
-            track_temperature_change: bytes[4] as i8,
-            air_temperature: bytes[5] as i8,
-            air_temperature_change: bytes[6] as i8,
-            rain_percentage: bytes[7],
-        });
+impl ToPacket for WeatherForecastSample {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.session_type);
+        writer.write(&self.time_offset);
+        writer.write(&self.weather);
+        writer.write(&self.track_temperature);
+        writer.write(&self.track_temperature_change);
+        writer.write(&self.air_temperature);
+        writer.write(&self.air_temperature_change);
+        writer.write(&self.rain_percentage);
     }
 }
 
+/// F1 22 and 23 send 56 forecast samples and end after the session length
+/// and the safety car counts respectively; the rest is left at its default
 #[derive(Debug, Clone, Copy)]
 pub struct PacketSessionData {
-    pub weather: u8, // 0 = clear, 1 = light cloud, 2 = overcast, 3 = light rain, 4 = heavy rain, 5 = storm
+    pub weather: Weather, // 0 = clear, 1 = light cloud, 2 = overcast, 3 = light rain, 4 = heavy rain, 5 = storm
     pub track_temperature: i8, // Track temp in Celsius
     pub air_temperature: i8, // Air temp in Celsius
-    pub total_laps: u8, // Total number of laps in race
+    pub total_laps: u8,   // Total number of laps in race
     pub track_length: u16, // Track length in metres
-    pub session_type: u8, // 0 = unknown, see appendix
-    pub track_id: i8, // -1 for unknown, see appendix
-    pub formula: u8, // 0 = F1 Modern, 1 = F1 Classic, 2 = F2, 3 = F1 Generic, 4 = Beta, 6 = Esports
+    pub session_type: SessionType, // 0 = unknown, see appendix
+    pub track_id: Track,  // -1 for unknown, see appendix
+    pub formula: Formula, // 0 = F1 Modern, 1 = F1 Classic, 2 = F2, 3 = F1 Generic, 4 = Beta, 6 = Esports
     pub session_time_left: u16, // Time left in session in seconds
     pub session_duration: u16, // Session duration in seconds
     pub pit_speed_limit: u8, // Pit speed limit in km/h
-    pub game_paused: u8, // 0 = not paused, 1 = paused
+    pub game_paused: u8,  // 0 = not paused, 1 = paused
     pub is_spectating: u8, // 0 = not spectating, 1 = spectating
     pub spectator_car_index: u8, // Index of car being spectated
     pub sli_pro_native_support: u8, // 0 = inactive, 1 = active
     pub num_marshal_zones: u8, // Number of marshal zones
     pub marshal_zones: [MarshalZone; 21], // List of marshal zones
-    pub safety_car_status: u8, // 0 = no safety car, 1 = full, 2 = virtual, 3 = formation lap
-    pub network_game: u8, // 0 = offline, 1 = online
+    pub safety_car_status: SafetyCarStatus, // 0 = no safety car, 1 = full, 2 = virtual, 3 = formation lap
+    pub network_game: u8,                   // 0 = offline, 1 = online
     pub num_weather_forecast_samples: u8,
     pub weather_forecast_samples: [WeatherForecastSample; 64],
     pub forecast_accuracy: u8,             // 0 = Perfect, 1 = Approximate
@@ -135,7 +115,7 @@ pub struct PacketSessionData {
     pub pit_stop_window_ideal_lap: u8,     // Ideal pit stop lap
     pub pit_stop_window_latest_lap: u8,    // Latest pit stop lap
     pub pit_stop_rejoin_position: u8,      // Predicted rejoin position
-    pub steering_assist: bool,             // 0 = off, 1 = on
+    pub steering_assist: u8,               // 0 = off, 1 = on
     pub braking_assist: u8,                // 0 = off, 1 = low, 2 = medium, 3 = high
     pub gearbox_assist: u8,                // 1 = manual, 2 = manual & suggested gear, 3 = auto
     pub pit_assist: u8,                    // 0 = off, 1 = on
@@ -144,11 +124,11 @@ pub struct PacketSessionData {
     pub drs_assist: u8,                    // 0 = off, 1 = on
     pub dynamic_racing_line: u8,           // 0 = off, 1 = corners only, 2 = full
     pub dynamic_racing_line_type: u8,      // 0 = 2D, 1 = 3D
-    pub game_mode: u8,                     // Game mode id
-    pub ruleset: u8,                       // Ruleset
+    pub game_mode: GameMode,               // Game mode id
+    pub ruleset: Ruleset,                  // Ruleset
     pub time_of_day: u32,                  // Minutes since midnight
-    pub session_length: u8, // 0 = None, 2 = Very Short, 3 = Short, 4 = Medium, 5 = Medium Long, 6 = Long, 7 = Full
-    pub speed_units_lead_player: u8, // 0 = MPH, 1 = KPH
+    pub session_length: SessionLength, // 0 = None, 2 = Very Short, 3 = Short, 4 = Medium, 5 = Medium Long, 6 = Long, 7 = Full
+    pub speed_units_lead_player: u8,   // 0 = MPH, 1 = KPH
     pub temperature_units_lead_player: u8, // 0 = Celsius, 1 = Fahrenheit
     pub speed_units_secondary_player: u8, // 0 = MPH, 1 = KPH
     pub temperature_units_secondary_player: u8, // 0 = Celsius, 1 = Fahrenheit
@@ -180,131 +160,212 @@ pub struct PacketSessionData {
     pub affects_licence_level_solo: u8,        // 0 = Off, 1 = On
     pub affects_licence_level_mp: u8,          // 0 = Off, 1 = On
     pub num_sessions_in_weekend: u8,
-    pub weekend_structure: [u8; 12],     // List of session types
-    pub sector2_lap_distance_start: f32, // Distance in m for sector 2 start
-    pub sector3_lap_distance_start: f32, // Distance in m for sector 3 start
+    pub weekend_structure: [SessionType; 12], // List of session types
+    pub sector2_lap_distance_start: f32,      // Distance in m for sector 2 start
+    pub sector3_lap_distance_start: f32,      // Distance in m for sector 3 start
 }
 
 impl PacketSize for PacketSessionData {
-    fn size() -> usize {
-        724
+    fn size(format: PacketFormat) -> usize {
+        match format {
+            PacketFormat::F1_22 => 608,
+            PacketFormat::F1_23 => 615,
+            PacketFormat::F1_24 => 724,
+        }
     }
 }
 
-impl TryFrom<&[u8]> for PacketSessionData {
-    type Error = String;
-
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() < PacketSessionData::size() {
-            return Err("Buffer too small for PacketSessionData".into());
-        }
-
+impl FromPacket for PacketSessionData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
         Ok(Self {
-            weather: bytes[0],
-            track_temperature: i8::from_le_bytes([bytes[1]]),
-            air_temperature: i8::from_le_bytes([bytes[2]]),
-            total_laps: bytes[3],
-            track_length: u16::from_le_bytes([bytes[4], bytes[5]]),
-            session_type: bytes[6],
-            track_id: i8::from_le_bytes([bytes[7]]),
-            formula: bytes[8],
-            session_time_left: u16::from_le_bytes([bytes[9], bytes[10]]),
-            session_duration: u16::from_le_bytes([bytes[11], bytes[12]]),
-            pit_speed_limit: bytes[13],
-            game_paused: bytes[14],
-            is_spectating: bytes[15],
-            spectator_car_index: bytes[16],
-            sli_pro_native_support: bytes[17],
-            num_marshal_zones: bytes[18],
-            marshal_zones: {
-                let mut zones = [MarshalZone::default(); 21];
-                for i in 0..21 {
-                    let base = 19 + (i * 5);
-                    zones[i] = MarshalZone::try_from(&bytes[base..base + 5])?
-                }
-                zones
-            },
-            safety_car_status: bytes[124],
-            network_game: bytes[125],
-            num_weather_forecast_samples: bytes[126],
-            weather_forecast_samples: {
-                let mut samples = [WeatherForecastSample::default(); 64];
-                for i in 0..64 {
-                    let base = 127 + (i * 8);
-                    samples[i] = WeatherForecastSample::try_from(&bytes[base..base + 8])?
-                }
-                samples
-            },
-            forecast_accuracy: bytes[639],
-            ai_difficulty: bytes[640],
-            season_link_identifier: u32::from_le_bytes([
-                bytes[641], bytes[642], bytes[643], bytes[644],
-            ]),
-            weekend_link_identifier: u32::from_le_bytes([
-                bytes[645], bytes[646], bytes[647], bytes[648],
-            ]),
-            session_link_identifier: u32::from_le_bytes([
-                bytes[649], bytes[650], bytes[651], bytes[652],
-            ]),
-            pit_stop_window_ideal_lap: bytes[653],
-            pit_stop_window_latest_lap: bytes[654],
-            pit_stop_rejoin_position: bytes[655],
-            steering_assist: bytes[656] != 0,
-            braking_assist: bytes[657],
-            gearbox_assist: bytes[658],
-            pit_assist: bytes[659],
-            pit_release_assist: bytes[660],
-            ers_assist: bytes[661],
-            drs_assist: bytes[662],
-            dynamic_racing_line: bytes[663],
-            dynamic_racing_line_type: bytes[664],
-            game_mode: bytes[665],
-            ruleset: bytes[666],
-            time_of_day: u32::from_le_bytes([bytes[667], bytes[668], bytes[669], bytes[670]]),
-            session_length: bytes[671],
-            speed_units_lead_player: bytes[672],
-            temperature_units_lead_player: bytes[673],
-            speed_units_secondary_player: bytes[674],
-            temperature_units_secondary_player: bytes[675],
-            num_safety_car_periods: bytes[676],
-            num_virtual_safety_car_periods: bytes[677],
-            num_red_flag_periods: bytes[678],
-            equal_car_performance: bytes[679],
-            recovery_mode: bytes[680],
-            flashback_limit: bytes[681],
-            surface_type: bytes[682],
-            low_fuel_mode: bytes[683],
-            race_starts: bytes[684],
-            tyre_temperature: bytes[685],
-            pit_lane_tyre_sim: bytes[686],
-            car_damage: bytes[687],
-            car_damage_rate: bytes[688],
-            collisions: bytes[689],
-            collisions_off_for_first_lap_only: bytes[690],
-            mp_unsafe_pit_release: bytes[691],
-            mp_off_for_griefing: bytes[692],
-            corner_cutting_stringency: bytes[693],
-            parc_ferme_rules: bytes[694],
-            pit_stop_experience: bytes[695],
-            safety_car: bytes[696],
-            safety_car_experience: bytes[697],
-            formation_lap: bytes[698],
-            formation_lap_experience: bytes[699],
-            red_flags: bytes[700],
-            affects_licence_level_solo: bytes[701],
-            affects_licence_level_mp: bytes[702],
-            num_sessions_in_weekend: bytes[703],
-            weekend_structure: {
-                let mut structure = [0u8; 12];
-                structure.copy_from_slice(&bytes[704..716]);
-                structure
+            weather: reader.read()?,
+            track_temperature: reader.read()?,
+            air_temperature: reader.read()?,
+            total_laps: reader.read()?,
+            track_length: reader.read()?,
+            session_type: SessionType::from_format(reader.read()?, reader.format()),
+            track_id: reader.read()?,
+            formula: reader.read()?,
+            session_time_left: reader.read()?,
+            session_duration: reader.read()?,
+            pit_speed_limit: reader.read()?,
+            game_paused: reader.read()?,
+            is_spectating: reader.read()?,
+            spectator_car_index: reader.read()?,
+            sli_pro_native_support: reader.read()?,
+            num_marshal_zones: reader.read()?,
+            marshal_zones: reader.read()?,
+            safety_car_status: reader.read()?,
+            network_game: reader.read()?,
+            num_weather_forecast_samples: reader.read()?,
+            weather_forecast_samples: match reader.format() {
+                PacketFormat::F1_22 | PacketFormat::F1_23 => reader.padded(56)?,
+                PacketFormat::F1_24 => reader.read()?,
             },
-            sector2_lap_distance_start: f32::from_le_bytes([
-                bytes[716], bytes[717], bytes[718], bytes[719],
-            ]),
-            sector3_lap_distance_start: f32::from_le_bytes([
-                bytes[720], bytes[721], bytes[722], bytes[723],
-            ]),
+            forecast_accuracy: reader.read()?,
+            ai_difficulty: reader.read()?,
+            season_link_identifier: reader.read()?,
+            weekend_link_identifier: reader.read()?,
+            session_link_identifier: reader.read()?,
+            pit_stop_window_ideal_lap: reader.read()?,
+            pit_stop_window_latest_lap: reader.read()?,
+            pit_stop_rejoin_position: reader.read()?,
+            steering_assist: reader.read()?,
+            braking_assist: reader.read()?,
+            gearbox_assist: reader.read()?,
+            pit_assist: reader.read()?,
+            pit_release_assist: reader.read()?,
+            ers_assist: reader.read()?,
+            drs_assist: reader.read()?,
+            dynamic_racing_line: reader.read()?,
+            dynamic_racing_line_type: reader.read()?,
+            game_mode: reader.read()?,
+            ruleset: reader.read()?,
+            time_of_day: reader.read()?,
+            session_length: reader.read()?,
+            speed_units_lead_player: reader.since(PacketFormat::F1_23)?,
+            temperature_units_lead_player: reader.since(PacketFormat::F1_23)?,
+            speed_units_secondary_player: reader.since(PacketFormat::F1_23)?,
+            temperature_units_secondary_player: reader.since(PacketFormat::F1_23)?,
+            num_safety_car_periods: reader.since(PacketFormat::F1_23)?,
+            num_virtual_safety_car_periods: reader.since(PacketFormat::F1_23)?,
+            num_red_flag_periods: reader.since(PacketFormat::F1_23)?,
+            equal_car_performance: reader.since(PacketFormat::F1_24)?,
+            recovery_mode: reader.since(PacketFormat::F1_24)?,
+            flashback_limit: reader.since(PacketFormat::F1_24)?,
+            surface_type: reader.since(PacketFormat::F1_24)?,
+            low_fuel_mode: reader.since(PacketFormat::F1_24)?,
+            race_starts: reader.since(PacketFormat::F1_24)?,
+            tyre_temperature: reader.since(PacketFormat::F1_24)?,
+            pit_lane_tyre_sim: reader.since(PacketFormat::F1_24)?,
+            car_damage: reader.since(PacketFormat::F1_24)?,
+            car_damage_rate: reader.since(PacketFormat::F1_24)?,
+            collisions: reader.since(PacketFormat::F1_24)?,
+            collisions_off_for_first_lap_only: reader.since(PacketFormat::F1_24)?,
+            mp_unsafe_pit_release: reader.since(PacketFormat::F1_24)?,
+            mp_off_for_griefing: reader.since(PacketFormat::F1_24)?,
+            corner_cutting_stringency: reader.since(PacketFormat::F1_24)?,
+            parc_ferme_rules: reader.since(PacketFormat::F1_24)?,
+            pit_stop_experience: reader.since(PacketFormat::F1_24)?,
+            safety_car: reader.since(PacketFormat::F1_24)?,
+            safety_car_experience: reader.since(PacketFormat::F1_24)?,
+            formation_lap: reader.since(PacketFormat::F1_24)?,
+            formation_lap_experience: reader.since(PacketFormat::F1_24)?,
+            red_flags: reader.since(PacketFormat::F1_24)?,
+            affects_licence_level_solo: reader.since(PacketFormat::F1_24)?,
+            affects_licence_level_mp: reader.since(PacketFormat::F1_24)?,
+            num_sessions_in_weekend: reader.since(PacketFormat::F1_24)?,
+            weekend_structure: reader.since(PacketFormat::F1_24)?,
+            sector2_lap_distance_start: reader.since(PacketFormat::F1_24)?,
+            sector3_lap_distance_start: reader.since(PacketFormat::F1_24)?,
         })
     }
 }
+
+impl ToPacket for PacketSessionData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.weather);
+        writer.write(&self.track_temperature);
+        writer.write(&self.air_temperature);
+        writer.write(&self.total_laps);
+        writer.write(&self.track_length);
+        writer.write(&self.session_type);
+        writer.write(&self.track_id);
+        writer.write(&self.formula);
+        writer.write(&self.session_time_left);
+        writer.write(&self.session_duration);
+        writer.write(&self.pit_speed_limit);
+        writer.write(&self.game_paused);
+        writer.write(&self.is_spectating);
+        writer.write(&self.spectator_car_index);
+        writer.write(&self.sli_pro_native_support);
+        writer.write(&self.num_marshal_zones);
+        writer.write(&self.marshal_zones);
+        writer.write(&self.safety_car_status);
+        writer.write(&self.network_game);
+        writer.write(&self.num_weather_forecast_samples);
+        writer.write(&self.weather_forecast_samples);
+        writer.write(&self.forecast_accuracy);
+        writer.write(&self.ai_difficulty);
+        writer.write(&self.season_link_identifier);
+        writer.write(&self.weekend_link_identifier);
+        writer.write(&self.session_link_identifier);
+        writer.write(&self.pit_stop_window_ideal_lap);
+        writer.write(&self.pit_stop_window_latest_lap);
+        writer.write(&self.pit_stop_rejoin_position);
+        writer.write(&self.steering_assist);
+        writer.write(&self.braking_assist);
+        writer.write(&self.gearbox_assist);
+        writer.write(&self.pit_assist);
+        writer.write(&self.pit_release_assist);
+        writer.write(&self.ers_assist);
+        writer.write(&self.drs_assist);
+        writer.write(&self.dynamic_racing_line);
+        writer.write(&self.dynamic_racing_line_type);
+        writer.write(&self.game_mode);
+        writer.write(&self.ruleset);
+        writer.write(&self.time_of_day);
+        writer.write(&self.session_length);
+        writer.write(&self.speed_units_lead_player);
+        writer.write(&self.temperature_units_lead_player);
+        writer.write(&self.speed_units_secondary_player);
+        writer.write(&self.temperature_units_secondary_player);
+        writer.write(&self.num_safety_car_periods);
+        writer.write(&self.num_virtual_safety_car_periods);
+        writer.write(&self.num_red_flag_periods);
+        writer.write(&self.equal_car_performance);
+        writer.write(&self.recovery_mode);
+        writer.write(&self.flashback_limit);
+        writer.write(&self.surface_type);
+        writer.write(&self.low_fuel_mode);
+        writer.write(&self.race_starts);
+        writer.write(&self.tyre_temperature);
+        writer.write(&self.pit_lane_tyre_sim);
+        writer.write(&self.car_damage);
+        writer.write(&self.car_damage_rate);
+        writer.write(&self.collisions);
+        writer.write(&self.collisions_off_for_first_lap_only);
+        writer.write(&self.mp_unsafe_pit_release);
+        writer.write(&self.mp_off_for_griefing);
+        writer.write(&self.corner_cutting_stringency);
+        writer.write(&self.parc_ferme_rules);
+        writer.write(&self.pit_stop_experience);
+        writer.write(&self.safety_car);
+        writer.write(&self.safety_car_experience);
+        writer.write(&self.formation_lap);
+        writer.write(&self.formation_lap_experience);
+        writer.write(&self.red_flags);
+        writer.write(&self.affects_licence_level_solo);
+        writer.write(&self.affects_licence_level_mp);
+        writer.write(&self.num_sessions_in_weekend);
+        writer.write(&self.weekend_structure);
+        writer.write(&self.sector2_lap_distance_start);
+        writer.write(&self.sector3_lap_distance_start);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::f1_telemetry_client::packets::header::PacketType;
+    use crate::f1_telemetry_client::packets::testing::{
+        assert_encodes_to, assert_round_trips_as_f1_24, datagram, earlier_datagram,
+    };
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn f1_24_datagrams_round_trip(
+            datagram in datagram(PacketFormat::F1_24, PacketType::Session, PacketSessionData::size),
+        ) {
+            assert_encodes_to(&datagram, &datagram)?;
+        }
+
+        #[test]
+        fn earlier_games_round_trip_as_f1_24(
+            datagram in earlier_datagram(PacketType::Session, PacketSessionData::size),
+        ) {
+            assert_round_trips_as_f1_24(&datagram, PacketSessionData::size)?;
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use super::enums::{ActualTyreCompound, VisualTyreCompound};
+use super::error::PacketError;
+use super::format::PacketFormat;
+use super::reader::{FromPacket, PacketReader};
+use super::writer::{PacketWriter, ToPacket};
+use super::PacketSize;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LapHistoryData {
+    pub lap_time_in_ms: u32,           // Lap time in milliseconds
+    pub sector1_time_ms_part: u16,     // Sector 1 milliseconds part
+    pub sector1_time_minutes_part: u8, // Sector 1 whole minute part
+    pub sector2_time_ms_part: u16,     // Sector 2 time milliseconds part
+    pub sector2_time_minutes_part: u8, // Sector 2 whole minute part
+    pub sector3_time_ms_part: u16,     // Sector 3 time milliseconds part
+    pub sector3_time_minutes_part: u8, // Sector 3 whole minute part
+    pub lap_valid_bit_flags: u8,       // 0x01 bit set-lap valid, 0x02 bit set-sector 1 valid
+                                       // 0x04 bit set-sector 2 valid, 0x08 bit set-sector 3 valid
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TyreStintHistoryData {
+    pub end_lap: u8, // Lap the tyre usage ends on (255 of current tyre)
+    pub tyre_actual_compound: ActualTyreCompound, // Actual tyres used by this driver
+    pub tyre_visual_compound: VisualTyreCompound, // Visual tyres used by this driver
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PacketSessionHistoryData {
+    pub car_idx: u8,               // Index of the car this lap data relates to
+    pub num_laps: u8,              // Num laps in the data (including current partial lap)
+    pub num_tyre_stints: u8,       // Number of tyre stints in the data
+    pub best_lap_time_lap_num: u8, // Lap the best lap time was achieved on
+    pub best_sector1_lap_num: u8,  // Lap the best Sector 1 time was achieved on
+    pub best_sector2_lap_num: u8,  // Lap the best Sector 2 time was achieved on
+    pub best_sector3_lap_num: u8,  // Lap the best Sector 3 time was achieved on
+    pub lap_history_data: Vec<LapHistoryData>, // 100 laps of data max
+    pub tyre_stints_history_data: Vec<TyreStintHistoryData>, // 8 tyre stints max
+}
+
+impl PacketSize for PacketSessionHistoryData {
+    fn size(format: PacketFormat) -> usize {
+        // Size specified in the UDP spec, excluding the header
+        match format {
+            PacketFormat::F1_22 => 1131,
+            PacketFormat::F1_23 | PacketFormat::F1_24 => 1431,
+        }
+    }
+}
+
+impl FromPacket for PacketSessionHistoryData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            car_idx: reader.read()?,
+            num_laps: reader.read()?,
+            num_tyre_stints: reader.read()?,
+            best_lap_time_lap_num: reader.read()?,
+            best_sector1_lap_num: reader.read()?,
+            best_sector2_lap_num: reader.read()?,
+            best_sector3_lap_num: reader.read()?,
+            lap_history_data: reader.vec(100)?,
+            tyre_stints_history_data: reader.vec(8)?,
+        })
+    }
+}
+
+impl ToPacket for PacketSessionHistoryData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.car_idx);
+        writer.write(&self.num_laps);
+        writer.write(&self.num_tyre_stints);
+        writer.write(&self.best_lap_time_lap_num);
+        writer.write(&self.best_sector1_lap_num);
+        writer.write(&self.best_sector2_lap_num);
+        writer.write(&self.best_sector3_lap_num);
+        writer.vec(&self.lap_history_data, 100);
+        writer.vec(&self.tyre_stints_history_data, 8);
+    }
+}
+
+// F1 22 sends sector times in milliseconds only
+impl FromPacket for LapHistoryData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            lap_time_in_ms: reader.read()?,
+            sector1_time_ms_part: reader.read()?,
+            sector1_time_minutes_part: reader.since(PacketFormat::F1_23)?,
+            sector2_time_ms_part: reader.read()?,
+            sector2_time_minutes_part: reader.since(PacketFormat::F1_23)?,
+            sector3_time_ms_part: reader.read()?,
+            sector3_time_minutes_part: reader.since(PacketFormat::F1_23)?,
+            lap_valid_bit_flags: reader.read()?,
+        })
+    }
+}
+
+impl ToPacket for LapHistoryData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.lap_time_in_ms);
+        writer.write(&self.sector1_time_ms_part);
+        writer.write(&self.sector1_time_minutes_part);
+        writer.write(&self.sector2_time_ms_part);
+        writer.write(&self.sector2_time_minutes_part);
+        writer.write(&self.sector3_time_ms_part);
+        writer.write(&self.sector3_time_minutes_part);
+        writer.write(&self.lap_valid_bit_flags);
+    }
+}
+
+impl FromPacket for TyreStintHistoryData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            end_lap: reader.read()?,
+            tyre_actual_compound: reader.read()?,
+            tyre_visual_compound: reader.read()?,
+        })
+    }
+}
+
+impl ToPacket for TyreStintHistoryData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.end_lap);
+        writer.write(&self.tyre_actual_compound);
+        writer.write(&self.tyre_visual_compound);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::f1_telemetry_client::packets::header::PacketType;
+    use crate::f1_telemetry_client::packets::testing::{
+        assert_encodes_to, assert_round_trips_as_f1_24, datagram, earlier_datagram,
+    };
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn f1_24_datagrams_round_trip(
+            datagram in datagram(
+                PacketFormat::F1_24,
+                PacketType::SessionHistory,
+                PacketSessionHistoryData::size,
+            ),
+        ) {
+            assert_encodes_to(&datagram, &datagram)?;
+        }
+
+        #[test]
+        fn earlier_games_round_trip_as_f1_24(
+            datagram in earlier_datagram(
+                PacketType::SessionHistory,
+                PacketSessionHistoryData::size,
+            ),
+        ) {
+            assert_round_trips_as_f1_24(&datagram, PacketSessionHistoryData::size)?;
+        }
+    }
+}
@@ -1,22 +1,27 @@
+use super::enums::TractionControl;
+use super::error::PacketError;
+use super::format::PacketFormat;
+use super::reader::{FromPacket, PacketReader};
+use super::writer::{PacketWriter, ToPacket};
 use super::PacketSize;
 
 #[derive(Debug, Clone, Copy)]
 pub struct TimeTrialDataSet {
-    pub car_idx: u8,               // Index of the car this data relates to
-    pub team_id: u8,               // Team id - see appendix
-    pub lap_time_in_ms: u32,       // Lap time in milliseconds
-    pub sector1_time_in_ms: u32,   // Sector 1 time in milliseconds
-    pub sector2_time_in_ms: u32,   // Sector 2 time in milliseconds
-    pub sector3_time_in_ms: u32,   // Sector 3 time in milliseconds
-    pub traction_control: u8,      // 0 = off, 1 = medium, 2 = full
-    pub gearbox_assist: u8,        // 1 = manual, 2 = manual & suggested gear, 3 = auto
-    pub anti_lock_brakes: u8,      // 0 (off) - 1 (on)
-    pub equal_car_performance: u8, // 0 = Realistic, 1 = Equal
-    pub custom_setup: u8,          // 0 = No, 1 = Yes
-    pub valid: u8,                 // 0 = invalid, 1 = valid
+    pub car_idx: u8,                       // Index of the car this data relates to
+    pub team_id: u8,                       // Team id - see appendix
+    pub lap_time_in_ms: u32,               // Lap time in milliseconds
+    pub sector1_time_in_ms: u32,           // Sector 1 time in milliseconds
+    pub sector2_time_in_ms: u32,           // Sector 2 time in milliseconds
+    pub sector3_time_in_ms: u32,           // Sector 3 time in milliseconds
+    pub traction_control: TractionControl, // 0 = off, 1 = medium, 2 = full
+    pub gearbox_assist: u8,                // 1 = manual, 2 = manual & suggested gear, 3 = auto
+    pub anti_lock_brakes: u8,              // 0 (off) - 1 (on)
+    pub equal_car_performance: u8,         // 0 = Realistic, 1 = Equal
+    pub custom_setup: u8,                  // 0 = No, 1 = Yes
+    pub valid: u8,                         // 0 = invalid, 1 = valid
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct PacketTimeTrialData {
     pub player_session_best_data_set: TimeTrialDataSet, // Player session best data set
     pub personal_best_data_set: TimeTrialDataSet,       // Personal best data set
@@ -24,70 +29,65 @@ pub struct PacketTimeTrialData {
 }
 
 impl PacketSize for PacketTimeTrialData {
-    fn size() -> usize {
-        101 // Size specified in the UDP spec
+    fn size(_format: PacketFormat) -> usize {
+        72 // Size specified in the UDP spec, excluding the header
     }
 }
 
-impl TryFrom<&[u8]> for PacketTimeTrialData {
-    type Error = String;
-
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() < PacketTimeTrialData::size() {
-            return Err("Packet too short for PacketTimeTrialData".into());
-        }
-
-        // Helper function to parse a single TimeTrialDataSet
-        let parse_data_set = |offset: usize| -> TimeTrialDataSet {
-            TimeTrialDataSet {
-                car_idx: bytes[offset],
-                team_id: bytes[offset + 1],
-                lap_time_in_ms: u32::from_le_bytes([
-                    bytes[offset + 2],
-                    bytes[offset + 3],
-                    bytes[offset + 4],
-                    bytes[offset + 5],
-                ]),
-                sector1_time_in_ms: u32::from_le_bytes([
-                    bytes[offset + 6],
-                    bytes[offset + 7],
-                    bytes[offset + 8],
-                    bytes[offset + 9],
-                ]),
-                sector2_time_in_ms: u32::from_le_bytes([
-                    bytes[offset + 10],
-                    bytes[offset + 11],
-                    bytes[offset + 12],
-                    bytes[offset + 13],
-                ]),
-                sector3_time_in_ms: u32::from_le_bytes([
-                    bytes[offset + 14],
-                    bytes[offset + 15],
-                    bytes[offset + 16],
-                    bytes[offset + 17],
-                ]),
-                traction_control: bytes[offset + 18],
-                gearbox_assist: bytes[offset + 19],
-                anti_lock_brakes: bytes[offset + 20],
-                equal_car_performance: bytes[offset + 21],
-                custom_setup: bytes[offset + 22],
-                valid: bytes[offset + 23],
-            }
-        };
+impl FromPacket for PacketTimeTrialData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            player_session_best_data_set: reader.read()?,
+            personal_best_data_set: reader.read()?,
+            rival_data_set: reader.read()?,
+        })
+    }
+}
 
-        // Parse the three data sets at their respective offsets
-        let player_session_best_data_set = parse_data_set(0);
-        let personal_best_data_set = parse_data_set(33); // Size of each data set is 33 bytes
-        let rival_data_set = parse_data_set(66);
+impl ToPacket for PacketTimeTrialData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.player_session_best_data_set);
+        writer.write(&self.personal_best_data_set);
+        writer.write(&self.rival_data_set);
+    }
+}
 
-        Ok(PacketTimeTrialData {
-            player_session_best_data_set,
-            personal_best_data_set,
-            rival_data_set,
+impl FromPacket for TimeTrialDataSet {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            car_idx: reader.read()?,
+            team_id: reader.read()?,
+            lap_time_in_ms: reader.read()?,
+            sector1_time_in_ms: reader.read()?,
+            sector2_time_in_ms: reader.read()?,
+            sector3_time_in_ms: reader.read()?,
+            traction_control: reader.read()?,
+            gearbox_assist: reader.read()?,
+            anti_lock_brakes: reader.read()?,
+            equal_car_performance: reader.read()?,
+            custom_setup: reader.read()?,
+            valid: reader.read()?,
         })
     }
 }
 
+impl ToPacket for TimeTrialDataSet {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.car_idx);
+        writer.write(&self.team_id);
+        writer.write(&self.lap_time_in_ms);
+        writer.write(&self.sector1_time_in_ms);
+        writer.write(&self.sector2_time_in_ms);
+        writer.write(&self.sector3_time_in_ms);
+        writer.write(&self.traction_control);
+        writer.write(&self.gearbox_assist);
+        writer.write(&self.anti_lock_brakes);
+        writer.write(&self.equal_car_performance);
+        writer.write(&self.custom_setup);
+        writer.write(&self.valid);
+    }
+}
+
 impl Default for TimeTrialDataSet {
     fn default() -> Self {
         TimeTrialDataSet {
@@ -97,7 +97,7 @@ impl Default for TimeTrialDataSet {
             sector1_time_in_ms: 0,
             sector2_time_in_ms: 0,
             sector3_time_in_ms: 0,
-            traction_control: 0,
+            traction_control: TractionControl::Off,
             gearbox_assist: 1, // Default to manual
             anti_lock_brakes: 0,
             equal_car_performance: 0,
@@ -107,12 +107,23 @@ impl Default for TimeTrialDataSet {
     }
 }
 
-impl Default for PacketTimeTrialData {
-    fn default() -> Self {
-        PacketTimeTrialData {
-            player_session_best_data_set: TimeTrialDataSet::default(),
-            personal_best_data_set: TimeTrialDataSet::default(),
-            rival_data_set: TimeTrialDataSet::default(),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::f1_telemetry_client::packets::header::PacketType;
+    use crate::f1_telemetry_client::packets::testing::{assert_encodes_to, datagram};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn f1_24_datagrams_round_trip(
+            datagram in datagram(
+                PacketFormat::F1_24,
+                PacketType::TimeTrial,
+                PacketTimeTrialData::size,
+            ),
+        ) {
+            assert_encodes_to(&datagram, &datagram)?;
         }
     }
 }
@@ -1,112 +1,116 @@
+use super::enums::{ActualTyreCompound, SessionType, VisualTyreCompound};
+use super::error::PacketError;
+use super::format::PacketFormat;
+use super::reader::{FromPacket, PacketReader};
+use super::writer::{PacketWriter, ToPacket};
 use super::PacketSize;
 
 #[derive(Debug, Clone, Copy, Default)]
-pub struct LapHistoryData {
-    pub lap_time_in_ms: u32,           // Lap time in milliseconds
-    pub sector1_time_ms_part: u16,     // Sector 1 milliseconds part
-    pub sector1_time_minutes_part: u8, // Sector 1 whole minute part
-    pub sector2_time_ms_part: u16,     // Sector 2 time milliseconds part
-    pub sector2_time_minutes_part: u8, // Sector 2 whole minute part
-    pub sector3_time_ms_part: u16,     // Sector 3 time milliseconds part
-    pub sector3_time_minutes_part: u8, // Sector 3 whole minute part
-    pub lap_valid_bit_flags: u8,       // 0x01 bit set-lap valid, 0x02 bit set-sector 1 valid
-                                       // 0x04 bit set-sector 2 valid, 0x08 bit set-sector 3 valid
+pub struct TyreSetData {
+    pub actual_tyre_compound: ActualTyreCompound, // Actual tyre compound used
+    pub visual_tyre_compound: VisualTyreCompound, // Visual tyre compound used
+    pub wear: u8,                                 // Tyre wear (percentage)
+    pub available: u8,                            // Whether this set is currently available
+    pub recommended_session: SessionType,         // Recommended session for tyre set, see appendix
+    pub life_span: u8,                            // Laps left in this tyre set
+    pub usable_life: u8,     // Max number of laps recommended for this compound
+    pub lap_delta_time: i16, // Lap delta time in milliseconds compared to fitted set
+    pub fitted: u8,          // Whether the set is fitted or not
 }
 
-#[derive(Debug, Clone, Copy, Default)]
-pub struct TyreStintHistoryData {
-    pub end_lap: u8,              // Lap the tyre usage ends on (255 of current tyre)
-    pub tyre_actual_compound: u8, // Actual tyres used by this driver
-    pub tyre_visual_compound: u8, // Visual tyres used by this driver
+impl PacketSize for TyreSetData {
+    fn size(_format: PacketFormat) -> usize {
+        10
+    }
 }
 
-#[derive(Debug, Clone, Default)]
-pub struct PacketSessionHistoryData {
-    pub car_idx: u8,               // Index of the car this lap data relates to
-    pub num_laps: u8,              // Num laps in the data (including current partial lap)
-    pub num_tyre_stints: u8,       // Number of tyre stints in the data
-    pub best_lap_time_lap_num: u8, // Lap the best lap time was achieved on
-    pub best_sector1_lap_num: u8,  // Lap the best Sector 1 time was achieved on
-    pub best_sector2_lap_num: u8,  // Lap the best Sector 2 time was achieved on
-    pub best_sector3_lap_num: u8,  // Lap the best Sector 3 time was achieved on
-    pub lap_history_data: Vec<LapHistoryData>, // 100 laps of data max
-    pub tyre_stints_history_data: Vec<TyreStintHistoryData>, // 8 tyre stints max
+impl FromPacket for TyreSetData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            actual_tyre_compound: reader.read()?,
+            visual_tyre_compound: reader.read()?,
+            wear: reader.read()?,
+            available: reader.read()?,
+            recommended_session: reader.read()?,
+            life_span: reader.read()?,
+            usable_life: reader.read()?,
+            lap_delta_time: reader.read()?,
+            fitted: reader.read()?,
+        })
+    }
 }
 
-impl PacketSize for PacketSessionHistoryData {
-    fn size() -> usize {
-        1460 // Size specified in the UDP spec
+impl ToPacket for TyreSetData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.actual_tyre_compound);
+        writer.write(&self.visual_tyre_compound);
+        writer.write(&self.wear);
+        writer.write(&self.available);
+        writer.write(&self.recommended_session);
+        writer.write(&self.life_span);
+        writer.write(&self.usable_life);
+        writer.write(&self.lap_delta_time);
+        writer.write(&self.fitted);
     }
 }
 
-impl TryFrom<&[u8]> for PacketSessionHistoryData {
-    type Error = String;
+#[derive(Debug, Clone)]
+pub struct PacketTyreSetsData {
+    pub car_idx: u8,                      // Index of the car this data relates to
+    pub tyre_set_data: [TyreSetData; 20], // 13 (dry) + 7 (wet)
+    pub fitted_idx: u8,                   // Index into array of fitted tyre
+}
 
-    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
-        if bytes.len() < PacketSessionHistoryData::size() {
-            return Err("Packet too short for PacketSessionHistoryData".into());
-        }
+impl PacketSize for PacketTyreSetsData {
+    fn size(_format: PacketFormat) -> usize {
+        202 // Size specified in the UDP spec, excluding the header
+    }
+}
 
-        let mut offset = 0;
-        let car_idx = bytes[offset];
-        offset += 1;
-        let num_laps = bytes[offset];
-        offset += 1;
-        let num_tyre_stints = bytes[offset];
-        offset += 1;
-        let best_lap_time_lap_num = bytes[offset];
-        offset += 1;
-        let best_sector1_lap_num = bytes[offset];
-        offset += 1;
-        let best_sector2_lap_num = bytes[offset];
-        offset += 1;
-        let best_sector3_lap_num = bytes[offset];
-        offset += 1;
+impl FromPacket for PacketTyreSetsData {
+    fn read(reader: &mut PacketReader) -> Result<Self, PacketError> {
+        Ok(Self {
+            car_idx: reader.read()?,
+            tyre_set_data: reader.read()?,
+            fitted_idx: reader.read()?,
+        })
+    }
+}
 
-        // Parse lap history data (100 laps maximum)
-        let mut lap_history_data = Vec::with_capacity(100);
-        for _ in 0..100 {
-            let lap_data = LapHistoryData {
-                lap_time_in_ms: u32::from_le_bytes([
-                    bytes[offset],
-                    bytes[offset + 1],
-                    bytes[offset + 2],
-                    bytes[offset + 3],
-                ]),
-                sector1_time_ms_part: u16::from_le_bytes([bytes[offset + 4], bytes[offset + 5]]),
-                sector1_time_minutes_part: bytes[offset + 6],
-                sector2_time_ms_part: u16::from_le_bytes([bytes[offset + 7], bytes[offset + 8]]),
-                sector2_time_minutes_part: bytes[offset + 9],
-                sector3_time_ms_part: u16::from_le_bytes([bytes[offset + 10], bytes[offset + 11]]),
-                sector3_time_minutes_part: bytes[offset + 12],
-                lap_valid_bit_flags: bytes[offset + 13],
-            };
-            lap_history_data.push(lap_data);
-            offset += 14; // Size of each lap history data block
-        }
+impl ToPacket for PacketTyreSetsData {
+    fn write(&self, writer: &mut PacketWriter) {
+        writer.write(&self.car_idx);
+        writer.write(&self.tyre_set_data);
+        writer.write(&self.fitted_idx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::f1_telemetry_client::packets::header::PacketType;
+    use crate::f1_telemetry_client::packets::testing::{
+        assert_encodes_to, assert_round_trips_as_f1_24, datagram, earlier_datagram,
+    };
+    use proptest::prelude::*;
 
-        // Parse tyre stint history data (8 stints maximum)
-        let mut tyre_stints_history_data = Vec::with_capacity(8);
-        for _ in 0..8 {
-            let stint_data = TyreStintHistoryData {
-                end_lap: bytes[offset],
-                tyre_actual_compound: bytes[offset + 1],
-                tyre_visual_compound: bytes[offset + 2],
-            };
-            tyre_stints_history_data.push(stint_data);
-            offset += 3; // Size of each tyre stint data block
+    proptest! {
+        #[test]
+        fn f1_24_datagrams_round_trip(
+            datagram in datagram(
+                PacketFormat::F1_24,
+                PacketType::TyreSets,
+                PacketTyreSetsData::size,
+            ),
+        ) {
+            assert_encodes_to(&datagram, &datagram)?;
         }
 
-        Ok(PacketSessionHistoryData {
-            car_idx,
-            num_laps,
-            num_tyre_stints,
-            best_lap_time_lap_num,
-            best_sector1_lap_num,
-            best_sector2_lap_num,
-            best_sector3_lap_num,
-            lap_history_data,
-            tyre_stints_history_data,
-        })
+        #[test]
+        fn earlier_games_round_trip_as_f1_24(
+            datagram in earlier_datagram(PacketType::TyreSets, PacketTyreSetsData::size),
+        ) {
+            assert_round_trips_as_f1_24(&datagram, PacketTyreSetsData::size)?;
+        }
     }
 }
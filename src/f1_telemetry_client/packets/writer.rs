@@ -0,0 +1,94 @@
+// Little-endian writing of packet data in the F1 24 layouts, the inverse of
+// the reader. Values are written in the same order they're read in, so a
+// decoded packet encodes back to the datagram it came from.
+
+/// Growing buffer that packet data is written to
+#[derive(Default)]
+pub struct PacketWriter {
+    bytes: Vec<u8>,
+}
+
+impl PacketWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Bytes written so far
+    pub fn offset(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn write<T: ToPacket + ?Sized>(&mut self, value: &T) {
+        value.write(self);
+    }
+
+    /// Write `count` values, padding with defaults if there are fewer
+    pub fn vec<T: ToPacket + Default>(&mut self, items: &[T], count: usize) {
+        for item in items.iter().take(count) {
+            item.write(self);
+        }
+        for _ in items.len()..count {
+            T::default().write(self);
+        }
+    }
+
+    pub fn bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub fn zeros(&mut self, len: usize) {
+        self.bytes.resize(self.bytes.len() + len, 0);
+    }
+
+    /// Write a string null-terminated in `len` bytes, truncating it at a
+    /// character boundary if it doesn't fit
+    pub fn string(&mut self, value: &str, len: usize) {
+        let mut end = value.len().min(len.saturating_sub(1));
+        while !value.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.bytes(&value.as_bytes()[..end]);
+        self.zeros(len - end);
+    }
+}
+
+/// Types that can be written as packet data
+pub trait ToPacket {
+    fn write(&self, writer: &mut PacketWriter);
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = PacketWriter::new();
+        self.write(&mut writer);
+        writer.into_bytes()
+    }
+
+    fn write_to(&self, out: &mut impl std::io::Write) -> std::io::Result<()> {
+        out.write_all(&self.to_bytes())
+    }
+}
+
+macro_rules! to_le_bytes {
+    ($($ty:ty),*) => {
+        $(
+            impl ToPacket for $ty {
+                fn write(&self, writer: &mut PacketWriter) {
+                    writer.bytes(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+to_le_bytes!(u8, i8, u16, i16, u32, i32, u64, f32, f64);
+
+impl<T: ToPacket, const N: usize> ToPacket for [T; N] {
+    fn write(&self, writer: &mut PacketWriter) {
+        for item in self {
+            item.write(writer);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use super::capture::CaptureReader;
+use super::{decode, ErrorCounts, TelemetryPacket};
+use std::error::Error;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::info;
+
+/// How quickly a capture is played back
+#[derive(Debug, Clone, Copy)]
+pub enum ReplaySpeed {
+    /// Multiple of the original pace, 1 being real time
+    Multiplier(f64),
+    /// No delay between datagrams
+    Max,
+}
+
+impl FromStr for ReplaySpeed {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("max") {
+            return Ok(Self::Max);
+        }
+
+        match s.trim_end_matches(['x', 'X']).parse::<f64>() {
+            Ok(speed) if speed > 0.0 => Ok(Self::Multiplier(speed)),
+            _ => Err(format!(
+                "Invalid replay speed \"{}\", expected a positive number or \"max\"",
+                s
+            )),
+        }
+    }
+}
+
+/// Plays a capture file back as if it were arriving from the game
+pub struct F1TelemetryReplay {
+    path: PathBuf,
+    speed: ReplaySpeed,
+}
+
+impl F1TelemetryReplay {
+    pub fn new(path: PathBuf, speed: ReplaySpeed) -> Self {
+        Self { path, speed }
+    }
+
+    pub async fn start<F>(&self, f: F, errors: &ErrorCounts) -> Result<(), Box<dyn Error>>
+    where
+        F: Fn(TelemetryPacket),
+    {
+        info!(
+            "Replaying telemetry from {} at {:?}",
+            self.path.display(),
+            self.speed
+        );
+
+        let started = Instant::now();
+        let mut count = 0;
+
+        let mut records = CaptureReader::open(&self.path).await?;
+        while let Some(record) = records.next_record().await {
+            let record = record?;
+
+            match self.speed {
+                ReplaySpeed::Multiplier(speed) => {
+                    let offset = Duration::from_micros(record.timestamp_us).div_f64(speed);
+                    tokio::time::sleep_until(started + offset).await;
+                }
+                // Give the rest of the runtime a chance to keep up
+                ReplaySpeed::Max => tokio::task::yield_now().await,
+            }
+
+            if let Some(p) = decode(&record.datagram, errors) {
+                f(p)
+            }
+            count += 1;
+        }
+
+        info!(
+            "Replay finished, {} datagrams in {:.1}s",
+            count,
+            started.elapsed().as_secs_f32()
+        );
+
+        Ok(())
+    }
+}
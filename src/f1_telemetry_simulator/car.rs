@@ -0,0 +1,137 @@
+use super::track::{Circuit, TrackPoint};
+
+// Gap between grid slots in metres
+const GRID_SPACING: f32 = 8.0;
+
+/// What happened to a car during a step
+pub enum CarProgress {
+    Driving,
+    /// Crossed the line, finishing a lap of this time in milliseconds
+    LapCompleted(u32),
+}
+
+/// One car driving the circuit's speed profile at its own pace
+pub struct SimulatedCar {
+    pub grid_position: u8,
+    // Fraction of the circuit's speed profile the car drives at
+    pace: f32,
+    // Phase of the slow drift in pace that varies its lap times
+    pace_phase: f32,
+    pub distance: f32,     // Metres from the start line, negative on the grid
+    pub speed: f32,        // m/s
+    pub acceleration: f32, // m/s², along the racing line
+    pub current_lap_num: u8,
+    pub lap_started_at: f32,     // Session time the current lap started at
+    pub sector1_time_in_ms: u32, // 0 until sector 1 of the current lap is done
+    pub sector2_time_in_ms: u32, // 0 until sector 2 of the current lap is done
+    pub last_lap_time_in_ms: u32,
+    pub best_lap_time_in_ms: Option<u32>,
+    pub finished: bool,
+}
+
+impl SimulatedCar {
+    /// A car in grid slot `grid_position` (1 on pole), with a pace close to
+    /// the others' but slower further down the grid
+    pub fn new(grid_position: u8, random: &mut Random) -> Self {
+        let slot = f32::from(grid_position - 1);
+        Self {
+            grid_position,
+            pace: 0.99 - slot * 0.0015 - random.next() * 0.01,
+            pace_phase: random.next() * std::f32::consts::TAU,
+            distance: -GRID_SPACING * (slot + 1.0),
+            speed: 0.0,
+            acceleration: 0.0,
+            current_lap_num: 1,
+            lap_started_at: 0.0,
+            sector1_time_in_ms: 0,
+            sector2_time_in_ms: 0,
+            last_lap_time_in_ms: 0,
+            best_lap_time_in_ms: None,
+            finished: false,
+        }
+    }
+
+    /// Distance into the current lap, negative before the first start
+    pub fn lap_distance(&self, circuit: &Circuit) -> f32 {
+        self.distance - circuit.length() * f32::from(self.current_lap_num - 1)
+    }
+
+    /// 0, 1 or 2 for the sector the car is in
+    pub fn sector(&self, circuit: &Circuit) -> u8 {
+        let third = circuit.length() / 3.0;
+        (self.lap_distance(circuit).max(0.0) / third).min(2.0) as u8
+    }
+
+    pub fn track_point(&self, circuit: &Circuit) -> TrackPoint {
+        circuit.at(self.distance)
+    }
+
+    /// Drive on for `dt` seconds, `time` being the session time at the end
+    /// of the step
+    pub fn step(&mut self, circuit: &Circuit, time: f32, dt: f32) -> CarProgress {
+        let pace = if self.finished {
+            // Cool-down lap
+            self.pace * 0.6
+        } else {
+            self.pace * (1.0 + 0.004 * (time * 0.05 + self.pace_phase).sin())
+        };
+
+        // Pulling away from the grid is limited by traction, not the line
+        let target = circuit.at(self.distance).speed * pace;
+        let speed = target.min(self.speed + 12.0 * dt);
+        self.acceleration = (speed - self.speed) / dt;
+        self.speed = speed;
+        self.distance += speed * dt;
+
+        if self.finished {
+            return CarProgress::Driving;
+        }
+
+        let lap_time = time - self.lap_started_at;
+        let third = circuit.length() / 3.0;
+        let lap_distance = self.lap_distance(circuit);
+        if self.sector1_time_in_ms == 0 && lap_distance >= third {
+            self.sector1_time_in_ms = to_ms(lap_time);
+        }
+        if self.sector2_time_in_ms == 0 && lap_distance >= 2.0 * third {
+            self.sector2_time_in_ms = to_ms(lap_time) - self.sector1_time_in_ms;
+        }
+
+        if lap_distance < circuit.length() {
+            return CarProgress::Driving;
+        }
+
+        let lap_time_in_ms = to_ms(lap_time);
+        self.current_lap_num += 1;
+        self.lap_started_at = time;
+        self.sector1_time_in_ms = 0;
+        self.sector2_time_in_ms = 0;
+        self.last_lap_time_in_ms = lap_time_in_ms;
+        self.best_lap_time_in_ms = Some(
+            self.best_lap_time_in_ms
+                .map_or(lap_time_in_ms, |best| best.min(lap_time_in_ms)),
+        );
+        CarProgress::LapCompleted(lap_time_in_ms)
+    }
+}
+
+fn to_ms(seconds: f32) -> u32 {
+    (seconds * 1000.0).round() as u32
+}
+
+/// Small xorshift generator; the simulation only needs variety, not quality
+pub struct Random(u64);
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    /// Next value in 0..1
+    pub fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
@@ -0,0 +1,102 @@
+// Generates a race without the game, sending the packets it would over UDP
+// so the whole pipeline can be run locally.
+
+mod car;
+mod session;
+mod track;
+
+use crate::f1_telemetry_client::packets::writer::ToPacket;
+use session::{SimulatedSession, DRIVERS};
+use std::error::Error;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::net::UdpSocket;
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+use track::Circuit;
+
+/// Most cars a simulated race can have, one per seat on the grid
+pub const MAX_CARS: usize = DRIVERS.len();
+
+// Seconds between every car taking the flag and the next race starting
+const RESTART_DELAY: f32 = 10.0;
+
+pub struct SimulatorConfig {
+    pub cars: usize,
+    pub laps: u8,
+    pub rate: u32, // Frames a second, as set in the game's telemetry settings
+    pub track: Option<PathBuf>, // JSON array of [x, y, z] points, the recorded line if unset
+}
+
+/// Drives cars around a circuit, one race after another, sending the
+/// telemetry of each frame to a UDP address
+pub struct Simulator {
+    circuit: Circuit,
+    config: SimulatorConfig,
+}
+
+impl Simulator {
+    pub fn new(config: SimulatorConfig) -> Result<Self, Box<dyn Error>> {
+        let circuit = match &config.track {
+            Some(path) => Circuit::load(path)?,
+            None => Circuit::recorded(),
+        };
+        Ok(Self { circuit, config })
+    }
+
+    pub async fn run(
+        &self,
+        target: &str,
+        shutdown: CancellationToken,
+    ) -> Result<(), Box<dyn Error>> {
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        socket
+            .connect(target)
+            .await
+            .map_err(|e| format!("Error sending telemetry to {}: {}", target, e))?;
+
+        info!(
+            "Simulating {} cars over {} laps of a {:.0} m circuit, sending {} frames a second to {}",
+            self.config.cars,
+            self.config.laps,
+            self.circuit.length(),
+            self.config.rate,
+            target
+        );
+
+        let dt = 1.0 / self.config.rate as f32;
+        let mut ticks = tokio::time::interval(Duration::from_secs_f32(dt));
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        let mut session = self.new_session();
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = ticks.tick() => {}
+            }
+
+            if session.ended_for().is_some_and(|t| t >= RESTART_DELAY) {
+                session = self.new_session();
+            }
+
+            for packet in session.step(&self.circuit, dt) {
+                // Nothing listening yet isn't a reason to stop
+                if let Err(e) = socket.send(&packet.to_bytes()).await {
+                    debug!("Error sending simulated packet: {}", e);
+                }
+            }
+        }
+
+        info!("Simulation stopped");
+        Ok(())
+    }
+
+    fn new_session(&self) -> SimulatedSession {
+        let session_uid = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(1, |d| d.as_nanos() as u64);
+        info!("Starting simulated race {}", session_uid);
+        SimulatedSession::new(session_uid, self.config.cars, self.config.laps)
+    }
+}
@@ -0,0 +1,466 @@
+use super::car::{CarProgress, Random, SimulatedCar};
+use super::track::Circuit;
+use crate::f1_telemetry_client::packets::car_motion_data::PacketMotionData;
+use crate::f1_telemetry_client::packets::car_status::PacketCarStatusData;
+use crate::f1_telemetry_client::packets::car_telemetry::PacketCarTelemetryData;
+use crate::f1_telemetry_client::packets::enums::{
+    ActualTyreCompound, DriverStatus, ErsDeployMode, FiaFlag, Formula, FuelMix, Platform,
+    ResultStatus, SessionType, Track, VisualTyreCompound, Weather,
+};
+use crate::f1_telemetry_client::packets::event::{
+    EventDataDetails, FastestLap, Overtake, PacketEventData, RaceWinner, StartLights,
+};
+use crate::f1_telemetry_client::packets::format::PacketFormat;
+use crate::f1_telemetry_client::packets::header::{PacketHeader, PacketType};
+use crate::f1_telemetry_client::packets::lap_data::PacketLapData;
+use crate::f1_telemetry_client::packets::participants::{PacketParticipantsData, ParticipantData};
+use crate::f1_telemetry_client::packets::reader::FromPacket;
+use crate::f1_telemetry_client::packets::session_data::PacketSessionData;
+use crate::f1_telemetry_client::packets::{parse, PacketSize};
+use crate::f1_telemetry_client::TelemetryPacket;
+
+// Names, race numbers and team ids of the 2024 grid, two cars per team
+pub const DRIVERS: [(&str, u8, u8); 20] = [
+    ("HAMILTON", 44, 0),
+    ("RUSSELL", 63, 0),
+    ("LECLERC", 16, 1),
+    ("SAINZ", 55, 1),
+    ("VERSTAPPEN", 1, 2),
+    ("PEREZ", 11, 2),
+    ("ALBON", 23, 3),
+    ("SARGEANT", 2, 3),
+    ("ALONSO", 14, 4),
+    ("STROLL", 18, 4),
+    ("GASLY", 10, 5),
+    ("OCON", 31, 5),
+    ("TSUNODA", 22, 6),
+    ("RICCIARDO", 3, 6),
+    ("HULKENBERG", 27, 7),
+    ("MAGNUSSEN", 20, 7),
+    ("NORRIS", 4, 8),
+    ("PIASTRI", 81, 8),
+    ("BOTTAS", 77, 9),
+    ("ZHOU", 24, 9),
+];
+
+const SESSION_DURATION: u16 = 7200; // Seconds
+const FUEL_PER_LAP: f32 = 1.6; // kg
+const MAX_RPM: f32 = 13_000.0;
+const IDLE_RPM: f32 = 4_000.0;
+
+/// A race from the grid to the chequered flag, stepped a frame at a time
+pub struct SimulatedSession {
+    session_uid: u64,
+    total_laps: u8,
+    time: f32, // Session time in seconds
+    frame: u32,
+    cars: Vec<SimulatedCar>,
+    // Car indices in race order
+    order: Vec<usize>,
+    lights_on: u8,
+    lights_out_at: f32,
+    fastest_lap_in_ms: Option<u32>,
+    chequered_flag: bool,
+    // Car indices in the order they took the flag
+    finishers: Vec<usize>,
+    ended_at: Option<f32>,
+    next_session_at: f32,
+    next_participants_at: f32,
+}
+
+impl SimulatedSession {
+    pub fn new(session_uid: u64, cars: usize, total_laps: u8) -> Self {
+        let mut random = Random::new(session_uid);
+        let cars: Vec<_> = (1..=cars as u8)
+            .map(|grid_position| SimulatedCar::new(grid_position, &mut random))
+            .collect();
+
+        Self {
+            session_uid,
+            total_laps,
+            time: 0.0,
+            frame: 0,
+            order: (0..cars.len()).collect(),
+            cars,
+            lights_on: 0,
+            // The game holds the lights for a random moment too
+            lights_out_at: 5.5 + random.next() * 1.5,
+            fastest_lap_in_ms: None,
+            chequered_flag: false,
+            finishers: Vec::new(),
+            ended_at: None,
+            next_session_at: 0.0,
+            next_participants_at: 0.0,
+        }
+    }
+
+    /// Seconds since every car took the flag
+    pub fn ended_for(&self) -> Option<f32> {
+        self.ended_at.map(|ended_at| self.time - ended_at)
+    }
+
+    /// Advance by `dt` seconds and return the packets the game would send
+    /// for the frame
+    pub fn step(&mut self, circuit: &Circuit, dt: f32) -> Vec<TelemetryPacket> {
+        let mut events = Vec::new();
+        if self.frame == 0 {
+            events.push(EventDataDetails::SessionStarted);
+        }
+
+        self.time += dt;
+        self.frame += 1;
+
+        // One light a second, then lights out
+        if self.time < self.lights_out_at {
+            if self.lights_on < 5 && self.time >= f32::from(self.lights_on + 1) {
+                self.lights_on += 1;
+                events.push(EventDataDetails::StartLights(StartLights {
+                    num_lights: self.lights_on,
+                }));
+            }
+        } else {
+            if self.lights_on > 0 {
+                self.lights_on = 0;
+                for car in &mut self.cars {
+                    car.lap_started_at = self.time;
+                }
+                events.push(EventDataDetails::LightsOut);
+            }
+            self.drive(circuit, dt, &mut events);
+        }
+
+        let mut packets = vec![
+            TelemetryPacket::Motion((self.header(PacketType::Motion), self.motion(circuit))),
+            TelemetryPacket::LapData((self.header(PacketType::LapData), self.lap_data(circuit))),
+            TelemetryPacket::CarTelemetry((
+                self.header(PacketType::CarTelemetry),
+                self.car_telemetry(circuit),
+            )),
+            TelemetryPacket::CarStatus((
+                self.header(PacketType::CarStatus),
+                self.car_status(circuit),
+            )),
+        ];
+
+        // The game sends the session twice a second and participants every
+        // five seconds, whatever the send rate
+        if self.time >= self.next_session_at {
+            self.next_session_at = self.time + 0.5;
+            packets.push(TelemetryPacket::Session((
+                self.header(PacketType::Session),
+                self.session(circuit),
+            )));
+        }
+        if self.time >= self.next_participants_at {
+            self.next_participants_at = self.time + 5.0;
+            packets.push(TelemetryPacket::Participants((
+                self.header(PacketType::Participants),
+                self.participants(),
+            )));
+        }
+
+        for details in events {
+            packets.push(TelemetryPacket::Event((
+                self.header(PacketType::Event),
+                event(details),
+            )));
+        }
+
+        packets
+    }
+
+    fn drive(&mut self, circuit: &Circuit, dt: f32, events: &mut Vec<EventDataDetails>) {
+        for (i, car) in self.cars.iter_mut().enumerate() {
+            let CarProgress::LapCompleted(lap_time_in_ms) = car.step(circuit, self.time, dt) else {
+                continue;
+            };
+
+            if self
+                .fastest_lap_in_ms
+                .is_none_or(|fastest| lap_time_in_ms < fastest)
+            {
+                self.fastest_lap_in_ms = Some(lap_time_in_ms);
+                events.push(EventDataDetails::FastestLap(FastestLap {
+                    vehicle_idx: i as u8,
+                    lap_time: lap_time_in_ms as f32 / 1000.0,
+                }));
+            }
+
+            // The first car to complete the race distance wins, the rest
+            // finish when they next cross the line
+            if !self.chequered_flag && car.current_lap_num > self.total_laps {
+                self.chequered_flag = true;
+                events.push(EventDataDetails::ChequeredFlag);
+                events.push(EventDataDetails::RaceWinner(RaceWinner {
+                    vehicle_idx: i as u8,
+                }));
+            }
+            if self.chequered_flag {
+                car.finished = true;
+                self.finishers.push(i);
+            }
+        }
+
+        // Finishers in the order they took the flag, then the rest by how
+        // far they've gone
+        let mut order: Vec<usize> = self.finishers.clone();
+        let mut running: Vec<usize> = (0..self.cars.len())
+            .filter(|i| !self.cars[*i].finished)
+            .collect();
+        running.sort_by(|a, b| self.cars[*b].distance.total_cmp(&self.cars[*a].distance));
+        order.extend(running);
+
+        // Taking the flag isn't overtaking anyone
+        for pair in order.windows(2) {
+            let (ahead, behind) = (pair[0], pair[1]);
+            let racing = !self.cars[ahead].finished && !self.cars[behind].finished;
+            if racing && self.position(ahead) > self.position(behind) {
+                events.push(EventDataDetails::Overtake(Overtake {
+                    overtaking_vehicle_idx: ahead as u8,
+                    being_overtaken_vehicle_idx: behind as u8,
+                }));
+            }
+        }
+        self.order = order;
+
+        if self.ended_at.is_none() && self.finishers.len() == self.cars.len() {
+            self.ended_at = Some(self.time);
+            events.push(EventDataDetails::SessionEnded);
+        }
+    }
+
+    fn header(&self, packet_id: PacketType) -> PacketHeader {
+        PacketHeader {
+            packet_format: 2024,
+            game_year: 24,
+            game_major_version: 1,
+            game_minor_version: 0,
+            packet_version: 1,
+            packet_id,
+            session_uid: self.session_uid,
+            session_time: self.time,
+            frame_identifier: self.frame,
+            overall_frame_identifier: self.frame,
+            player_car_index: 0,
+            secondary_player_car_index: None,
+        }
+    }
+
+    fn position(&self, i: usize) -> usize {
+        self.order.iter().position(|&car| car == i).unwrap_or(i)
+    }
+
+    fn motion(&self, circuit: &Circuit) -> PacketMotionData {
+        let mut data: PacketMotionData = zeroed();
+        for (car, motion) in self.cars.iter().zip(data.car_motion_data.iter_mut()) {
+            let point = car.track_point(circuit);
+            let [x, y, z] = point.position;
+            let [fx, _, fz] = point.forward;
+            // Direction vectors are sent normalised to the range of an i16
+            let normalised = |v: f32| (v * 32767.0) as i16;
+
+            motion.world_position_x = x;
+            motion.world_position_y = y;
+            motion.world_position_z = z;
+            motion.world_velocity_x = fx * car.speed;
+            motion.world_velocity_z = fz * car.speed;
+            motion.world_forward_dir_x = normalised(fx);
+            motion.world_forward_dir_z = normalised(fz);
+            motion.world_right_dir_x = normalised(-fz);
+            motion.world_right_dir_z = normalised(fx);
+            motion.g_force_lateral = car.speed.powi(2) * point.curvature / 9.81;
+            motion.g_force_longitudinal = car.acceleration / 9.81;
+            motion.g_force_vertical = 1.0;
+            motion.yaw = fx.atan2(fz);
+        }
+        data
+    }
+
+    fn lap_data(&self, circuit: &Circuit) -> PacketLapData {
+        let mut data: PacketLapData = zeroed();
+        data.time_trial_pb_car_idx = 255;
+        data.time_trial_rival_car_idx = 255;
+
+        let leader = &self.cars[self.order[0]];
+        for (i, (car, lap)) in self.cars.iter().zip(data.lap_data.iter_mut()).enumerate() {
+            let position = self.position(i);
+            // Gaps are how long the car takes to cover the distance between
+            // them at its current speed
+            let gap_to = |other: &SimulatedCar| {
+                let metres = (other.distance - car.distance).max(0.0);
+                (metres / car.speed.max(10.0) * 1000.0) as u32
+            };
+            let in_front = gap_to(&self.cars[self.order[position.saturating_sub(1)]]);
+            let to_leader = gap_to(leader);
+
+            lap.last_lap_time_in_ms = car.last_lap_time_in_ms;
+            lap.current_lap_time_in_ms = if self.time < self.lights_out_at {
+                0
+            } else {
+                ((self.time - car.lap_started_at) * 1000.0) as u32
+            };
+            lap.sector1_time_ms_part = (car.sector1_time_in_ms % 60_000) as u16;
+            lap.sector1_time_minutes_part = (car.sector1_time_in_ms / 60_000) as u8;
+            lap.sector2_time_ms_part = (car.sector2_time_in_ms % 60_000) as u16;
+            lap.sector2_time_minutes_part = (car.sector2_time_in_ms / 60_000) as u8;
+            lap.delta_to_car_in_front_ms_part = (in_front % 60_000) as u16;
+            lap.delta_to_car_in_front_minutes_part = (in_front / 60_000) as u8;
+            lap.delta_to_race_leader_ms_part = (to_leader % 60_000) as u16;
+            lap.delta_to_race_leader_minutes_part = (to_leader / 60_000) as u8;
+            lap.lap_distance = car.lap_distance(circuit);
+            lap.total_distance = car.distance;
+            lap.car_position = position as u8 + 1;
+            lap.current_lap_num = car.current_lap_num;
+            lap.sector = car.sector(circuit);
+            lap.grid_position = car.grid_position;
+            lap.driver_status = if car.finished {
+                DriverStatus::InLap
+            } else {
+                DriverStatus::FlyingLap
+            };
+            lap.result_status = if car.finished {
+                ResultStatus::Finished
+            } else {
+                ResultStatus::Active
+            };
+            lap.speed_trap_fastest_lap = 255;
+        }
+        data
+    }
+
+    fn car_telemetry(&self, circuit: &Circuit) -> PacketCarTelemetryData {
+        let mut data: PacketCarTelemetryData = zeroed();
+        data.mfd_panel_index = 255;
+        data.mfd_panel_index_secondary_player = 255;
+
+        for (car, telemetry) in self.cars.iter().zip(data.car_telemetry_data.iter_mut()) {
+            let point = car.track_point(circuit);
+            let speed = car.speed * 3.6;
+            let brake = (-car.acceleration / 45.0).clamp(0.0, 1.0);
+            // Eight evenly spread gears, each revving from 10,500 to 12,500
+            let gear = (speed / 41.25).ceil().clamp(1.0, 8.0);
+            let in_gear = ((speed - (gear - 1.0) * 41.25) / 41.25).clamp(0.0, 1.0);
+            let rpm = if car.speed > 0.0 {
+                10_500.0 + 2_000.0 * in_gear
+            } else {
+                IDLE_RPM
+            };
+
+            telemetry.speed = speed as u16;
+            telemetry.throttle = if brake > 0.05 {
+                0.0
+            } else if car.acceleration > 0.1 || car.speed >= point.speed * 0.95 {
+                1.0
+            } else {
+                0.6
+            };
+            telemetry.steer = (point.curvature * 30.0).clamp(-1.0, 1.0);
+            telemetry.brake = brake;
+            telemetry.gear = gear as i8;
+            telemetry.engine_rpm = rpm as u16;
+            telemetry.rev_lights_percent = (in_gear * 100.0) as u8;
+            telemetry.rev_lights_bit_value = (1u16 << (in_gear * 15.0) as u16) - 1;
+            telemetry.brake_temp = [(350.0 + 600.0 * brake) as u16; 4];
+            telemetry.tyre_surface_temp =
+                [(88.0 + 600.0 * point.curvature.abs()).min(110.0) as u8; 4];
+            telemetry.tyre_inner_temp = [100; 4];
+            telemetry.engine_temperature = 105;
+            telemetry.tyre_pressure = [21.5, 21.5, 23.0, 23.0];
+        }
+        data
+    }
+
+    fn car_status(&self, circuit: &Circuit) -> PacketCarStatusData {
+        let mut data: PacketCarStatusData = zeroed();
+        for (car, status) in self.cars.iter().zip(data.car_status_data.iter_mut()) {
+            let laps_done = car.distance.max(0.0) / circuit.length();
+            let fuel_in_tank =
+                (f32::from(self.total_laps) + 2.0) * FUEL_PER_LAP - laps_done * FUEL_PER_LAP;
+
+            status.fuel_mix = FuelMix::Standard;
+            status.front_brake_bias = 56;
+            status.fuel_in_tank = fuel_in_tank.max(0.0);
+            status.fuel_capacity = 110.0;
+            status.fuel_remaining_laps =
+                fuel_in_tank / FUEL_PER_LAP - (f32::from(self.total_laps) - laps_done);
+            status.max_rpm = MAX_RPM as u16;
+            status.idle_rpm = IDLE_RPM as u16;
+            status.max_gears = 8;
+            status.actual_tyre_compound = ActualTyreCompound::C3;
+            status.visual_tyre_compound = VisualTyreCompound::Medium;
+            status.tyres_age_laps = car.current_lap_num - 1;
+            status.vehicle_fia_flags = FiaFlag::Green;
+            status.engine_power_ice = 560_000.0;
+            status.engine_power_mguk = 120_000.0;
+            status.ers_store_energy = 2_000_000.0 + 2_000_000.0 * (laps_done * 6.0).sin();
+            status.ers_deploy_mode = ErsDeployMode::Medium;
+        }
+        data
+    }
+
+    fn session(&self, circuit: &Circuit) -> PacketSessionData {
+        let mut data: PacketSessionData = zeroed();
+        data.weather = Weather::Clear;
+        data.track_temperature = 34;
+        data.air_temperature = 27;
+        data.total_laps = self.total_laps;
+        data.track_length = circuit.length() as u16;
+        data.session_type = SessionType::Race;
+        // The recorded line isn't from a circuit the game has an id for
+        data.track_id = Track::Unknown(-1);
+        data.formula = Formula::F1Modern;
+        data.session_time_left = SESSION_DURATION.saturating_sub(self.time as u16);
+        data.session_duration = SESSION_DURATION;
+        data.pit_speed_limit = 80;
+        data.sector2_lap_distance_start = circuit.length() / 3.0;
+        data.sector3_lap_distance_start = circuit.length() * 2.0 / 3.0;
+        data
+    }
+
+    fn participants(&self) -> PacketParticipantsData {
+        PacketParticipantsData {
+            num_active_cars: self.cars.len() as u8,
+            participants: DRIVERS
+                .iter()
+                .take(self.cars.len())
+                .enumerate()
+                .map(|(i, &(name, race_number, team_id))| ParticipantData {
+                    // The first car is the player's
+                    ai_controlled: u8::from(i != 0),
+                    team_id,
+                    race_number,
+                    name: name.to_string(),
+                    your_telemetry: 1,
+                    platform: Platform::Steam,
+                    ..Default::default()
+                })
+                .collect(),
+        }
+    }
+}
+
+fn event(event_details: EventDataDetails) -> PacketEventData {
+    let code = match event_details {
+        EventDataDetails::SessionStarted => "SSTA",
+        EventDataDetails::SessionEnded => "SEND",
+        EventDataDetails::FastestLap(_) => "FTLP",
+        EventDataDetails::ChequeredFlag => "CHQF",
+        EventDataDetails::RaceWinner(_) => "RCWN",
+        EventDataDetails::StartLights(_) => "STLG",
+        EventDataDetails::LightsOut => "LGOT",
+        EventDataDetails::Overtake(_) => "OVTK",
+        other => unreachable!("{:?} isn't simulated", other),
+    };
+
+    PacketEventData {
+        event_string_code: code.as_bytes().try_into().expect("codes are 4 bytes"),
+        event_details,
+    }
+}
+
+// A packet as decoded from zeroed data, which the fields a simulation sets
+// are then filled in on
+fn zeroed<T: FromPacket + PacketSize>() -> T {
+    let bytes = vec![0; T::size(PacketFormat::F1_24)];
+    parse(&bytes, PacketFormat::F1_24).expect("zeroed packets decode")
+}
@@ -0,0 +1,599 @@
+use std::error::Error;
+use std::path::Path;
+
+// Grip and power the speed profile is worked out with, roughly those of a
+// modern F1 car
+const MAX_SPEED: f32 = 92.0; // m/s
+const LATERAL_GRIP: f32 = 38.0; // m/s², cornering acceleration
+const ACCELERATION: f32 = 11.0; // m/s²
+const BRAKING: f32 = 45.0; // m/s²
+
+/// Where a car is and where it's heading at a distance around the lap
+#[derive(Debug, Clone, Copy)]
+pub struct TrackPoint {
+    pub position: [f32; 3],
+    pub forward: [f32; 3], // Unit vector along the racing line
+    pub curvature: f32,    // 1/radius in 1/m, signed by the direction of the turn
+    pub speed: f32,        // Fastest a car takes this part of the lap in m/s
+}
+
+/// Closed polyline of world positions a simulated lap follows
+pub struct Circuit {
+    points: Vec<[f32; 3]>,
+    // Distance around the lap at each point, the last entry being the
+    // length of the closing segment back to the first point included
+    distances: Vec<f32>,
+    curvatures: Vec<f32>,
+    speeds: Vec<f32>,
+}
+
+impl Circuit {
+    /// Racing line recorded from the game, the same one the web client draws
+    pub fn recorded() -> Self {
+        Self::new(RECORDED.to_vec()).expect("the recorded line has enough points")
+    }
+
+    /// Read a JSON array of `[x, y, z]` world positions
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Error reading track {}: {}", path.display(), e))?;
+        let points: Vec<[f32; 3]> = serde_json::from_str(&json)
+            .map_err(|e| format!("Error parsing track {}: {}", path.display(), e))?;
+        Self::new(points).map_err(|e| format!("Invalid track {}: {}", path.display(), e).into())
+    }
+
+    fn new(mut points: Vec<[f32; 3]>) -> Result<Self, String> {
+        // Consecutive duplicates would leave segments without a direction
+        points.dedup_by(|a, b| planar_distance(a, b) < 0.01);
+        if points.len() < 3 {
+            return Err(format!(
+                "need at least 3 distinct points, got {}",
+                points.len()
+            ));
+        }
+
+        let count = points.len();
+        let mut distances = Vec::with_capacity(count + 1);
+        let mut total = 0.0;
+        distances.push(0.0);
+        for i in 0..count {
+            total += planar_distance(&points[i], &points[(i + 1) % count]);
+            distances.push(total);
+        }
+
+        // Curvature of the circle through points a few apart either side,
+        // which smooths out the jitter of a recorded line
+        let curvatures: Vec<f32> = (0..count)
+            .map(|i| {
+                let a = points[(i + count - 3) % count];
+                let b = points[i];
+                let c = points[(i + 3) % count];
+                let cross = (b[0] - a[0]) * (c[2] - b[2]) - (b[2] - a[2]) * (c[0] - b[0]);
+                let sides =
+                    planar_distance(&a, &b) * planar_distance(&b, &c) * planar_distance(&a, &c);
+                if sides > 0.0 {
+                    2.0 * cross / sides
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        // Fastest speed through each corner, then slowed down ahead of them
+        // for braking and after them for accelerating. Going around twice
+        // carries the limits across the start line.
+        let mut speeds: Vec<f32> = curvatures
+            .iter()
+            .map(|k| (LATERAL_GRIP / k.abs().max(1e-6)).sqrt().min(MAX_SPEED))
+            .collect();
+        for _ in 0..2 {
+            for i in (0..count).rev() {
+                let next = (i + 1) % count;
+                let gap = distances[i + 1] - distances[i];
+                speeds[i] = speeds[i].min((speeds[next].powi(2) + 2.0 * BRAKING * gap).sqrt());
+            }
+            for i in 0..count {
+                let previous = (i + count - 1) % count;
+                let gap = distances[previous + 1] - distances[previous];
+                speeds[i] =
+                    speeds[i].min((speeds[previous].powi(2) + 2.0 * ACCELERATION * gap).sqrt());
+            }
+        }
+
+        Ok(Self {
+            points,
+            distances,
+            curvatures,
+            speeds,
+        })
+    }
+
+    /// Length of a lap in metres
+    pub fn length(&self) -> f32 {
+        self.distances[self.points.len()]
+    }
+
+    /// Interpolated point `distance` metres into the lap
+    pub fn at(&self, distance: f32) -> TrackPoint {
+        let count = self.points.len();
+        let distance = distance.rem_euclid(self.length());
+        let i = match self.distances.binary_search_by(|d| d.total_cmp(&distance)) {
+            Ok(i) => i.min(count - 1),
+            Err(i) => i - 1,
+        };
+        let next = (i + 1) % count;
+        let t = (distance - self.distances[i]) / (self.distances[i + 1] - self.distances[i]);
+
+        let (a, b) = (self.points[i], self.points[next]);
+        let length = planar_distance(&a, &b);
+        let lerp = |x: f32, y: f32| x + (y - x) * t;
+
+        TrackPoint {
+            position: [lerp(a[0], b[0]), lerp(a[1], b[1]), lerp(a[2], b[2])],
+            forward: [(b[0] - a[0]) / length, 0.0, (b[2] - a[2]) / length],
+            curvature: lerp(self.curvatures[i], self.curvatures[next]),
+            speed: lerp(self.speeds[i], self.speeds[next]),
+        }
+    }
+}
+
+// Distance across the ground, ignoring elevation
+fn planar_distance(a: &[f32; 3], b: &[f32; 3]) -> f32 {
+    ((b[0] - a[0]).powi(2) + (b[2] - a[2]).powi(2)).sqrt()
+}
+
+// Every 10 m of packages/client/src/trackData.ts with repeated points
+// removed. The recording stops short of the line; the lap is closed with a
+// straight back to the first point.
+const RECORDED: &[[f32; 3]] = &[
+    [179.7, 91.4, 52.8],
+    [183.1, 91.2, 64.8],
+    [186.4, 91.0, 77.0],
+    [189.6, 90.9, 89.2],
+    [192.7, 90.7, 101.6],
+    [195.8, 90.6, 114.0],
+    [198.8, 90.5, 126.5],
+    [201.7, 90.4, 139.1],
+    [204.6, 90.4, 151.7],
+    [207.4, 90.4, 164.5],
+    [210.2, 90.3, 177.2],
+    [213.0, 90.3, 190.1],
+    [215.8, 90.3, 203.0],
+    [218.6, 90.3, 215.9],
+    [221.4, 90.3, 228.9],
+    [224.2, 90.3, 242.0],
+    [227.0, 90.3, 255.0],
+    [229.9, 90.3, 268.2],
+    [232.4, 90.3, 279.9],
+    [234.6, 90.3, 290.1],
+    [237.4, 90.3, 303.4],
+    [240.0, 90.3, 315.1],
+    [243.2, 90.2, 329.9],
+    [246.0, 90.2, 343.2],
+    [248.9, 90.1, 356.6],
+    [251.8, 90.0, 370.0],
+    [254.7, 89.8, 383.4],
+    [257.5, 89.6, 396.9],
+    [260.4, 89.5, 410.4],
+    [263.3, 89.3, 423.9],
+    [266.1, 89.1, 437.5],
+    [268.9, 88.8, 451.1],
+    [271.8, 88.7, 464.7],
+    [274.5, 88.5, 478.4],
+    [277.3, 88.3, 492.0],
+    [280.1, 88.2, 505.7],
+    [282.8, 88.1, 519.5],
+    [285.6, 88.0, 533.2],
+    [288.4, 87.9, 547.0],
+    [291.2, 87.8, 560.8],
+    [294.0, 87.7, 574.5],
+    [296.8, 87.6, 587.7],
+    [299.4, 87.6, 599.9],
+    [301.8, 87.7, 611.3],
+    [303.9, 87.7, 621.8],
+    [306.5, 87.7, 634.5],
+    [308.8, 87.7, 645.7],
+    [310.8, 87.7, 655.6],
+    [313.0, 87.7, 666.9],
+    [314.2, 87.6, 678.2],
+    [313.9, 87.6, 689.1],
+    [312.0, 87.5, 699.1],
+    [307.6, 87.5, 709.7],
+    [301.6, 87.5, 718.6],
+    [295.6, 87.5, 727.2],
+    [291.4, 87.5, 736.4],
+    [289.0, 87.6, 747.4],
+    [288.4, 87.7, 758.6],
+    [289.2, 87.7, 770.7],
+    [291.4, 87.7, 781.4],
+    [294.6, 87.7, 792.9],
+    [297.5, 87.7, 803.2],
+    [300.4, 87.7, 814.0],
+    [303.3, 87.7, 825.5],
+    [306.4, 87.7, 837.6],
+    [309.0, 87.7, 847.7],
+    [311.7, 87.7, 858.2],
+    [314.5, 87.7, 869.1],
+    [317.1, 87.7, 879.4],
+    [320.1, 87.7, 891.0],
+    [322.6, 87.7, 900.8],
+    [325.7, 87.7, 912.9],
+    [328.8, 87.7, 925.4],
+    [332.1, 87.7, 938.1],
+    [335.4, 87.7, 951.0],
+    [337.9, 87.7, 960.9],
+    [340.4, 87.7, 970.9],
+    [342.9, 87.7, 981.1],
+    [345.5, 87.7, 991.4],
+    [348.1, 87.7, 1001.9],
+    [350.6, 87.7, 1012.5],
+    [353.1, 87.7, 1023.3],
+    [355.5, 87.7, 1034.1],
+    [358.0, 87.7, 1045.0],
+    [360.5, 87.7, 1056.1],
+    [362.9, 87.7, 1067.3],
+    [365.4, 87.7, 1078.6],
+    [368.0, 87.7, 1090.0],
+    [370.4, 87.7, 1101.5],
+    [372.5, 87.7, 1113.2],
+    [374.1, 87.7, 1125.0],
+    [375.2, 87.7, 1136.9],
+    [376.0, 87.6, 1147.6],
+    [377.0, 87.6, 1161.1],
+    [378.0, 87.6, 1173.3],
+    [378.9, 87.7, 1185.6],
+    [379.7, 87.7, 1197.8],
+    [380.1, 87.7, 1209.9],
+    [380.4, 87.7, 1221.8],
+    [380.4, 87.7, 1233.6],
+    [380.0, 87.7, 1245.1],
+    [379.0, 87.7, 1256.5],
+    [377.7, 87.7, 1267.8],
+    [376.5, 87.7, 1278.8],
+    [375.3, 87.7, 1289.8],
+    [374.0, 87.7, 1300.6],
+    [372.8, 87.7, 1311.2],
+    [371.3, 87.7, 1323.9],
+    [370.1, 87.7, 1335.2],
+    [369.0, 87.7, 1347.4],
+    [368.9, 87.7, 1357.9],
+    [370.3, 87.6, 1369.5],
+    [373.2, 87.7, 1380.4],
+    [378.1, 87.9, 1390.0],
+    [384.4, 87.9, 1397.9],
+    [393.2, 87.8, 1405.1],
+    [402.8, 87.8, 1410.2],
+    [411.9, 87.7, 1415.6],
+    [419.1, 87.7, 1422.5],
+    [424.7, 87.7, 1431.6],
+    [427.9, 87.7, 1441.2],
+    [427.4, 87.8, 1451.6],
+    [422.1, 87.9, 1460.7],
+    [413.0, 88.0, 1466.8],
+    [402.4, 88.2, 1470.5],
+    [392.5, 88.5, 1472.2],
+    [381.1, 88.9, 1472.8],
+    [370.2, 89.3, 1472.8],
+    [358.8, 89.7, 1471.2],
+    [349.1, 90.0, 1468.5],
+    [339.6, 90.2, 1464.4],
+    [330.6, 90.4, 1459.1],
+    [321.6, 90.5, 1454.3],
+    [312.0, 90.6, 1450.3],
+    [302.0, 90.6, 1446.4],
+    [291.6, 90.6, 1442.3],
+    [281.0, 90.6, 1437.9],
+    [270.1, 90.6, 1432.4],
+    [261.1, 90.6, 1427.6],
+    [251.8, 90.6, 1422.4],
+    [242.4, 90.6, 1416.8],
+    [232.7, 90.6, 1411.0],
+    [222.9, 90.6, 1404.7],
+    [212.9, 90.6, 1398.1],
+    [202.7, 90.6, 1391.1],
+    [192.4, 90.6, 1383.7],
+    [182.3, 90.6, 1375.9],
+    [172.0, 90.6, 1367.7],
+    [164.4, 90.6, 1361.1],
+    [157.0, 90.6, 1354.2],
+    [147.9, 90.6, 1345.3],
+    [140.5, 90.6, 1338.0],
+    [132.9, 90.5, 1329.8],
+    [126.8, 90.5, 1321.8],
+    [120.8, 90.4, 1311.2],
+    [116.7, 90.2, 1300.4],
+    [114.3, 89.9, 1289.6],
+    [113.9, 89.6, 1279.2],
+    [115.1, 89.3, 1269.2],
+    [116.6, 88.7, 1257.7],
+    [116.4, 88.2, 1246.0],
+    [114.1, 87.8, 1234.5],
+    [110.1, 87.8, 1223.2],
+    [105.3, 87.8, 1214.4],
+    [99.6, 87.8, 1205.6],
+    [93.3, 87.8, 1196.6],
+    [86.1, 87.8, 1187.7],
+    [77.9, 87.8, 1179.2],
+    [70.5, 87.8, 1172.3],
+    [62.7, 87.8, 1165.3],
+    [54.6, 87.8, 1158.1],
+    [46.3, 87.8, 1150.6],
+    [37.6, 87.8, 1143.0],
+    [28.7, 87.8, 1135.0],
+    [19.5, 87.8, 1126.9],
+    [10.1, 87.8, 1118.5],
+    [0.7, 87.7, 1109.8],
+    [-8.3, 87.7, 1100.5],
+    [-16.6, 87.7, 1090.2],
+    [-23.8, 87.7, 1079.0],
+    [-28.6, 87.7, 1070.1],
+    [-32.7, 87.7, 1060.9],
+    [-36.1, 87.7, 1051.3],
+    [-38.8, 87.7, 1041.4],
+    [-40.7, 87.8, 1031.3],
+    [-42.0, 87.8, 1021.1],
+    [-42.7, 87.8, 1010.6],
+    [-42.7, 87.8, 1000.1],
+    [-42.4, 87.8, 989.5],
+    [-42.0, 87.8, 978.7],
+    [-41.8, 87.8, 967.9],
+    [-41.7, 87.8, 957.0],
+    [-41.7, 87.8, 946.0],
+    [-41.8, 87.8, 934.9],
+    [-42.0, 87.9, 924.2],
+    [-42.2, 88.1, 911.3],
+    [-42.4, 88.3, 899.9],
+    [-42.8, 88.6, 887.7],
+    [-43.2, 88.8, 877.3],
+    [-44.6, 89.0, 866.2],
+    [-47.8, 89.3, 856.0],
+    [-52.8, 89.4, 847.1],
+    [-60.3, 89.6, 838.5],
+    [-69.1, 89.8, 831.8],
+    [-78.7, 89.8, 827.2],
+    [-88.7, 89.8, 824.4],
+    [-98.7, 89.8, 823.4],
+    [-109.9, 89.8, 824.0],
+    [-120.6, 89.9, 823.9],
+    [-130.4, 89.9, 821.9],
+    [-140.4, 90.0, 818.1],
+    [-149.4, 90.1, 812.4],
+    [-157.3, 90.1, 803.9],
+    [-163.6, 90.2, 794.6],
+    [-169.1, 90.2, 784.9],
+    [-172.9, 90.2, 775.4],
+    [-175.4, 90.2, 765.0],
+    [-177.1, 90.2, 753.4],
+    [-178.2, 90.1, 742.9],
+    [-179.7, 90.1, 731.7],
+    [-181.5, 90.1, 719.8],
+    [-183.0, 90.1, 709.7],
+    [-184.6, 90.1, 699.2],
+    [-186.1, 90.1, 688.3],
+    [-187.3, 90.1, 677.1],
+    [-188.6, 90.1, 665.4],
+    [-189.7, 90.1, 653.3],
+    [-190.5, 90.1, 640.9],
+    [-191.2, 90.1, 628.2],
+    [-191.7, 90.1, 615.2],
+    [-192.1, 90.1, 601.9],
+    [-192.1, 90.1, 591.7],
+    [-191.9, 90.1, 581.4],
+    [-191.7, 90.1, 570.9],
+    [-191.4, 90.1, 557.9],
+    [-191.2, 90.1, 545.9],
+    [-190.9, 90.1, 535.1],
+    [-190.5, 90.1, 524.1],
+    [-189.9, 90.1, 513.0],
+    [-189.3, 90.1, 501.7],
+    [-188.6, 90.1, 490.4],
+    [-188.0, 90.1, 478.9],
+    [-187.2, 90.1, 467.3],
+    [-186.4, 90.1, 455.7],
+    [-185.4, 90.1, 443.9],
+    [-184.3, 90.1, 432.1],
+    [-182.9, 90.1, 420.2],
+    [-181.2, 90.1, 408.2],
+    [-179.3, 90.1, 396.2],
+    [-177.4, 90.1, 384.1],
+    [-175.5, 90.1, 371.9],
+    [-173.5, 90.1, 359.7],
+    [-171.4, 90.1, 347.4],
+    [-169.1, 90.1, 335.0],
+    [-166.7, 90.1, 322.7],
+    [-164.0, 90.1, 310.2],
+    [-161.5, 90.1, 299.2],
+    [-158.1, 90.1, 285.7],
+    [-155.1, 90.1, 274.2],
+    [-152.4, 90.1, 263.6],
+    [-149.9, 90.1, 253.8],
+    [-146.9, 90.1, 242.0],
+    [-144.2, 90.2, 231.7],
+    [-141.3, 90.2, 220.6],
+    [-138.2, 90.1, 210.6],
+    [-134.2, 90.1, 201.2],
+    [-128.0, 90.0, 191.2],
+    [-120.3, 90.0, 183.2],
+    [-109.9, 90.1, 176.9],
+    [-100.2, 90.2, 173.2],
+    [-90.5, 90.4, 168.8],
+    [-80.7, 90.5, 163.6],
+    [-71.5, 90.7, 157.0],
+    [-62.9, 91.0, 149.0],
+    [-56.5, 91.2, 141.4],
+    [-50.8, 91.4, 132.9],
+    [-46.0, 91.6, 123.6],
+    [-41.6, 91.7, 113.7],
+    [-37.5, 91.9, 103.4],
+    [-33.5, 92.1, 92.6],
+    [-29.7, 92.4, 81.0],
+    [-26.8, 92.7, 71.2],
+    [-23.9, 93.0, 61.0],
+    [-21.0, 93.3, 50.4],
+    [-18.5, 93.6, 39.4],
+    [-16.1, 94.0, 28.0],
+    [-13.6, 94.3, 16.2],
+    [-11.1, 94.7, 4.1],
+    [-8.5, 95.0, -8.4],
+    [-6.0, 95.3, -21.0],
+    [-3.6, 95.6, -33.9],
+    [-1.8, 95.8, -43.8],
+    [0.0, 96.0, -53.8],
+    [1.9, 96.2, -63.9],
+    [3.7, 96.4, -74.2],
+    [5.5, 96.5, -84.7],
+    [7.2, 96.6, -95.3],
+    [8.8, 96.6, -106.0],
+    [10.3, 96.6, -116.8],
+    [11.7, 96.7, -127.8],
+    [12.9, 96.6, -138.8],
+    [14.2, 96.6, -152.4],
+    [15.0, 96.6, -165.0],
+    [15.4, 96.6, -176.4],
+    [15.6, 96.6, -187.9],
+    [15.5, 96.6, -199.5],
+    [15.1, 96.6, -211.2],
+    [14.5, 96.6, -222.9],
+    [13.7, 96.6, -234.7],
+    [12.7, 96.6, -246.6],
+    [11.6, 96.6, -257.2],
+    [9.8, 96.6, -270.4],
+    [7.9, 96.6, -282.4],
+    [6.0, 96.6, -294.4],
+    [4.2, 96.6, -305.1],
+    [1.9, 96.6, -317.1],
+    [-0.8, 96.6, -330.1],
+    [-3.1, 96.7, -340.8],
+    [-5.2, 96.7, -350.7],
+    [-7.8, 96.7, -362.6],
+    [-10.1, 96.7, -373.0],
+    [-12.5, 96.7, -384.2],
+    [-14.8, 96.7, -395.3],
+    [-17.0, 96.7, -405.5],
+    [-18.0, 96.6, -416.5],
+    [-15.9, 96.6, -426.7],
+    [-10.1, 96.5, -435.5],
+    [-1.0, 96.5, -440.3],
+    [9.5, 96.5, -439.0],
+    [16.8, 96.5, -431.5],
+    [22.3, 96.6, -422.1],
+    [26.3, 96.7, -412.7],
+    [29.2, 96.7, -402.0],
+    [30.7, 96.7, -390.8],
+    [31.9, 96.7, -379.1],
+    [33.0, 96.7, -369.1],
+    [34.2, 96.7, -358.3],
+    [35.5, 96.7, -346.7],
+    [37.3, 96.7, -334.6],
+    [39.2, 96.7, -324.4],
+    [41.8, 96.6, -314.0],
+    [45.0, 96.5, -303.4],
+    [48.8, 96.3, -292.7],
+    [53.0, 96.2, -281.7],
+    [57.6, 96.0, -270.4],
+    [62.3, 95.9, -258.9],
+    [67.1, 95.7, -247.1],
+    [71.8, 95.6, -234.9],
+    [76.6, 95.4, -222.6],
+    [80.2, 95.3, -213.1],
+    [84.0, 95.2, -203.6],
+    [87.9, 95.1, -193.9],
+    [92.8, 94.9, -182.0],
+    [97.3, 94.8, -171.0],
+    [101.5, 94.7, -161.0],
+    [105.6, 94.5, -150.8],
+    [111.3, 94.4, -137.1],
+    [117.0, 94.2, -123.3],
+    [120.8, 94.1, -114.0],
+    [125.6, 94.0, -102.2],
+    [129.9, 93.9, -91.5],
+    [133.8, 93.8, -81.9],
+    [138.1, 93.6, -71.0],
+    [142.8, 93.5, -58.8],
+    [146.8, 93.4, -47.6],
+    [150.2, 93.3, -37.5],
+    [154.4, 93.1, -24.8],
+    [157.7, 92.9, -14.6],
+    [161.8, 92.6, -1.7],
+    [165.6, 92.4, 10.0],
+    [169.4, 92.1, 21.7],
+    [173.2, 91.8, 33.5],
+    [176.6, 91.6, 44.1],
+    [180.4, 91.4, 56.1],
+    [184.4, 91.1, 69.5],
+    [188.0, 91.0, 81.7],
+    [191.1, 90.8, 92.6],
+    [194.7, 90.7, 106.3],
+    [197.7, 90.6, 118.8],
+    [200.3, 90.5, 129.9],
+    [203.1, 90.4, 142.6],
+    [205.1, 90.4, 152.5],
+    [207.5, 90.4, 163.8],
+    [209.4, 90.4, 173.8],
+    [211.6, 90.3, 185.3],
+    [214.2, 90.3, 199.7],
+    [216.3, 90.3, 211.3],
+    [218.8, 90.3, 224.3],
+    [221.5, 90.3, 238.9],
+    [224.1, 90.3, 252.0],
+    [226.5, 90.3, 263.7],
+    [229.3, 90.3, 276.9],
+    [232.2, 90.3, 290.1],
+    [234.5, 90.3, 300.3],
+    [237.1, 90.3, 312.1],
+    [240.1, 90.2, 325.4],
+    [243.4, 90.2, 340.2],
+    [246.1, 90.1, 352.0],
+    [249.0, 90.0, 365.4],
+    [251.9, 89.9, 378.9],
+    [254.7, 89.7, 392.3],
+    [257.6, 89.5, 405.8],
+    [260.5, 89.3, 419.4],
+    [263.5, 89.1, 432.9],
+    [266.6, 88.9, 446.5],
+    [269.5, 88.7, 460.1],
+    [272.4, 88.5, 473.7],
+    [274.7, 88.4, 484.3],
+    [277.2, 88.3, 496.5],
+    [280.1, 88.2, 510.2],
+    [283.2, 88.0, 525.4],
+    [286.1, 87.9, 539.2],
+    [289.0, 87.8, 553.0],
+    [291.5, 87.8, 565.2],
+    [294.7, 87.6, 580.5],
+    [297.5, 87.6, 593.4],
+    [299.9, 87.6, 604.2],
+    [302.6, 87.7, 616.7],
+    [305.3, 87.7, 629.2],
+    [307.9, 87.7, 641.4],
+    [310.0, 87.7, 652.2],
+    [312.0, 87.7, 664.2],
+    [312.6, 87.7, 674.3],
+    [311.8, 87.6, 684.7],
+    [309.4, 87.5, 695.2],
+    [305.6, 87.5, 705.0],
+    [300.7, 87.5, 714.7],
+    [295.9, 87.4, 725.4],
+    [292.8, 87.5, 735.4],
+    [291.1, 87.6, 745.8],
+    [290.6, 87.7, 757.2],
+    [291.1, 87.7, 767.6],
+    [292.5, 87.7, 779.0],
+    [294.6, 87.7, 790.8],
+    [297.7, 87.7, 802.9],
+    [300.5, 87.7, 812.5],
+    [304.0, 87.7, 824.4],
+    [306.8, 87.7, 834.2],
+    [309.7, 87.7, 844.3],
+    [312.6, 87.7, 854.5],
+    [315.4, 87.7, 864.9],
+    [318.2, 87.7, 875.6],
+    [321.1, 87.7, 886.6],
+    [324.0, 87.7, 898.1],
+    [326.9, 87.7, 909.8],
+    [329.9, 87.7, 921.9],
+    [332.9, 87.7, 934.2],
+    [335.9, 87.7, 946.7],
+    [338.4, 87.7, 957.1],
+    [341.0, 87.7, 968.6],
+];
@@ -0,0 +1,88 @@
+use libsql::Connection;
+use tracing::info;
+
+// Applied in order, each at most once. The number of applied migrations is
+// tracked in SQLite's `user_version` pragma, so entries must never be edited
+// or reordered once released -- add a new one instead.
+const MIGRATIONS: &[&str] = &[
+    // 1: sessions, completed laps and sampled telemetry
+    "
+    CREATE TABLE sessions (
+        session_uid INTEGER PRIMARY KEY, -- u64 session uid stored bit-for-bit as i64
+        track_id INTEGER,
+        session_type INTEGER,
+        formula INTEGER,
+        weather INTEGER,
+        total_laps INTEGER,
+        track_length INTEGER,
+        first_seen_at TEXT NOT NULL,
+        last_seen_at TEXT NOT NULL
+    );
+
+    CREATE TABLE laps (
+        session_uid INTEGER NOT NULL REFERENCES sessions(session_uid),
+        car_index INTEGER NOT NULL,
+        lap_num INTEGER NOT NULL,
+        lap_time_ms INTEGER NOT NULL,
+        sector1_time_ms INTEGER NOT NULL,
+        sector2_time_ms INTEGER NOT NULL,
+        sector3_time_ms INTEGER NOT NULL,
+        invalid INTEGER NOT NULL,
+        PRIMARY KEY (session_uid, car_index, lap_num)
+    );
+
+    CREATE TABLE telemetry_samples (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        session_uid INTEGER NOT NULL REFERENCES sessions(session_uid),
+        car_index INTEGER NOT NULL,
+        session_time REAL NOT NULL,
+        frame_identifier INTEGER NOT NULL,
+        lap_num INTEGER,
+        lap_distance REAL,
+        speed INTEGER NOT NULL,
+        throttle REAL NOT NULL,
+        brake REAL NOT NULL,
+        steer REAL NOT NULL,
+        gear INTEGER NOT NULL,
+        engine_rpm INTEGER NOT NULL,
+        drs INTEGER NOT NULL,
+        world_position_x REAL,
+        world_position_y REAL,
+        world_position_z REAL
+    );
+
+    CREATE INDEX telemetry_samples_session_car_time
+        ON telemetry_samples (session_uid, car_index, session_time);
+    ",
+    // 2: tyre and fuel details of completed laps
+    "
+    ALTER TABLE laps ADD COLUMN tyre_actual_compound INTEGER;
+    ALTER TABLE laps ADD COLUMN tyre_visual_compound INTEGER;
+    ALTER TABLE laps ADD COLUMN fuel_used REAL;
+    ALTER TABLE laps ADD COLUMN session_time REAL;
+    ",
+    // 3: frame laps were completed at, so a flashback can discard them
+    "
+    ALTER TABLE laps ADD COLUMN frame_identifier INTEGER;
+    CREATE INDEX telemetry_samples_session_frame
+        ON telemetry_samples (session_uid, frame_identifier);
+    ",
+];
+
+/// Bring the database schema up to date
+pub async fn run(conn: &Connection) -> Result<(), libsql::Error> {
+    let mut rows = conn.query("PRAGMA user_version", ()).await?;
+    let current: i64 = match rows.next().await? {
+        Some(row) => row.get(0)?,
+        None => 0,
+    };
+
+    for (i, sql) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        let version = i + 1;
+        info!("Applying storage migration {}", version);
+        conn.execute_transactional_batch(&format!("{}\nPRAGMA user_version = {};", sql, version))
+            .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+mod migrations;
+mod writer;
+
+use crate::f1_telemetry_api::events::CarSelection;
+use crate::f1_telemetry_api::laps::CompletedLap;
+use crate::f1_telemetry_api::sessions::SessionSummary;
+use libsql::{params, Builder, Connection, Database, Row};
+use std::sync::Arc;
+use tokio_util::task::TaskTracker;
+pub use writer::StorageWriter;
+
+/// Local libSQL database holding sessions, completed laps and sampled telemetry
+#[derive(Clone)]
+pub struct Storage {
+    // Kept alive for as long as any connection is in use
+    _db: Arc<Database>,
+    conn: Connection,
+}
+
+impl Storage {
+    /// Open (or create) the database file at `path` and apply any pending migrations
+    pub async fn open(path: &str) -> Result<Self, libsql::Error> {
+        let db = Builder::new_local(path).build().await?;
+        let conn = db.connect()?;
+
+        migrations::run(&conn).await?;
+
+        Ok(Self {
+            _db: Arc::new(db),
+            conn,
+        })
+    }
+
+    /// Start a background task that persists incoming packets
+    pub fn start_writer(&self, car_selection: CarSelection, tasks: &TaskTracker) -> StorageWriter {
+        StorageWriter::spawn(self.conn.clone(), car_selection, tasks)
+    }
+
+    /// All sessions recorded in the database, most recently seen first
+    pub async fn sessions(&self) -> Result<Vec<SessionSummary>, libsql::Error> {
+        let mut rows = self
+            .conn
+            .query(&format!("{} ORDER BY last_seen_at DESC", SESSION_QUERY), ())
+            .await?;
+
+        let mut sessions = Vec::new();
+        while let Some(row) = rows.next().await? {
+            sessions.push(session_from_row(&row)?);
+        }
+
+        Ok(sessions)
+    }
+
+    pub async fn session(&self, session_uid: u64) -> Result<Option<SessionSummary>, libsql::Error> {
+        let mut rows = self
+            .conn
+            .query(
+                &format!("{} WHERE session_uid = ?1", SESSION_QUERY),
+                params![session_uid as i64],
+            )
+            .await?;
+
+        match rows.next().await? {
+            Some(row) => Ok(Some(session_from_row(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Completed laps of a session in the order they were driven, optionally for one car
+    pub async fn laps(
+        &self,
+        session_uid: u64,
+        car_index: Option<u8>,
+    ) -> Result<Vec<CompletedLap>, libsql::Error> {
+        let mut rows = self
+            .conn
+            .query(
+                "SELECT session_uid, car_index, lap_num, lap_time_ms, sector1_time_ms,
+                    sector2_time_ms, sector3_time_ms, invalid, tyre_actual_compound,
+                    tyre_visual_compound, fuel_used, session_time, frame_identifier
+                 FROM laps
+                 WHERE session_uid = ?1 AND (?2 IS NULL OR car_index = ?2)
+                 ORDER BY session_time, car_index, lap_num",
+                params![session_uid as i64, car_index],
+            )
+            .await?;
+
+        let mut laps = Vec::new();
+        while let Some(row) = rows.next().await? {
+            laps.push(lap_from_row(&row)?);
+        }
+
+        Ok(laps)
+    }
+}
+
+const SESSION_QUERY: &str = "SELECT session_uid, track_id, session_type, formula, weather,
+    total_laps, track_length, first_seen_at, last_seen_at FROM sessions";
+
+fn session_from_row(row: &Row) -> Result<SessionSummary, libsql::Error> {
+    Ok(SessionSummary {
+        session_uid: row.get::<u64>(0)?.to_string(),
+        is_current: false,
+        track: row.get::<Option<i32>>(1)?.map(|v| (v as i8).into()),
+        session_type: row.get::<Option<u32>>(2)?.map(|v| (v as u8).into()),
+        formula: row.get::<Option<u32>>(3)?.map(|v| (v as u8).into()),
+        weather: row.get::<Option<u32>>(4)?.map(|v| (v as u8).into()),
+        total_laps: row.get::<Option<u32>>(5)?.map(|v| v as u8),
+        track_length: row.get::<Option<u32>>(6)?.map(|v| v as u16),
+        first_seen_at: row.get(7)?,
+        last_seen_at: row.get(8)?,
+    })
+}
+
+fn lap_from_row(row: &Row) -> Result<CompletedLap, libsql::Error> {
+    Ok(CompletedLap {
+        session_uid: row.get::<u64>(0)?.to_string(),
+        car_index: row.get::<u32>(1)? as u8,
+        lap_num: row.get::<u32>(2)? as u8,
+        lap_time_in_ms: row.get(3)?,
+        sector1_time_in_ms: row.get(4)?,
+        sector2_time_in_ms: row.get(5)?,
+        sector3_time_in_ms: row.get(6)?,
+        valid: !row.get::<bool>(7)?,
+        tyre_actual_compound: row.get::<Option<u32>>(8)?.map(|v| (v as u8).into()),
+        tyre_visual_compound: row.get::<Option<u32>>(9)?.map(|v| (v as u8).into()),
+        fuel_used: row.get::<Option<f64>>(10)?.map(|v| v as f32),
+        session_time: row.get::<Option<f64>>(11)?.unwrap_or_default() as f32,
+        frame_identifier: row.get::<Option<u32>>(12)?.unwrap_or_default(),
+    })
+}
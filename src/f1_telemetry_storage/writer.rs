@@ -0,0 +1,340 @@
+use crate::f1_telemetry_api::events::CarSelection;
+use crate::f1_telemetry_api::laps::{CompletedLap, LapAssembler};
+use crate::f1_telemetry_client::packets::car_motion_data::PacketMotionData;
+use crate::f1_telemetry_client::packets::car_telemetry::PacketCarTelemetryData;
+use crate::f1_telemetry_client::packets::event::EventDataDetails;
+use crate::f1_telemetry_client::packets::header::PacketHeader;
+use crate::f1_telemetry_client::packets::lap_data::PacketLapData;
+use crate::f1_telemetry_client::packets::session_data::PacketSessionData;
+use crate::f1_telemetry_client::TelemetryPacket;
+use chrono::Utc;
+use libsql::{params, Connection};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_util::task::TaskTracker;
+use tracing::{debug, error, warn};
+
+// Minimum session time in seconds between two stored telemetry samples of a car
+const SAMPLE_INTERVAL: f32 = 0.1;
+
+// Most packets queued between two database transactions
+const MAX_BATCH: usize = 256;
+
+// Packets waiting for the database, about two seconds of a full grid,
+// past which sampled packets are dropped rather than held in memory
+const QUEUE_CAPACITY: usize = 4096;
+
+/// Queues packets for the background task that writes them to the database
+#[derive(Clone)]
+pub struct StorageWriter {
+    // Unbounded so that packets the stored state depends on are never lost,
+    // and in arrival order so that a flashback discards what came before it
+    sender: mpsc::UnboundedSender<TelemetryPacket>,
+    // Packets sent that the writer hasn't taken yet
+    queued: Arc<AtomicUsize>,
+    // Packets dropped because the queue was full, since the writer last
+    // reported them
+    dropped: Arc<AtomicU64>,
+}
+
+impl StorageWriter {
+    /// Start the writer on a task of `tasks`. It runs until every clone of
+    /// the writer is dropped and all the packets queued have been written.
+    pub fn spawn(conn: Connection, car_selection: CarSelection, tasks: &TaskTracker) -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<TelemetryPacket>();
+        let queued = Arc::new(AtomicUsize::new(0));
+        let dropped = Arc::new(AtomicU64::new(0));
+
+        let writer_queued = queued.clone();
+        let writer_dropped = dropped.clone();
+        tasks.spawn(async move {
+            let mut recorder = Recorder::new(conn, car_selection);
+            let mut batch = Vec::with_capacity(MAX_BATCH);
+
+            while let Some(packet) = receiver.recv().await {
+                batch.push(packet);
+                while batch.len() < MAX_BATCH {
+                    match receiver.try_recv() {
+                        Ok(packet) => batch.push(packet),
+                        Err(_) => break,
+                    }
+                }
+
+                writer_queued.fetch_sub(batch.len(), Ordering::Relaxed);
+
+                if let Err(e) = recorder.write(batch.drain(..)).await {
+                    error!("Error writing telemetry to storage: {}", e);
+                }
+
+                let dropped = writer_dropped.swap(0, Ordering::Relaxed);
+                if dropped > 0 {
+                    warn!(
+                        "Storage is falling behind, dropped {} sampled packets",
+                        dropped
+                    );
+                }
+            }
+            debug!("Storage writer flushed");
+        });
+
+        Self {
+            sender,
+            queued,
+            dropped,
+        }
+    }
+
+    /// Queue a packet for storage. Packets that aren't persisted are ignored.
+    /// While the queue is full, motion, telemetry and status packets are
+    /// dropped, since they are sampled anyway; sessions, lap data and events
+    /// are always queued, as laps and flashbacks can't be rebuilt without
+    /// them.
+    pub fn record(&self, packet: &TelemetryPacket) {
+        let sampled = match packet {
+            TelemetryPacket::Motion(_)
+            | TelemetryPacket::CarTelemetry(_)
+            | TelemetryPacket::CarStatus(_) => true,
+            TelemetryPacket::Session(_)
+            | TelemetryPacket::LapData(_)
+            | TelemetryPacket::Event(_) => false,
+            _ => return,
+        };
+
+        if sampled && self.queued.load(Ordering::Relaxed) >= QUEUE_CAPACITY {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+
+        self.queued.fetch_add(1, Ordering::Relaxed);
+        if self.sender.send(packet.clone()).is_err() {
+            self.queued.fetch_sub(1, Ordering::Relaxed);
+            error!("Storage writer has stopped, dropping packet");
+        }
+    }
+}
+
+struct Recorder {
+    conn: Connection,
+    car_selection: CarSelection,
+    session_uid: Option<u64>,
+    laps: LapAssembler,
+    // Most recent lap data and motion packets, used to attach lap and
+    // position to telemetry samples
+    lap_data: Option<PacketLapData>,
+    motion: Option<PacketMotionData>,
+    last_sample_time: [f32; 22],
+}
+
+impl Recorder {
+    fn new(conn: Connection, car_selection: CarSelection) -> Self {
+        Self {
+            conn,
+            car_selection,
+            session_uid: None,
+            laps: LapAssembler::new(),
+            lap_data: None,
+            motion: None,
+            last_sample_time: [f32::MIN; 22],
+        }
+    }
+
+    async fn write(
+        &mut self,
+        packets: impl Iterator<Item = TelemetryPacket>,
+    ) -> Result<(), libsql::Error> {
+        let tx = self.conn.transaction().await?;
+
+        for packet in packets {
+            match packet {
+                TelemetryPacket::Session((header, data)) => {
+                    self.start_session(&tx, &header).await?;
+                    write_session(&tx, &header, &data).await?;
+                }
+                TelemetryPacket::LapData((header, data)) => {
+                    self.start_session(&tx, &header).await?;
+                    for lap in self.laps.on_lap_data(&header, &data) {
+                        write_lap(&tx, &header, &lap).await?;
+                    }
+                    self.lap_data = Some(data);
+                }
+                TelemetryPacket::Motion((header, data)) => {
+                    self.start_session(&tx, &header).await?;
+                    self.motion = Some(data);
+                }
+                TelemetryPacket::CarTelemetry((header, data)) => {
+                    self.start_session(&tx, &header).await?;
+                    self.write_samples(&tx, &header, &data).await?;
+                }
+                TelemetryPacket::CarStatus((header, data)) => {
+                    self.start_session(&tx, &header).await?;
+                    self.laps.on_car_status(&header, &data);
+                }
+                TelemetryPacket::Event((header, data)) => {
+                    if let EventDataDetails::Flashback(f) = data.event_details {
+                        self.start_session(&tx, &header).await?;
+                        self.laps
+                            .on_flashback(&header, f.flashback_frame_identifier);
+                        discard_after(&tx, &header, f.flashback_frame_identifier).await?;
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        tx.commit().await
+    }
+
+    // Make sure a row exists for the packet's session, and forget any
+    // per-session state when the game moves on to a new one
+    async fn start_session(
+        &mut self,
+        conn: &Connection,
+        header: &PacketHeader,
+    ) -> Result<(), libsql::Error> {
+        if self.session_uid == Some(header.session_uid) {
+            return Ok(());
+        }
+
+        let now = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT OR IGNORE INTO sessions (session_uid, first_seen_at, last_seen_at)
+             VALUES (?1, ?2, ?2)",
+            params![header.session_uid as i64, now],
+        )
+        .await?;
+
+        self.session_uid = Some(header.session_uid);
+        self.lap_data = None;
+        self.motion = None;
+        self.last_sample_time = [f32::MIN; 22];
+
+        Ok(())
+    }
+
+    async fn write_samples(
+        &mut self,
+        conn: &Connection,
+        header: &PacketHeader,
+        data: &PacketCarTelemetryData,
+    ) -> Result<(), libsql::Error> {
+        for car_index in self.car_selection.car_indices(header) {
+            let last = self.last_sample_time[car_index];
+            // A session time earlier than the last sample means the game rewound
+            if header.session_time - last < SAMPLE_INTERVAL && header.session_time >= last {
+                continue;
+            }
+            self.last_sample_time[car_index] = header.session_time;
+
+            let t = &data.car_telemetry_data[car_index];
+            let lap = self.lap_data.as_ref().map(|l| &l.lap_data[car_index]);
+            let motion = self.motion.as_ref().map(|m| &m.car_motion_data[car_index]);
+
+            conn.execute(
+                "INSERT INTO telemetry_samples (
+                    session_uid, car_index, session_time, frame_identifier, lap_num,
+                    lap_distance, speed, throttle, brake, steer, gear, engine_rpm, drs,
+                    world_position_x, world_position_y, world_position_z
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+                params![
+                    header.session_uid as i64,
+                    car_index as i64,
+                    header.session_time,
+                    header.frame_identifier,
+                    lap.map(|l| l.current_lap_num),
+                    lap.map(|l| l.lap_distance),
+                    t.speed,
+                    t.throttle,
+                    t.brake,
+                    t.steer,
+                    t.gear,
+                    t.engine_rpm,
+                    t.drs,
+                    motion.map(|m| m.world_position_x),
+                    motion.map(|m| m.world_position_y),
+                    motion.map(|m| m.world_position_z),
+                ],
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
+
+async fn write_session(
+    conn: &Connection,
+    header: &PacketHeader,
+    data: &PacketSessionData,
+) -> Result<(), libsql::Error> {
+    conn.execute(
+        "UPDATE sessions SET track_id = ?2, session_type = ?3, formula = ?4, weather = ?5,
+            total_laps = ?6, track_length = ?7, last_seen_at = ?8
+         WHERE session_uid = ?1",
+        params![
+            header.session_uid as i64,
+            i8::from(data.track_id),
+            u8::from(data.session_type),
+            u8::from(data.formula),
+            u8::from(data.weather),
+            data.total_laps,
+            data.track_length,
+            Utc::now().to_rfc3339(),
+        ],
+    )
+    .await?;
+
+    Ok(())
+}
+
+// Delete what was stored for frames a flashback rewound past
+async fn discard_after(
+    conn: &Connection,
+    header: &PacketHeader,
+    frame_identifier: u32,
+) -> Result<(), libsql::Error> {
+    for table in ["telemetry_samples", "laps"] {
+        conn.execute(
+            &format!(
+                "DELETE FROM {} WHERE session_uid = ?1 AND frame_identifier > ?2",
+                table
+            ),
+            params![header.session_uid as i64, frame_identifier],
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+async fn write_lap(
+    conn: &Connection,
+    header: &PacketHeader,
+    lap: &CompletedLap,
+) -> Result<(), libsql::Error> {
+    conn.execute(
+        "INSERT OR REPLACE INTO laps (
+            session_uid, car_index, lap_num, lap_time_ms, sector1_time_ms,
+            sector2_time_ms, sector3_time_ms, invalid, tyre_actual_compound,
+            tyre_visual_compound, fuel_used, session_time, frame_identifier
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+        params![
+            header.session_uid as i64,
+            lap.car_index,
+            lap.lap_num,
+            lap.lap_time_in_ms,
+            lap.sector1_time_in_ms,
+            lap.sector2_time_in_ms,
+            lap.sector3_time_in_ms,
+            !lap.valid,
+            lap.tyre_actual_compound.map(u8::from),
+            lap.tyre_visual_compound.map(u8::from),
+            lap.fuel_used,
+            lap.session_time,
+            lap.frame_identifier,
+        ],
+    )
+    .await?;
+
+    Ok(())
+}
@@ -1,36 +1,147 @@
 mod f1_telemetry_api;
 mod f1_telemetry_client;
+mod f1_telemetry_simulator;
+mod f1_telemetry_storage;
 
-use clap::Parser;
-use f1_telemetry_api::F1TelemetryApi;
+use clap::{Parser, Subcommand};
+use f1_telemetry_api::buffer::Retention;
+use f1_telemetry_api::events::CarSelection;
+use f1_telemetry_api::{ApiConfig, F1TelemetryApi};
+use f1_telemetry_client::{ReplaySpeed, TelemetrySource};
+use f1_telemetry_simulator::{Simulator, SimulatorConfig, MAX_CARS};
+use f1_telemetry_storage::Storage;
 use std::error::Error;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tracing::Level;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, Level};
 
 /// F1 24 Telemetry Client
+///
+/// Every option can also be set with the environment variable named in its
+/// help, or in a dotenv-style config file (`.env` by default)
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    /// Config file of KEY=value lines, applied beneath the environment
+    #[arg(long, env = "F1_TELEMETRY_CONFIG")]
+    config: Option<PathBuf>,
+
     /// UDP port to listen on
-    #[arg(long, default_value_t = 20777)]
+    #[arg(long, env = "F1_TELEMETRY_UDP_PORT", default_value_t = 20777)]
     udp_port: u16,
 
-    /// UDP port to listen on
-    #[arg(long, default_value_t = 4000)]
+    /// HTTP port to serve the API on
+    #[arg(long, env = "F1_TELEMETRY_API_PORT", default_value_t = 4000)]
     api_port: u16,
 
-    /// IP address to bind to
-    #[arg(long, default_value = "0.0.0.0")]
+    /// IP address to bind the UDP listener and the API to
+    #[arg(long, env = "F1_TELEMETRY_HOST", default_value = "0.0.0.0")]
     host: String,
 
+    /// Events buffered for each real-time subscriber before it starts missing some
+    #[arg(long, env = "F1_TELEMETRY_EVENT_CAPACITY", default_value_t = 5000)]
+    event_capacity: usize,
+
+    /// Telemetry events kept in memory for the history endpoints
+    #[arg(
+        long,
+        env = "F1_TELEMETRY_BUFFER_MAX_EVENTS",
+        default_value_t = 500_000
+    )]
+    buffer_max_events: usize,
+
+    /// Seconds after which telemetry is dropped from memory, unlimited if unset
+    #[arg(long, env = "F1_TELEMETRY_BUFFER_MAX_AGE")]
+    buffer_max_age: Option<u64>,
+
+    /// Emit events for every car on the grid instead of only the player's car
+    #[arg(long, env = "F1_TELEMETRY_ALL_CARS")]
+    all_cars: bool,
+
+    /// Path of the local libSQL database telemetry is stored in
+    #[arg(long, env = "F1_TELEMETRY_DB_PATH", default_value = "telemetry.db")]
+    db_path: String,
+
+    /// Keep data in memory only instead of writing it to --db-path
+    #[arg(long, env = "F1_TELEMETRY_NO_STORAGE")]
+    no_storage: bool,
+
+    /// Record every UDP datagram received to this capture file
+    #[arg(long, env = "F1_TELEMETRY_CAPTURE")]
+    capture: Option<PathBuf>,
+
     /// Enable debug logging
-    #[arg(short, long)]
+    #[arg(short, long, env = "F1_TELEMETRY_DEBUG")]
     debug: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Serve a recorded capture file instead of listening for the game
+    Replay {
+        /// Capture file written with --capture
+        file: PathBuf,
+
+        /// Playback speed: 1 for real time, 2 for double speed, or "max"
+        #[arg(long, default_value = "1")]
+        speed: ReplaySpeed,
+    },
+    /// Send packets of a simulated race over UDP instead of running the API,
+    /// for development without the game
+    Simulate {
+        /// Number of cars in the race
+        #[arg(long, default_value_t = 20, value_parser = parse_cars)]
+        cars: usize,
+
+        /// Race distance in laps
+        #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u8).range(1..=100))]
+        laps: u8,
+
+        /// Frames sent a second, like the game's UDP send rate setting
+        #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u32).range(10..=120))]
+        rate: u32,
+
+        /// Address to send packets to, 127.0.0.1 on --udp-port by default
+        #[arg(long)]
+        target: Option<String>,
+
+        /// JSON array of [x, y, z] world positions to drive around instead
+        /// of the built-in recorded line
+        #[arg(long)]
+        track: Option<PathBuf>,
+    },
+}
+
+fn parse_cars(s: &str) -> Result<usize, String> {
+    match s.parse::<usize>() {
+        Ok(cars) if (1..=MAX_CARS).contains(&cars) => Ok(cars),
+        _ => Err(format!("expected a number of cars from 1 to {}", MAX_CARS)),
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    let args = Args::parse();
+    let mut args = Args::parse();
+
+    // Values from the config file never override variables already set in the
+    // environment, and clap lets flags on the command line override both
+    match &args.config {
+        Some(path) => {
+            dotenvy::from_path(path)
+                .map_err(|e| format!("Error reading config {}: {}", path.display(), e))?;
+            args = Args::parse();
+        }
+        None => {
+            if dotenvy::dotenv().is_ok() {
+                args = Args::parse();
+            }
+        }
+    }
 
     tracing_subscriber::fmt()
         .with_max_level(if args.debug {
@@ -42,10 +153,66 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     let http_addr = format!("{}:{}", args.host, args.api_port);
 
-    let api = F1TelemetryApi::new();
+    let config = ApiConfig {
+        car_selection: if args.all_cars {
+            CarSelection::All
+        } else {
+            CarSelection::Player
+        },
+        event_capacity: args.event_capacity,
+        retention: Retention {
+            max_events: args.buffer_max_events,
+            max_age: args.buffer_max_age.map(Duration::from_secs),
+        },
+    };
+
+    // Cancelled on Ctrl-C, stopping a simulation, or the listener and the
+    // server once the data received so far is stored
+    let shutdown = CancellationToken::new();
+    let ctrlc_shutdown = shutdown.clone();
+    tokio::spawn(async move {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!("Error waiting for Ctrl-C: {}", e);
+            return;
+        }
+        info!("Shutting down...");
+        ctrlc_shutdown.cancel();
+    });
+
+    let source = match args.command {
+        Some(Command::Replay { file, speed }) => TelemetrySource::Replay { path: file, speed },
+        Some(Command::Simulate {
+            cars,
+            laps,
+            rate,
+            target,
+            track,
+        }) => {
+            let target = target.unwrap_or_else(|| format!("127.0.0.1:{}", args.udp_port));
+            let simulator = Simulator::new(SimulatorConfig {
+                cars,
+                laps,
+                rate,
+                track,
+            })?;
+            return simulator.run(&target, shutdown).await;
+        }
+        None => TelemetrySource::Udp {
+            addr: format!("{}:{}", args.host, args.udp_port),
+            capture: args.capture,
+        },
+    };
+
+    let storage = if args.no_storage {
+        Storage::open(":memory:").await?
+    } else {
+        Storage::open(&args.db_path).await?
+    };
+
+    let api = F1TelemetryApi::new(source, config, storage);
     let api_handle = Arc::new(api);
 
-    api_handle.start(&http_addr).await?;
+    api_handle.start(&http_addr, shutdown).await?;
 
     Ok(())
 }